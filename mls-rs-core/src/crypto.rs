@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
 use crate::error::IntoAnyError;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::{
@@ -105,7 +106,7 @@ pub struct HpkeSecretKey(
 
 impl Debug for HpkeSecretKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        crate::debug::pretty_bytes(&self.0)
+        crate::debug::pretty_secret(&self.0)
             .named("HpkeSecretKey")
             .fmt(f)
     }
@@ -209,6 +210,30 @@ impl SignaturePublicKey {
     }
 }
 
+impl SignaturePublicKey {
+    /// Number of hash bytes kept in a [fingerprint](SignaturePublicKey::fingerprint).
+    pub const FINGERPRINT_LEN: usize = 16;
+
+    /// Short, stable, hex encoded fingerprint of this key suitable for display
+    /// and pinning.
+    ///
+    /// The fingerprint is the hash of the key bytes, as defined by
+    /// `cipher_suite_provider`, truncated to
+    /// [`FINGERPRINT_LEN`](SignaturePublicKey::FINGERPRINT_LEN) bytes.
+    /// Keys are hashed in the same uncompressed representation that is
+    /// used on the wire.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn fingerprint<P: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &P,
+    ) -> Result<String, P::Error> {
+        let hash = cipher_suite_provider.hash(&self.0).await?;
+        let len = hash.len().min(Self::FINGERPRINT_LEN);
+
+        Ok(hex::encode(&hash[..len]))
+    }
+}
+
 impl Deref for SignaturePublicKey {
     type Target = [u8];
 
@@ -244,7 +269,7 @@ pub struct SignatureSecretKey {
 
 impl Debug for SignatureSecretKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        crate::debug::pretty_bytes(&self.bytes)
+        crate::debug::pretty_secret(&self.bytes)
             .named("SignatureSecretKey")
             .fmt(f)
     }
@@ -315,6 +340,17 @@ pub trait CipherSuiteProvider: Send + Sync {
     /// Return the implemented MLS [CipherSuite](CipherSuite).
     fn cipher_suite(&self) -> CipherSuite;
 
+    /// Prefix of every label used by MLS for key derivation, signing, HPKE encryption,
+    /// exporting the external init secret and computing hash references.
+    ///
+    /// The default is `"MLS 1.0 "`, as required by RFC 9420. Private deployments may
+    /// return a different prefix, at the cost of interoperability with clients that
+    /// use any other prefix.
+    #[cfg(feature = "custom_label_prefix")]
+    fn label_prefix(&self) -> &str {
+        "MLS 1.0 "
+    }
+
     /// Compute the hash of `data`.
     async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error>;
 
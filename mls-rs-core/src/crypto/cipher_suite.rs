@@ -77,6 +77,14 @@ impl CipherSuite {
         self.0
     }
 
+    /// Default MLS ciphersuite with the raw value `value`, or `None` if `value` does not
+    /// identify one of the suites returned by [`CipherSuite::all`].
+    ///
+    /// Use [`CipherSuite::new`] to refer to a custom ciphersuite.
+    pub fn from_raw(value: u16) -> Option<CipherSuite> {
+        Self::all().find(|cs| cs.0 == value)
+    }
+
     /// An iterator over all of the default MLS ciphersuites.
     pub fn all() -> impl Iterator<Item = CipherSuite> {
         (1..=7).map(CipherSuite)
@@ -96,3 +104,40 @@ pub enum HpkeModeId {
     /// Authenticated variant that authenticates possession of a PSK as well as a KEM private key.
     AuthPsk = 0x03,
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::CipherSuite;
+
+    #[test]
+    fn default_suites_round_trip_through_raw_values() {
+        let all = CipherSuite::all().collect::<Vec<_>>();
+
+        assert_eq!(
+            all,
+            [
+                CipherSuite::CURVE25519_AES128,
+                CipherSuite::P256_AES128,
+                CipherSuite::CURVE25519_CHACHA,
+                CipherSuite::CURVE448_AES256,
+                CipherSuite::P521_AES256,
+                CipherSuite::CURVE448_CHACHA,
+                CipherSuite::P384_AES256,
+            ]
+        );
+
+        for cs in all {
+            assert_eq!(CipherSuite::from_raw(cs.raw_value()), Some(cs));
+            assert_eq!(CipherSuite::from(u16::from(cs)), cs);
+        }
+    }
+
+    #[test]
+    fn unknown_raw_value_is_not_a_default_suite() {
+        assert_eq!(CipherSuite::from_raw(0), None);
+        assert_eq!(CipherSuite::from_raw(8), None);
+        assert_eq!(CipherSuite::from_raw(0xF000), None);
+    }
+}
@@ -12,6 +12,19 @@ pub fn pretty_bytes(bytes: &[u8]) -> PrettyBytes<'_> {
         bytes,
         show_len: true,
         show_raw: false,
+        redact: false,
+    }
+}
+
+/// Like [`pretty_bytes`], but the bytes are never shown, not even with the
+/// alternate `{:#?}` format. Only the length is.
+///
+/// This is meant for key material and other secrets, so that logging a value
+/// holding one does not expose it.
+pub fn pretty_secret(bytes: &[u8]) -> PrettyBytes<'_> {
+    PrettyBytes {
+        redact: true,
+        ..pretty_bytes(bytes)
     }
 }
 
@@ -20,6 +33,7 @@ pub struct PrettyBytes<'a> {
     bytes: &'a [u8],
     show_len: bool,
     show_raw: bool,
+    redact: bool,
 }
 
 impl<'a> PrettyBytes<'a> {
@@ -47,8 +61,9 @@ impl<'a> PrettyBytes<'a> {
 
 impl Debug for PrettyBytes<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let show_raw = self.show_raw || f.alternate();
-        match (self.ty, self.show_len, show_raw) {
+        let show_raw = !self.redact && (self.show_raw || f.alternate());
+        let show_len = self.show_len || self.redact;
+        match (self.ty, show_len, show_raw) {
             (_, false, false) => show_only_type(self.ty, f),
             (None, false, true) => show_only_raw(self.bytes, f),
             (Some(ty), false, true) => show_newtype(ty, self.bytes, f),
@@ -108,7 +123,12 @@ pub fn pretty_group_id(id: &[u8]) -> impl Debug + '_ {
 
 #[cfg(test)]
 mod tests {
-    use crate::debug::pretty_bytes;
+    use crate::{
+        crypto::{HpkeSecretKey, SignatureSecretKey},
+        debug::{pretty_bytes, pretty_secret},
+        psk::PreSharedKey,
+        secret::Secret,
+    };
 
     #[test]
     fn default_format_contains_only_length() {
@@ -127,4 +147,29 @@ mod tests {
         assert!(output.contains("raw"));
         assert!(output.contains(&hex::encode(b"foobar")));
     }
+
+    #[test]
+    fn secret_format_never_contains_bytes() {
+        let secret = pretty_secret(b"foobar").named("Secret").show_raw(true);
+        let output = format!("{secret:?} {secret:#?}");
+        assert!(output.contains("len"));
+        assert!(!output.contains(&hex::encode(b"foobar")));
+    }
+
+    #[test]
+    fn secret_types_do_not_expose_bytes() {
+        let bytes = b"secret key bytes".to_vec();
+
+        let outputs = [
+            format!("{:#?}", SignatureSecretKey::new(bytes.clone())),
+            format!("{:#?}", HpkeSecretKey::from(bytes.clone())),
+            format!("{:#?}", Secret::from(bytes.clone())),
+            format!("{:#?}", PreSharedKey::new(bytes.clone())),
+        ];
+
+        for output in outputs {
+            assert!(output.contains("len"));
+            assert!(!output.contains(&hex::encode(&bytes)));
+        }
+    }
 }
@@ -20,6 +20,14 @@ impl Display for AnyError {
     }
 }
 
+#[cfg(feature = "std")]
+impl AnyError {
+    /// Reference to the wrapped provider error, if it is of type `E`.
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.0.downcast_ref()
+    }
+}
+
 #[cfg(feature = "std")]
 impl std::error::Error for AnyError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
@@ -21,7 +21,7 @@ impl Debug for GroupState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("GroupState")
             .field("id", &crate::debug::pretty_bytes(&self.id))
-            .field("data", &crate::debug::pretty_bytes(&self.data))
+            .field("data", &crate::debug::pretty_secret(&self.data))
             .finish()
     }
 }
@@ -38,7 +38,7 @@ impl Debug for EpochRecord {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("EpochRecord")
             .field("id", &self.id)
-            .field("data", &crate::debug::pretty_bytes(&self.data))
+            .field("data", &crate::debug::pretty_secret(&self.data))
             .finish()
     }
 }
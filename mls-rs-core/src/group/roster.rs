@@ -90,11 +90,16 @@ impl Default for Capabilities {
 }
 
 /// A member of a MLS group.
+///
+/// With the `serde` feature, a member can be serialized, for instance to export
+/// a roster as JSON. It only holds public information from the member's leaf
+/// node.
 #[cfg_attr(
     all(feature = "ffi", not(test)),
     safer_ffi_gen::ffi_type(clone, opaque)
 )]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct Member {
     /// The index of this member within a group.
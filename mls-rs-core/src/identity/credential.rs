@@ -240,3 +240,46 @@ pub trait MlsCredential: Sized {
     /// Function to convert this type into a [`Credential`] enum.
     fn into_credential(self) -> Result<Credential, Self::Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use mls_rs_codec::{MlsDecode, MlsEncode};
+
+    use super::{BasicCredential, Credential, CredentialType, CustomCredential};
+
+    #[cfg(feature = "x509")]
+    use super::CertificateChain;
+
+    #[test]
+    fn credential_type_of_each_kind() {
+        let basic = Credential::Basic(BasicCredential::new(b"alice".to_vec()));
+        assert_eq!(basic.credential_type(), CredentialType::BASIC);
+        assert_eq!(basic.credential_type().raw_value(), 1);
+
+        #[cfg(feature = "x509")]
+        {
+            let x509 = Credential::X509(CertificateChain::from(vec![b"cert".to_vec()]));
+            assert_eq!(x509.credential_type(), CredentialType::X509);
+            assert_eq!(x509.credential_type().raw_value(), 2);
+        }
+
+        let custom_type = CredentialType::new(0xf000);
+        let custom = Credential::Custom(CustomCredential::new(custom_type, vec![1, 2, 3]));
+        assert_eq!(custom.credential_type(), custom_type);
+        assert_eq!(*custom.credential_type(), 0xf000);
+    }
+
+    #[test]
+    fn credential_type_survives_encoding() {
+        let custom_type = CredentialType::new(0xf000);
+        let custom = Credential::Custom(CustomCredential::new(custom_type, vec![1, 2, 3]));
+
+        let encoded = custom.mls_encode_to_vec().unwrap();
+        assert_eq!(&encoded[..2], &[0xf0, 0x00]);
+
+        let decoded = Credential::mls_decode(&mut &*encoded).unwrap();
+        assert_eq!(decoded.credential_type(), custom_type);
+        assert_eq!(decoded, custom);
+    }
+}
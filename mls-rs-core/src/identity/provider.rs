@@ -65,6 +65,19 @@ pub trait IdentityProvider: Send + Sync {
         extensions: &ExtensionList,
     ) -> Result<bool, Self::Error>;
 
+    /// Time after which `signing_identity` is no longer valid.
+    ///
+    /// Returns `None` if `signing_identity` does not expire, which is the
+    /// default. Providers of credentials with a validity period, such as
+    /// X.509 certificates, can implement this to let expired members be
+    /// found with `Group::propose_remove_expired`.
+    async fn expiration(
+        &self,
+        _signing_identity: &SigningIdentity,
+    ) -> Result<Option<MlsTime>, Self::Error> {
+        Ok(None)
+    }
+
     /// Credential types that are supported by this provider.
     fn supported_types(&self) -> Vec<CredentialType>;
 }
@@ -2,11 +2,36 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+use alloc::vec::Vec;
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 
-use crate::crypto::SignaturePublicKey;
+use crate::{
+    crypto::{CipherSuite, SignaturePublicKey},
+    error::IntoAnyError,
+};
 
-use super::Credential;
+use super::{BasicCredential, Credential};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+/// Error returned when raw signature key bytes do not match the signature
+/// scheme of a cipher suite.
+pub enum SignatureKeyError {
+    #[cfg_attr(
+        feature = "std",
+        error("signature key length {0} is invalid for cipher suite {1:?}")
+    )]
+    InvalidLength(usize, CipherSuite),
+    #[cfg_attr(feature = "std", error("EC signature key is not uncompressed"))]
+    NotUncompressed,
+}
+
+impl IntoAnyError for SignatureKeyError {
+    #[cfg(feature = "std")]
+    fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+        Ok(self.into())
+    }
+}
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq, PartialOrd, Ord, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -30,4 +55,109 @@ impl SigningIdentity {
             signature_key,
         }
     }
+
+    /// Create a new signing identity holding a [`BasicCredential`] from raw
+    /// parts received over the wire.
+    ///
+    /// For the default MLS cipher suites, `signature_key` is checked to have
+    /// the length and encoding expected by the signature scheme of
+    /// `cipher_suite`. EC keys must be uncompressed. Keys for other cipher
+    /// suites are accepted as is, since their format is defined by the
+    /// [`CryptoProvider`](crate::crypto::CryptoProvider) in use.
+    ///
+    /// This does not check that the key is a valid point on the curve.
+    pub fn from_basic_parts(
+        identifier: Vec<u8>,
+        cipher_suite: CipherSuite,
+        signature_key: Vec<u8>,
+    ) -> Result<SigningIdentity, SignatureKeyError> {
+        let (expected_len, uncompressed) = match cipher_suite {
+            CipherSuite::CURVE25519_AES128 | CipherSuite::CURVE25519_CHACHA => (Some(32), false),
+            CipherSuite::CURVE448_AES256 | CipherSuite::CURVE448_CHACHA => (Some(57), false),
+            CipherSuite::P256_AES128 => (Some(65), true),
+            CipherSuite::P384_AES256 => (Some(97), true),
+            CipherSuite::P521_AES256 => (Some(133), true),
+            _ => (None, false),
+        };
+
+        if let Some(expected_len) = expected_len {
+            if signature_key.len() != expected_len {
+                return Err(SignatureKeyError::InvalidLength(
+                    signature_key.len(),
+                    cipher_suite,
+                ));
+            }
+        }
+
+        if uncompressed && signature_key.first() != Some(&0x04) {
+            return Err(SignatureKeyError::NotUncompressed);
+        }
+
+        Ok(SigningIdentity::new(
+            BasicCredential::new(identifier).into_credential(),
+            signature_key.into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use assert_matches::assert_matches;
+
+    use crate::crypto::CipherSuite;
+
+    use super::{SignatureKeyError, SigningIdentity};
+
+    #[test]
+    fn from_basic_parts_accepts_well_formed_keys() {
+        let cases = [
+            (CipherSuite::CURVE25519_AES128, 32),
+            (CipherSuite::CURVE25519_CHACHA, 32),
+            (CipherSuite::CURVE448_AES256, 57),
+            (CipherSuite::CURVE448_CHACHA, 57),
+            (CipherSuite::P256_AES128, 65),
+            (CipherSuite::P384_AES256, 97),
+            (CipherSuite::P521_AES256, 133),
+        ];
+
+        for (cipher_suite, len) in cases {
+            let mut key = vec![0x01; len];
+            key[0] = 0x04;
+
+            let identity =
+                SigningIdentity::from_basic_parts(b"alice".to_vec(), cipher_suite, key.clone())
+                    .unwrap();
+
+            assert_eq!(identity.signature_key.as_bytes(), key);
+
+            assert_eq!(
+                identity.credential.as_basic().unwrap().identifier,
+                b"alice".to_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn from_basic_parts_rejects_wrong_length() {
+        for cipher_suite in CipherSuite::all() {
+            let res = SigningIdentity::from_basic_parts(vec![], cipher_suite, vec![0x04; 16]);
+            assert_matches!(res, Err(SignatureKeyError::InvalidLength(16, cs)) if cs == cipher_suite);
+        }
+    }
+
+    #[test]
+    fn from_basic_parts_rejects_compressed_ec_key() {
+        let mut key = vec![0x01; 65];
+        key[0] = 0x02;
+
+        let res = SigningIdentity::from_basic_parts(vec![], CipherSuite::P256_AES128, key);
+        assert_matches!(res, Err(SignatureKeyError::NotUncompressed));
+    }
+
+    #[test]
+    fn from_basic_parts_accepts_custom_cipher_suite() {
+        let res = SigningIdentity::from_basic_parts(vec![], CipherSuite::new(0xf000), vec![1]);
+        assert!(res.is_ok());
+    }
 }
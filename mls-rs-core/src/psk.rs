@@ -11,9 +11,9 @@ use core::{
     ops::Deref,
 };
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
-use zeroize::Zeroizing;
+use zeroize::{ZeroizeOnDrop, Zeroizing};
 
-#[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+#[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode, ZeroizeOnDrop)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Wrapper type that holds a pre-shared key value and zeroizes on drop.
 pub struct PreSharedKey(
@@ -24,7 +24,7 @@ pub struct PreSharedKey(
 
 impl Debug for PreSharedKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        crate::debug::pretty_bytes(&self.0)
+        crate::debug::pretty_secret(&self.0)
             .named("PreSharedKey")
             .fmt(f)
     }
@@ -96,6 +96,10 @@ impl X509CredentialValidator for CertificateValidator {
     ) -> Result<SignaturePublicKey, Self::Error> {
         self.validate(chain, timestamp)
     }
+
+    fn not_after(&self, certificate: &DerCertificate) -> Result<MlsTime, Self::Error> {
+        Certificate::try_from(certificate)?.not_after()
+    }
 }
 
 fn certs_to_stack(certs: &[DerCertificate]) -> Result<Stack<Certificate>, AwsLcCryptoError> {
@@ -206,4 +210,15 @@ mod tests {
 
         assert_matches!(res, Err(AwsLcCryptoError::CertValidationFailure(_)));
     }
+
+    #[test]
+    fn not_after_is_read_from_certificate() {
+        let chain = load_test_cert_chain();
+        let validator = CertificateValidator::new_der(&[load_test_ca()]).unwrap();
+
+        let not_after = validator.not_after(&chain[0]).unwrap();
+
+        // 2026-01-09 14:14:00 UTC
+        assert_eq!(not_after, MlsTime::from(1767968040));
+    }
 }
@@ -26,7 +26,7 @@ impl<KDF: KdfType + Debug, AEAD: AeadType + Debug> Debug for Context<KDF, AEAD>
         f.debug_struct("Context")
             .field(
                 "exporter_secret",
-                &mls_rs_core::debug::pretty_bytes(&self.exporter_secret),
+                &mls_rs_core::debug::pretty_secret(&self.exporter_secret),
             )
             .field("encryption_context", &self.encryption_context)
             .field("kdf", &self.kdf)
@@ -149,13 +149,13 @@ impl<AEAD: AeadType + Debug> Debug for EncryptionContext<AEAD> {
         f.debug_struct("EncryptionContext")
             .field(
                 "base_nonce",
-                &mls_rs_core::debug::pretty_bytes(&self.base_nonce),
+                &mls_rs_core::debug::pretty_secret(&self.base_nonce),
             )
             .field("seq_number", &self.seq_number)
             .field("aead", &self.aead)
             .field(
                 "aead_key",
-                &mls_rs_core::debug::pretty_bytes(&self.aead_key),
+                &mls_rs_core::debug::pretty_secret(&self.aead_key),
             )
             .finish()
     }
@@ -30,7 +30,7 @@ pub enum EcError {
 }
 
 #[inline(always)]
-fn nist_curve_id(curve: Curve) -> Option<Nid> {
+pub fn nist_curve_id(curve: Curve) -> Option<Nid> {
     match curve {
         Curve::P256 => Some(Nid::X9_62_PRIME256V1),
         Curve::P384 => Some(Nid::SECP384R1),
@@ -57,7 +57,7 @@ impl Debug for KeyPair {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("KeyPair")
             .field("public", &mls_rs_core::debug::pretty_bytes(&self.public))
-            .field("secret", &mls_rs_core::debug::pretty_bytes(&self.secret))
+            .field("secret", &mls_rs_core::debug::pretty_secret(&self.secret))
             .finish()
     }
 }
@@ -6,7 +6,12 @@ use std::ops::Deref;
 
 use mls_rs_core::crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey};
 use mls_rs_crypto_traits::Curve;
-use openssl::hash::MessageDigest;
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    ec::EcGroup,
+    ecdsa::EcdsaSig,
+    hash::MessageDigest,
+};
 
 #[cfg(feature = "x509")]
 use openssl::pkey::{PKey, Private, Public};
@@ -14,9 +19,10 @@ use openssl::pkey::{PKey, Private, Public};
 use thiserror::Error;
 
 use crate::ec::{
-    curve_from_private_key, curve_from_public_key, generate_keypair, private_key_bytes_to_public,
-    private_key_from_bytes, private_key_from_der, private_key_to_bytes, pub_key_from_uncompressed,
-    pub_key_to_uncompressed, public_key_from_der, EcError,
+    curve_from_private_key, curve_from_public_key, generate_keypair, nist_curve_id,
+    private_key_bytes_to_public, private_key_from_bytes, private_key_from_der,
+    private_key_to_bytes, pub_key_from_uncompressed, pub_key_to_uncompressed, public_key_from_der,
+    EcError,
 };
 
 #[derive(Debug, Error)]
@@ -29,26 +35,126 @@ pub enum EcSignerError {
     InvalidSignature,
 }
 
+/// Handling of the `s` component of ECDSA signatures over P-256, P-384 and P-521.
+///
+/// Both `(r, s)` and `(r, n - s)` are valid signatures of the same message. Signatures
+/// are always produced with `s <= n / 2` (low-S form) so that they are accepted by peers
+/// enforcing that form. The mode only controls which signatures are accepted.
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum EcdsaSignatureMode {
+    /// Accept signatures in both low-S and high-S form.
+    #[default]
+    Permissive,
+    /// Reject signatures that are not in low-S form.
+    StrictLowS,
+}
+
+/// Encoding of ECDSA signatures over P-256, P-384 and P-521.
+///
+/// MLS uses the TLS 1.3 signature schemes, which encode ECDSA signatures as a DER
+/// `ECDSA-Sig-Value`. This is what the provider produces and expects by default. Some
+/// stacks (WebCrypto, JOSE, PKCS#11) instead use the fixed-length `r || s` form.
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum EcdsaSignatureEncoding {
+    /// DER-encoded `ECDSA-Sig-Value`, as required by RFC 9420.
+    #[default]
+    Der,
+    /// Big-endian `r || s`, each padded to the size of the curve's field.
+    Raw,
+}
+
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
-pub struct EcSigner(Curve);
+pub struct EcSigner {
+    curve: Curve,
+    signature_mode: EcdsaSignatureMode,
+    signature_encoding: EcdsaSignatureEncoding,
+}
 
 impl Deref for EcSigner {
     type Target = Curve;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.curve
+    }
+}
+
+impl From<Curve> for EcSigner {
+    fn from(curve: Curve) -> Self {
+        Self {
+            curve,
+            signature_mode: Default::default(),
+            signature_encoding: Default::default(),
+        }
     }
 }
 
 impl EcSigner {
     pub fn new(cipher_suite: CipherSuite) -> Option<Self> {
-        Curve::from_ciphersuite(cipher_suite, true).map(Self)
+        Curve::from_ciphersuite(cipher_suite, true).map(Self::from)
+    }
+
+    pub fn with_signature_mode(self, signature_mode: EcdsaSignatureMode) -> Self {
+        Self {
+            signature_mode,
+            ..self
+        }
+    }
+
+    pub fn signature_mode(&self) -> EcdsaSignatureMode {
+        self.signature_mode
+    }
+
+    pub fn with_signature_encoding(self, signature_encoding: EcdsaSignatureEncoding) -> Self {
+        Self {
+            signature_encoding,
+            ..self
+        }
+    }
+
+    pub fn signature_encoding(&self) -> EcdsaSignatureEncoding {
+        self.signature_encoding
+    }
+
+    /// Convert a DER-encoded ECDSA signature to the raw `r || s` form.
+    pub fn der_to_raw(&self, signature: &[u8]) -> Result<Vec<u8>, EcSignerError> {
+        let size = self.ecdsa_component_size()?;
+        let signature =
+            EcdsaSig::from_der(signature).map_err(|_| EcSignerError::InvalidSignature)?;
+
+        let mut raw = signature
+            .r()
+            .to_vec_padded(size as i32)
+            .map_err(|_| EcSignerError::InvalidSignature)?;
+
+        raw.extend(
+            signature
+                .s()
+                .to_vec_padded(size as i32)
+                .map_err(|_| EcSignerError::InvalidSignature)?,
+        );
+
+        Ok(raw)
+    }
+
+    /// Convert a raw `r || s` ECDSA signature to DER.
+    pub fn raw_to_der(&self, signature: &[u8]) -> Result<Vec<u8>, EcSignerError> {
+        let size = self.ecdsa_component_size()?;
+
+        if signature.len() != 2 * size {
+            return Err(EcSignerError::InvalidSignature);
+        }
+
+        let (r, s) = signature.split_at(size);
+        let r = BigNum::from_slice(r)?;
+        let s = BigNum::from_slice(s)?;
+
+        Ok(EcdsaSig::from_private_components(r, s)?.to_der()?)
     }
 
     pub fn signature_key_generate(
         &self,
     ) -> Result<(SignatureSecretKey, SignaturePublicKey), EcSignerError> {
-        let key_pair = generate_keypair(self.0)?;
+        let key_pair = generate_keypair(self.curve)?;
         Ok((key_pair.secret.into(), key_pair.public.into()))
     }
 
@@ -59,7 +165,7 @@ impl EcSigner {
         let key = public_key_from_der(der_data)?;
 
         curve_from_public_key(&key)
-            .filter(|&c| c == self.0)
+            .filter(|&c| c == self.curve)
             .ok_or(EcError::InvalidKeyBytes)?;
 
         Ok(pub_key_to_uncompressed(&key)?.into())
@@ -72,7 +178,7 @@ impl EcSigner {
         let key = private_key_from_der(der_data)?;
 
         curve_from_private_key(&key)
-            .filter(|&c| c == self.0)
+            .filter(|&c| c == self.curve)
             .ok_or(EcError::InvalidKeyBytes)?;
 
         Ok(private_key_to_bytes(&key)?.into())
@@ -82,7 +188,7 @@ impl EcSigner {
         &self,
         secret_key: &SignatureSecretKey,
     ) -> Result<SignaturePublicKey, EcSignerError> {
-        Ok(private_key_bytes_to_public(secret_key, self.0)?.into())
+        Ok(private_key_bytes_to_public(secret_key, self.curve)?.into())
     }
 
     #[cfg(feature = "x509")]
@@ -90,7 +196,7 @@ impl EcSigner {
         &self,
         key: &SignatureSecretKey,
     ) -> Result<PKey<Private>, EcSignerError> {
-        private_key_from_bytes(key, self.0, true).map_err(Into::into)
+        private_key_from_bytes(key, self.curve, true).map_err(Into::into)
     }
 
     #[cfg(feature = "x509")]
@@ -98,7 +204,7 @@ impl EcSigner {
         &self,
         key: &SignaturePublicKey,
     ) -> Result<PKey<Public>, EcSignerError> {
-        pub_key_from_uncompressed(key, self.0).map_err(Into::into)
+        pub_key_from_uncompressed(key, self.curve).map_err(Into::into)
     }
 
     pub fn sign(
@@ -106,14 +212,25 @@ impl EcSigner {
         secret_key: &SignatureSecretKey,
         data: &[u8],
     ) -> Result<Vec<u8>, EcSignerError> {
-        let secret_key = private_key_from_bytes(secret_key, self.0, false)?;
+        let secret_key = private_key_from_bytes(secret_key, self.curve, false)?;
 
         let mut signer = match self.message_digest() {
             Some(md) => openssl::sign::Signer::new(md, &secret_key),
             None => openssl::sign::Signer::new_without_digest(&secret_key),
         }?;
 
-        Ok(signer.sign_oneshot_to_vec(data)?)
+        let signature = signer.sign_oneshot_to_vec(data)?;
+
+        let Some(order) = self.curve_order()? else {
+            return Ok(signature);
+        };
+
+        let signature = normalize_low_s(signature, &order)?;
+
+        match self.signature_encoding {
+            EcdsaSignatureEncoding::Der => Ok(signature),
+            EcdsaSignatureEncoding::Raw => self.der_to_raw(&signature),
+        }
     }
 
     pub fn verify(
@@ -122,7 +239,28 @@ impl EcSigner {
         signature: &[u8],
         data: &[u8],
     ) -> Result<(), EcSignerError> {
-        let public_key = pub_key_from_uncompressed(public_key, self.0)?;
+        let der_signature;
+
+        let signature = match self.signature_encoding {
+            EcdsaSignatureEncoding::Raw if nist_curve_id(self.curve).is_some() => {
+                der_signature = self.raw_to_der(signature)?;
+                &der_signature
+            }
+            _ => signature,
+        };
+
+        if self.signature_mode == EcdsaSignatureMode::StrictLowS {
+            if let Some(order) = self.curve_order()? {
+                let signature =
+                    EcdsaSig::from_der(signature).map_err(|_| EcSignerError::InvalidSignature)?;
+
+                (!is_high_s(&signature, &order)?)
+                    .then_some(())
+                    .ok_or(EcSignerError::InvalidSignature)?;
+            }
+        }
+
+        let public_key = pub_key_from_uncompressed(public_key, self.curve)?;
 
         let mut verifier = match self.message_digest() {
             Some(md) => openssl::sign::Verifier::new(md, &public_key),
@@ -136,34 +274,79 @@ impl EcSigner {
     }
 
     pub(crate) fn message_digest(&self) -> Option<MessageDigest> {
-        match self.0 {
+        match self.curve {
             Curve::P256 => Some(MessageDigest::sha256()),
             Curve::P384 => Some(MessageDigest::sha384()),
             Curve::P521 => Some(MessageDigest::sha512()),
             _ => None,
         }
     }
+
+    fn ecdsa_component_size(&self) -> Result<usize, EcSignerError> {
+        nist_curve_id(self.curve)
+            .map(|_| self.curve.secret_key_size())
+            .ok_or(EcSignerError::EcError(EcError::UnsupportedCipherSuite))
+    }
+
+    fn curve_order(&self) -> Result<Option<BigNum>, EcSignerError> {
+        let Some(nid) = nist_curve_id(self.curve) else {
+            return Ok(None);
+        };
+
+        let group = EcGroup::from_curve_name(nid)?;
+        let mut order = BigNum::new()?;
+        let mut ctx = BigNumContext::new()?;
+        group.order(&mut order, &mut ctx)?;
+
+        Ok(Some(order))
+    }
+}
+
+fn is_high_s(signature: &EcdsaSig, order: &BigNum) -> Result<bool, EcSignerError> {
+    let mut half_order = BigNum::new()?;
+    half_order.rshift1(order)?;
+
+    Ok(signature.s() > &*half_order)
+}
+
+fn normalize_low_s(signature: Vec<u8>, order: &BigNum) -> Result<Vec<u8>, EcSignerError> {
+    let parsed = EcdsaSig::from_der(&signature)?;
+
+    if !is_high_s(&parsed, order)? {
+        return Ok(signature);
+    }
+
+    let r = parsed.r().to_owned()?;
+    let s = &**order - parsed.s();
+
+    Ok(EcdsaSig::from_private_components(r, s)?.to_der()?)
 }
 
 #[cfg(test)]
 mod test {
+    use assert_matches::assert_matches;
     use mls_rs_crypto_traits::Curve;
+    use openssl::ecdsa::EcdsaSig;
 
     use crate::{
         ec::test_utils::{
             get_test_public_keys, get_test_public_keys_der, get_test_secret_keys,
             get_test_secret_keys_der, TestKeys,
         },
-        ec_signer::EcSigner,
+        ec_signer::{
+            is_high_s, EcSigner, EcSignerError, EcdsaSignatureEncoding, EcdsaSignatureMode,
+        },
     };
 
+    const ECDSA_CURVES: [Curve; 3] = [Curve::P256, Curve::P384, Curve::P521];
+
     #[test]
     fn import_der_public() {
         let keys = get_test_public_keys();
         let der_keys = get_test_public_keys_der();
 
         let convert = |keys: &TestKeys, curve: Curve| {
-            EcSigner(curve)
+            EcSigner::from(curve)
                 .signature_key_import_der_public(&keys.get_key_from_curve(curve))
                 .unwrap()
         };
@@ -187,7 +370,7 @@ mod test {
         let der_keys = get_test_secret_keys_der();
 
         let convert = |keys: &TestKeys, curve: Curve| {
-            EcSigner(curve)
+            EcSigner::from(curve)
                 .signature_key_import_der_private(&keys.get_key_from_curve(curve))
                 .unwrap()
         };
@@ -204,4 +387,127 @@ mod test {
 
         assert_eq!(keys, converted);
     }
+
+    #[test]
+    fn signatures_are_accepted_by_low_s_only_verifier() {
+        for curve in ECDSA_CURVES {
+            let signer = EcSigner::from(curve);
+            let strict = signer.with_signature_mode(EcdsaSignatureMode::StrictLowS);
+            let order = signer.curve_order().unwrap().unwrap();
+            let (secret, public) = signer.signature_key_generate().unwrap();
+
+            // ECDSA is randomized, so repeat to hit signatures that OpenSSL produces in high-S form
+            for i in 0..32u8 {
+                let signature = signer.sign(&secret, &[i]).unwrap();
+                let parsed = EcdsaSig::from_der(&signature).unwrap();
+
+                assert!(!is_high_s(&parsed, &order).unwrap());
+                strict.verify(&public, &signature, &[i]).unwrap();
+                signer.verify(&public, &signature, &[i]).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn high_s_signatures_are_rejected_in_strict_mode() {
+        for curve in ECDSA_CURVES {
+            let signer = EcSigner::from(curve);
+            let strict = signer.with_signature_mode(EcdsaSignatureMode::StrictLowS);
+            let order = signer.curve_order().unwrap().unwrap();
+            let (secret, public) = signer.signature_key_generate().unwrap();
+
+            let signature = strict.sign(&secret, b"message").unwrap();
+            let parsed = EcdsaSig::from_der(&signature).unwrap();
+
+            let high_s = EcdsaSig::from_private_components(
+                parsed.r().to_owned().unwrap(),
+                &*order - parsed.s(),
+            )
+            .unwrap()
+            .to_der()
+            .unwrap();
+
+            signer.verify(&public, &high_s, b"message").unwrap();
+
+            assert_matches!(
+                strict.verify(&public, &high_s, b"message"),
+                Err(EcSignerError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn signature_mode_does_not_affect_eddsa() {
+        let signer = EcSigner::from(Curve::Ed25519);
+        let strict = signer.with_signature_mode(EcdsaSignatureMode::StrictLowS);
+        let (secret, public) = signer.signature_key_generate().unwrap();
+
+        let signature = signer.sign(&secret, b"message").unwrap();
+        strict.verify(&public, &signature, b"message").unwrap();
+    }
+
+    #[test]
+    fn der_and_raw_signatures_round_trip() {
+        for curve in ECDSA_CURVES {
+            let signer = EcSigner::from(curve);
+            let raw_signer = signer.with_signature_encoding(EcdsaSignatureEncoding::Raw);
+            let (secret, public) = signer.signature_key_generate().unwrap();
+
+            let der = signer.sign(&secret, b"message").unwrap();
+            let raw = signer.der_to_raw(&der).unwrap();
+
+            assert_eq!(raw.len(), 2 * curve.secret_key_size());
+            assert_eq!(signer.raw_to_der(&raw).unwrap(), der);
+            raw_signer.verify(&public, &raw, b"message").unwrap();
+
+            let raw = raw_signer.sign(&secret, b"message").unwrap();
+
+            assert_eq!(raw.len(), 2 * curve.secret_key_size());
+            signer
+                .verify(&public, &raw_signer.raw_to_der(&raw).unwrap(), b"message")
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn raw_signature_reference() {
+        // RFC 6979 A.2.5, P-256 with SHA-256 over "sample"
+        let public = hex::decode(concat!(
+            "04",
+            "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6",
+            "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"
+        ))
+        .unwrap()
+        .into();
+
+        let raw = hex::decode(concat!(
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+        ))
+        .unwrap();
+
+        let signer = EcSigner::from(Curve::P256);
+        let raw_signer = signer.with_signature_encoding(EcdsaSignatureEncoding::Raw);
+
+        raw_signer.verify(&public, &raw, b"sample").unwrap();
+
+        let der = signer.raw_to_der(&raw).unwrap();
+        signer.verify(&public, &der, b"sample").unwrap();
+        assert_eq!(signer.der_to_raw(&der).unwrap(), raw);
+
+        assert_matches!(
+            signer.verify(&public, &raw, b"sample"),
+            Err(EcSignerError::InvalidSignature | EcSignerError::OpensslError(_))
+        );
+    }
+
+    #[test]
+    fn raw_signature_of_wrong_length_is_rejected() {
+        let signer = EcSigner::from(Curve::P256);
+
+        assert_matches!(
+            signer.raw_to_der(&[1u8; 63]),
+            Err(EcSignerError::InvalidSignature)
+        );
+    }
 }
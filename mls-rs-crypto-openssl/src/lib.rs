@@ -21,7 +21,7 @@ use mls_rs_crypto_hpke::{
 use mls_rs_crypto_traits::{AeadType, KdfType, KemId, KemType};
 
 use ec::EcError;
-use ec_signer::{EcSigner, EcSignerError};
+use ec_signer::{EcSigner, EcSignerError, EcdsaSignatureEncoding, EcdsaSignatureMode};
 use ecdh::Ecdh;
 use kdf::Kdf;
 use mac::{Hash, HashError};
@@ -67,6 +67,8 @@ impl IntoAnyError for OpensslCryptoError {
 #[non_exhaustive]
 pub struct OpensslCryptoProvider {
     pub enabled_cipher_suites: Vec<CipherSuite>,
+    pub ecdsa_signature_mode: EcdsaSignatureMode,
+    pub ecdsa_signature_encoding: EcdsaSignatureEncoding,
 }
 
 impl OpensslCryptoProvider {
@@ -77,6 +79,25 @@ impl OpensslCryptoProvider {
     pub fn with_enabled_cipher_suites(enabled_cipher_suites: Vec<CipherSuite>) -> Self {
         Self {
             enabled_cipher_suites,
+            ecdsa_signature_mode: Default::default(),
+            ecdsa_signature_encoding: Default::default(),
+        }
+    }
+
+    pub fn with_ecdsa_signature_mode(self, ecdsa_signature_mode: EcdsaSignatureMode) -> Self {
+        Self {
+            ecdsa_signature_mode,
+            ..self
+        }
+    }
+
+    pub fn with_ecdsa_signature_encoding(
+        self,
+        ecdsa_signature_encoding: EcdsaSignatureEncoding,
+    ) -> Self {
+        Self {
+            ecdsa_signature_encoding,
+            ..self
         }
     }
 
@@ -89,6 +110,8 @@ impl Default for OpensslCryptoProvider {
     fn default() -> Self {
         Self {
             enabled_cipher_suites: Self::all_supported_cipher_suites(),
+            ecdsa_signature_mode: Default::default(),
+            ecdsa_signature_encoding: Default::default(),
         }
     }
 }
@@ -114,7 +137,10 @@ impl CryptoProvider for OpensslCryptoProvider {
         let kem = DhKem::new(ecdh, kdf.clone(), kem_id as u16, kem_id.n_secret());
         let aead = Aead::new(cipher_suite)?;
 
-        OpensslCipherSuite::new(cipher_suite, kem, kdf, aead)
+        OpensslCipherSuite::new(cipher_suite, kem, kdf, aead).map(|cs| {
+            cs.with_ecdsa_signature_mode(self.ecdsa_signature_mode)
+                .with_ecdsa_signature_encoding(self.ecdsa_signature_encoding)
+        })
     }
 }
 
@@ -152,6 +178,25 @@ where
         })
     }
 
+    pub fn with_ecdsa_signature_mode(self, ecdsa_signature_mode: EcdsaSignatureMode) -> Self {
+        Self {
+            ec_signer: self.ec_signer.with_signature_mode(ecdsa_signature_mode),
+            ..self
+        }
+    }
+
+    pub fn with_ecdsa_signature_encoding(
+        self,
+        ecdsa_signature_encoding: EcdsaSignatureEncoding,
+    ) -> Self {
+        Self {
+            ec_signer: self
+                .ec_signer
+                .with_signature_encoding(ecdsa_signature_encoding),
+            ..self
+        }
+    }
+
     pub fn random_bytes(&self, out: &mut [u8]) -> Result<(), OpensslCryptoError> {
         Ok(openssl::rand::rand_bytes(out)?)
     }
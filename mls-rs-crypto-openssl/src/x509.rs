@@ -15,6 +15,7 @@ use mls_rs_identity_x509::{
     X509RequestWriter,
 };
 use openssl::{
+    asn1::Asn1Time,
     bn::BigNumContext,
     ec::PointConversionForm,
     error::ErrorStack,
@@ -40,6 +41,16 @@ pub enum X509Error {
     EmptyCertificateChain,
     #[error("certificate chain validation failure: {0:?}")]
     ChainValidationFailure(String),
+    #[error("certificate in the chain has expired")]
+    Expired,
+    #[error("certificate in the chain is not yet valid")]
+    NotYetValid,
+    #[error("certificate chain ends with a self-signed certificate that is not trusted")]
+    UntrustedAnchor,
+    #[error("certificate chain is missing the issuer of a certificate")]
+    ChainIncomplete,
+    #[error("malformed certificate: {0}")]
+    MalformedCertificate(ErrorStack),
     #[error("root ca is not a der encoded x509 certificate")]
     InvalidCertificateData,
     #[error("root ca is not properly self-signed")]
@@ -129,10 +140,10 @@ impl X509Validator {
 
         let leaf = chain.leaf().ok_or(X509Error::EmptyCertificateChain)?;
 
-        let leaf_certificate = X509::from_der(leaf)?;
+        let leaf_certificate = X509::from_der(leaf).map_err(X509Error::MalformedCertificate)?;
 
         let cert_chain = chain.iter().try_fold(Stack::new()?, |mut stack, cert| {
-            stack.push(X509::from_der(cert)?)?;
+            stack.push(X509::from_der(cert).map_err(X509Error::MalformedCertificate)?)?;
             Ok::<_, X509Error>(stack)
         })?;
 
@@ -147,13 +158,23 @@ impl X509Validator {
                     .map(SignaturePublicKey::from)?;
                 Ok(signature_public_key)
             }
-            _ => Err(X509Error::ChainValidationFailure(
-                verify_res.error_string().to_string(),
-            )),
+            _ => Err(verification_error(verify_res)),
         }
     }
 }
 
+fn verification_error(verify_res: X509VerifyResult) -> X509Error {
+    match verify_res.as_raw() {
+        openssl_sys::X509_V_ERR_CERT_HAS_EXPIRED => X509Error::Expired,
+        openssl_sys::X509_V_ERR_CERT_NOT_YET_VALID => X509Error::NotYetValid,
+        openssl_sys::X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT
+        | openssl_sys::X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN => X509Error::UntrustedAnchor,
+        openssl_sys::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT
+        | openssl_sys::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY => X509Error::ChainIncomplete,
+        _ => X509Error::ChainValidationFailure(verify_res.error_string().to_string()),
+    }
+}
+
 pub fn pub_key_to_uncompressed(key: PKey<Public>) -> Result<Vec<u8>, X509Error> {
     if let Ok(ec_key) = key.ec_key() {
         let mut ctx = BigNumContext::new()?;
@@ -177,6 +198,19 @@ impl X509CredentialValidator for X509Validator {
     ) -> Result<SignaturePublicKey, Self::Error> {
         self.validate_chain(chain, timestamp)
     }
+
+    fn not_after(
+        &self,
+        certificate: &DerCertificate,
+    ) -> Result<mls_rs_core::time::MlsTime, Self::Error> {
+        let certificate = X509::from_der(certificate).map_err(X509Error::MalformedCertificate)?;
+        let since_epoch = Asn1Time::from_unix(0)?.diff(certificate.not_after())?;
+        let seconds = i64::from(since_epoch.days) * 86400 + i64::from(since_epoch.secs);
+
+        u64::try_from(seconds)
+            .map(mls_rs_core::time::MlsTime::from)
+            .map_err(|_| X509Error::InvalidCertificateLifetime)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -573,11 +607,12 @@ mod tests {
     use assert_matches::assert_matches;
     use mls_rs_core::{
         crypto::{CipherSuite, SignaturePublicKey, SignatureSecretKey},
+        identity::SigningIdentity,
         time::MlsTime,
     };
     use mls_rs_identity_x509::{
         CertificateChain, CertificateRequestParameters, DerCertificateRequest, SubjectAltName,
-        SubjectComponent, X509CertificateReader, X509RequestWriter,
+        SubjectComponent, X509CertificateReader, X509CredentialValidator, X509RequestWriter,
     };
     use openssl::{
         pkey::PKey,
@@ -601,6 +636,11 @@ mod tests {
         X509Error, X509Reader, X509Validator,
     };
 
+    // Within the validity period of the test certificates
+    fn valid_time() -> MlsTime {
+        MlsTime::from_duration_since_epoch(Duration::from_secs(1704067200))
+    }
+
     #[test]
     fn can_detect_invalid_ca_certificates() {
         assert_matches!(
@@ -627,12 +667,12 @@ mod tests {
         let system_validator = X509Validator::new(vec![]).unwrap().with_system_ca();
 
         validator
-            .validate_chain(&chain, Some(MlsTime::now()))
+            .validate_chain(&chain, Some(valid_time()))
             .unwrap();
 
         assert_matches!(
             system_validator.validate_chain(&chain, None),
-            Err(X509Error::ChainValidationFailure(_))
+            Err(X509Error::UntrustedAnchor)
         )
     }
 
@@ -663,7 +703,7 @@ mod tests {
 
         assert_matches!(
             plain_validator.validate_chain(&chain, None),
-            Err(X509Error::ChainValidationFailure(_))
+            Err(X509Error::ChainIncomplete)
         )
     }
 
@@ -672,18 +712,39 @@ mod tests {
         let chain = load_test_invalid_chain();
         let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
 
-        let res = validator.validate_chain(&chain, Some(MlsTime::now()));
+        let res = validator.validate_chain(&chain, Some(valid_time()));
 
-        assert_matches!(res, Err(X509Error::ChainValidationFailure(_)));
+        // The chain is missing the intermediate certificate issuing the leaf
+        assert_matches!(res, Err(X509Error::ChainIncomplete));
     }
 
     #[test]
     fn will_fail_on_invalid_ca() {
         let chain = load_test_invalid_ca_chain();
         let validator = X509Validator::new(vec![load_another_ca()]).unwrap();
-        let res = validator.validate_chain(&chain, Some(MlsTime::now()));
+        let res = validator.validate_chain(&chain, Some(valid_time()));
 
-        assert_matches!(res, Err(X509Error::ChainValidationFailure(_)));
+        assert_matches!(res, Err(X509Error::ChainIncomplete));
+    }
+
+    #[test]
+    fn will_fail_on_untrusted_anchor() {
+        let chain = load_test_cert_chain();
+        let validator = X509Validator::new(vec![load_another_ca()]).unwrap();
+        let res = validator.validate_chain(&chain, Some(valid_time()));
+
+        assert_matches!(res, Err(X509Error::UntrustedAnchor));
+    }
+
+    #[test]
+    fn will_fail_on_malformed_certificate() {
+        let mut chain = load_test_cert_chain().to_vec();
+        chain[1] = vec![0u8; 32].into();
+
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+        let res = validator.validate_chain(&chain.into(), Some(valid_time()));
+
+        assert_matches!(res, Err(X509Error::MalformedCertificate(_)));
     }
 
     #[test]
@@ -699,7 +760,48 @@ mod tests {
             ))),
         );
 
-        assert_matches!(res, Err(X509Error::ChainValidationFailure(_)));
+        assert_matches!(res, Err(X509Error::Expired));
+    }
+
+    #[test]
+    fn can_detect_certs_not_yet_valid() {
+        let chain = load_test_cert_chain();
+
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+
+        let res = validator.validate_chain(
+            &chain,
+            Some(MlsTime::from_duration_since_epoch(Duration::from_secs(
+                1640995200,
+            ))),
+        );
+
+        assert_matches!(res, Err(X509Error::NotYetValid));
+    }
+
+    #[test]
+    fn not_after_is_read_from_certificate() {
+        let chain = load_test_cert_chain();
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+
+        let not_after = X509CredentialValidator::not_after(&validator, &chain[0]).unwrap();
+
+        // 2026-01-09 14:14:00 UTC
+        assert_eq!(not_after, MlsTime::from(1767968040));
+    }
+
+    #[test]
+    fn identity_provider_reports_expiration_of_leaf() {
+        let provider = super::identity_provider_from_certificate(&load_test_ca()).unwrap();
+
+        let signing_identity = SigningIdentity::new(
+            load_test_cert_chain().into_credential(),
+            SignaturePublicKey::from(vec![]),
+        );
+
+        let expiration = provider.expiration(&signing_identity).unwrap();
+
+        assert_eq!(expiration, MlsTime::from(1767968040));
     }
 
     #[test]
@@ -56,15 +56,30 @@ pub enum X509Error {
     InvalidCaExtensions,
     #[cfg_attr(feature = "std", error("invalid certificate lifetime"))]
     InvalidCertificateLifetime,
-    #[cfg_attr(feature = "std", error("no trusted CA certificate found in the chain"))]
-    CaNotFound,
+    #[cfg_attr(
+        feature = "std",
+        error("certificate chain ends with a self-signed certificate that is not trusted")
+    )]
+    UntrustedAnchor,
+    #[cfg_attr(
+        feature = "std",
+        error("certificate chain is missing the issuer of a certificate")
+    )]
+    ChainIncomplete,
+    #[cfg_attr(feature = "std", error("malformed certificate: {0}"))]
+    MalformedCertificate(x509_cert::der::Error),
     #[cfg_attr(feature = "std", error("pinned certificate not found in the chain"))]
     PinnedCertNotFound,
     #[cfg_attr(
         feature = "std",
-        error("Current (commit) timestamp {0} outside of the validity period of certificate {1}")
+        error("Current (commit) timestamp {0} is after the expiration of certificate {1}")
+    )]
+    Expired(u64, String),
+    #[cfg_attr(
+        feature = "std",
+        error("Current (commit) timestamp {0} is before the start of validity of certificate {1}")
     )]
-    ValidityError(u64, String),
+    NotYetValid(u64, String),
     #[cfg_attr(feature = "std", error("unsupported signing algorithm with OID {0}"))]
     UnsupportedAlgorithm(ObjectIdentifier),
     #[cfg_attr(feature = "std", error(transparent))]
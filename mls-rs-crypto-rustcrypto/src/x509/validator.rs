@@ -52,7 +52,7 @@ impl X509Validator {
             .into_iter()
             .map(|cert_data| {
                 // Verify the self-signture. Time is validated when CAs are used
-                let cert = Certificate::from_der(&cert_data)?;
+                let cert = parse_certificate(&cert_data)?;
                 verify_cert(&cert, &cert, None)?;
                 let subject = cert.tbs_certificate.subject.to_der()?;
                 Ok((subject, cert_data))
@@ -93,34 +93,22 @@ impl X509Validator {
 
         let chain = chain
             .iter()
-            .map(|cert_data| Certificate::from_der(cert_data))
+            .map(|cert_data| parse_certificate(cert_data))
             .collect::<Result<Vec<_>, _>>()?;
 
-        for (cert1, cert2) in chain
-            .iter()
-            .zip(chain.iter().skip(1).chain(chain.iter().rev().take(1)))
-        {
-            let maybe_ca = self
-                .root_ca_list
-                .get(&cert1.tbs_certificate.issuer.to_der()?);
-
-            let verifier = maybe_ca
-                .map(|ca| {
-                    let ca = Certificate::from_der(ca)?;
+        for (i, cert) in chain.iter().enumerate() {
+            let issuer = cert.tbs_certificate.issuer.to_der()?;
 
-                    if let Some(time) = timestamp {
-                        verify_time(&ca, time)?;
-                    }
+            // If we find a CA, we're done with the chain.
+            if let Some(ca) = self.root_ca_list.get(&issuer) {
+                let ca = parse_certificate(ca)?;
 
-                    Ok::<_, X509Error>(ca)
-                })
-                .transpose()?;
+                if let Some(time) = timestamp {
+                    verify_time(&ca, time)?;
+                }
 
-            let verifier = verifier.as_ref().unwrap_or(cert2);
-            verify_cert(verifier, cert1, timestamp)?;
+                verify_cert(&ca, cert, timestamp)?;
 
-            // If we found a CA, we're done with the chain.
-            if maybe_ca.is_some() {
                 let leaf_cert = chain.first().ok_or(X509Error::EmptyCertificateChain)?;
 
                 let pub_key =
@@ -130,21 +118,41 @@ impl X509Validator {
 
                 return Ok(pub_signing_key);
             }
+
+            // Otherwise the certificate must be issued by the next one in the chain, or be
+            // the self-signed last one.
+            let verifier = chain.get(i + 1).unwrap_or(cert);
+
+            if verifier.tbs_certificate.subject.to_der()? != issuer {
+                return Err(X509Error::ChainIncomplete);
+            }
+
+            verify_cert(verifier, cert, timestamp)?;
         }
 
-        Err(X509Error::CaNotFound)
+        Err(X509Error::UntrustedAnchor)
     }
 }
 
+fn parse_certificate(cert_data: &[u8]) -> Result<Certificate, X509Error> {
+    Certificate::from_der(cert_data).map_err(X509Error::MalformedCertificate)
+}
+
 fn verify_time(cert: &Certificate, time: MlsTime) -> Result<(), X509Error> {
     let validity = cert.tbs_certificate.validity;
     let now = time.seconds_since_epoch();
     let not_before = validity.not_before.to_unix_duration().as_secs();
     let not_after = validity.not_after.to_unix_duration().as_secs();
 
-    (not_before <= now && now <= not_after)
-        .then_some(())
-        .ok_or_else(|| X509Error::ValidityError(now, format!("{cert:?}")))
+    if now < not_before {
+        return Err(X509Error::NotYetValid(now, format!("{cert:?}")));
+    }
+
+    if now > not_after {
+        return Err(X509Error::Expired(now, format!("{cert:?}")));
+    }
+
+    Ok(())
 }
 
 fn verify_cert(
@@ -185,7 +193,7 @@ fn validate_self_signed(
         return Err(X509Error::SelfSignedWrongLength(chain.len()));
     }
 
-    let cert = Certificate::from_der(&chain[0])?;
+    let cert = parse_certificate(&chain[0])?;
 
     verify_cert(&cert, &cert, timestamp)?;
 
@@ -210,6 +218,13 @@ impl X509CredentialValidator for X509Validator {
             validate_self_signed(chain, timestamp)
         }
     }
+
+    fn not_after(&self, certificate: &DerCertificate) -> Result<MlsTime, Self::Error> {
+        let cert = parse_certificate(certificate)?;
+        let not_after = cert.tbs_certificate.validity.not_after.to_unix_duration();
+
+        Ok(MlsTime::from_duration_since_epoch(not_after))
+    }
 }
 
 #[cfg(test)]
@@ -219,7 +234,7 @@ mod tests {
     use assert_matches::assert_matches;
     use mls_rs_core::time::MlsTime;
     use mls_rs_identity_x509::{CertificateChain, X509CredentialValidator};
-    use spki::der::Decode;
+    use spki::der::{Decode, Encode};
     use x509_cert::Certificate;
 
     use crate::{
@@ -235,6 +250,11 @@ mod tests {
 
     use super::X509Validator;
 
+    // Within the validity period of the test certificates
+    fn valid_time() -> MlsTime {
+        MlsTime::from_duration_since_epoch(Duration::from_secs(1704067200))
+    }
+
     #[test]
     fn can_validate_cert_chain() {
         let chain = load_test_cert_chain();
@@ -270,6 +290,17 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn not_after_is_read_from_certificate() {
+        let chain = load_test_cert_chain();
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+
+        let not_after = validator.not_after(&chain[0]).unwrap();
+
+        // 2026-01-09 14:14:00 UTC
+        assert_eq!(not_after, MlsTime::from(1767968040));
+    }
+
     #[test]
     fn can_validate_self_signed() {
         let mut validator = X509Validator::new(vec![]).unwrap();
@@ -308,7 +339,7 @@ mod tests {
     fn can_detect_invalid_ca_certificates() {
         assert_matches!(
             X509Validator::new(vec![vec![0u8; 32].into()]),
-            Err(X509Error::X509DerError(_))
+            Err(X509Error::MalformedCertificate(_))
         )
     }
 
@@ -339,17 +370,53 @@ mod tests {
 
         let res = validator.validate_chain(&chain, Some(MlsTime::now()));
 
-        assert_matches!(
-            res,
-            Err(X509Error::EcSignerError(EcSignerError::InvalidSignature))
-        );
+        // The chain is missing the intermediate certificate issuing the leaf
+        assert_matches!(res, Err(X509Error::ChainIncomplete));
     }
 
     #[test]
     fn will_fail_on_invalid_ca() {
         let chain = load_test_invalid_ca_chain();
         let validator = X509Validator::new(vec![load_another_ca()]).unwrap();
-        let res = validator.validate_chain(&chain, Some(MlsTime::now()));
+        let res = validator.validate_chain(&chain, Some(valid_time()));
+
+        assert_matches!(res, Err(X509Error::ChainIncomplete));
+    }
+
+    #[test]
+    fn will_fail_on_untrusted_anchor() {
+        let chain = load_test_cert_chain();
+        let validator = X509Validator::new(vec![load_another_ca()]).unwrap();
+        let res = validator.validate_chain(&chain, Some(valid_time()));
+
+        assert_matches!(res, Err(X509Error::UntrustedAnchor));
+    }
+
+    #[test]
+    fn will_fail_on_malformed_certificate() {
+        let mut chain = load_test_cert_chain().to_vec();
+        chain[1] = vec![0u8; 32].into();
+
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+        let res = validator.validate_chain(&chain.into(), Some(valid_time()));
+
+        assert_matches!(res, Err(X509Error::MalformedCertificate(_)));
+    }
+
+    #[test]
+    fn will_fail_on_signature_mismatch() {
+        // The intermediate is replaced by a certificate with the same subject that
+        // did not issue the leaf
+        let mut chain = load_test_cert_chain().to_vec();
+        let mut intermediate = Certificate::from_der(&chain[1]).unwrap();
+        intermediate.tbs_certificate.subject_public_key_info = Certificate::from_der(&chain[0])
+            .unwrap()
+            .tbs_certificate
+            .subject_public_key_info;
+        chain[1] = intermediate.to_der().unwrap().into();
+
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+        let res = validator.validate_chain(&chain.into(), Some(valid_time()));
 
         assert_matches!(
             res,
@@ -370,7 +437,23 @@ mod tests {
             ))),
         );
 
-        assert_matches!(res, Err(X509Error::ValidityError(_, _)));
+        assert_matches!(res, Err(X509Error::Expired(_, _)));
+    }
+
+    #[test]
+    fn can_detect_certs_not_yet_valid() {
+        let chain = load_test_cert_chain();
+
+        let validator = X509Validator::new(vec![load_test_ca()]).unwrap();
+
+        let res = validator.validate_chain(
+            &chain,
+            Some(MlsTime::from_duration_since_epoch(Duration::from_secs(
+                1640995200,
+            ))),
+        );
+
+        assert_matches!(res, Err(X509Error::NotYetValid(_, _)));
     }
 
     #[test]
@@ -74,3 +74,49 @@ impl Curve {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use mls_rs_core::crypto::CipherSuite;
+
+    use super::Curve;
+
+    #[test]
+    fn every_cipher_suite_has_a_signature_curve() {
+        let mut curves = CipherSuite::all()
+            .map(|cs| Curve::from_ciphersuite(cs, true).unwrap())
+            .collect::<Vec<_>>();
+
+        curves.sort_by_key(|curve| *curve as u8);
+        curves.dedup();
+
+        assert_eq!(
+            curves,
+            [
+                Curve::P256,
+                Curve::P384,
+                Curve::P521,
+                Curve::Ed25519,
+                Curve::Ed448
+            ]
+        );
+    }
+
+    #[test]
+    fn signature_curve_matches_kem_curve() {
+        for cs in CipherSuite::all() {
+            let kem_curve = Curve::from_ciphersuite(cs, false).unwrap();
+            let sig_curve = Curve::from_ciphersuite(cs, true).unwrap();
+
+            let expected = match kem_curve {
+                Curve::X25519 => Curve::Ed25519,
+                Curve::X448 => Curve::Ed448,
+                curve => curve,
+            };
+
+            assert_eq!(sig_curve, expected, "cipher suite {cs:?}");
+        }
+    }
+}
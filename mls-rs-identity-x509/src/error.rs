@@ -32,6 +32,22 @@ pub enum X509IdentityError {
     IdentityWarningProviderError(AnyError),
 }
 
+#[cfg(feature = "std")]
+impl X509IdentityError {
+    /// Error returned by the
+    /// [`X509CredentialValidator`](crate::X509CredentialValidator) that
+    /// rejected a certificate chain, if it is of type `E`.
+    ///
+    /// This gives access to the reason of the failure, such as an expired
+    /// certificate or an untrusted root, as reported by the validator.
+    pub fn validation_error<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        match self {
+            X509IdentityError::X509ValidationError(e) => e.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
 impl mls_rs_core::error::IntoAnyError for X509IdentityError {
     #[cfg(feature = "std")]
     fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
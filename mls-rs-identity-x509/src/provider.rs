@@ -2,7 +2,7 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::{util::credential_to_chain, CertificateChain, X509IdentityError};
+use crate::{util::credential_to_chain, CertificateChain, DerCertificate, X509IdentityError};
 use alloc::vec;
 use alloc::vec::Vec;
 use mls_rs_core::{
@@ -50,6 +50,10 @@ pub trait X509CredentialValidator {
         chain: &CertificateChain,
         timestamp: Option<MlsTime>,
     ) -> Result<SignaturePublicKey, Self::Error>;
+
+    /// Time after which a certificate is no longer valid, as given by its
+    /// `notAfter` field.
+    fn not_after(&self, certificate: &DerCertificate) -> Result<MlsTime, Self::Error>;
 }
 
 #[derive(Clone, Debug)]
@@ -126,6 +130,23 @@ where
             .map_err(|e| X509IdentityError::IdentityExtractorError(e.into_any_error()))
     }
 
+    /// Time after which the leaf certificate of `signing_identity` is no
+    /// longer valid, based on the behavior of the underlying validator
+    /// provided.
+    pub fn expiration(
+        &self,
+        signing_identity: &mls_rs_core::identity::SigningIdentity,
+    ) -> Result<MlsTime, X509IdentityError> {
+        let chain = credential_to_chain(&signing_identity.credential)?;
+        let leaf = chain
+            .leaf()
+            .ok_or(X509IdentityError::EmptyCertificateChain)?;
+
+        self.validator
+            .not_after(leaf)
+            .map_err(|e| X509IdentityError::X509ValidationError(e.into_any_error()))
+    }
+
     /// Supported credential types.
     ///
     /// Only [`CredentialType::X509`] is supported.
@@ -178,6 +199,13 @@ where
         self.valid_successor(predecessor, successor)
     }
 
+    async fn expiration(
+        &self,
+        signing_identity: &mls_rs_core::identity::SigningIdentity,
+    ) -> Result<Option<MlsTime>, Self::Error> {
+        self.expiration(signing_identity).map(Some)
+    }
+
     fn supported_types(&self) -> Vec<CredentialType> {
         self.supported_types()
     }
@@ -284,4 +312,40 @@ mod tests {
             Err(X509IdentityError::X509ValidationError(_))
         )
     }
+
+    #[test]
+    fn expiration_is_not_after_of_leaf() {
+        let chain = test_certificate_chain();
+        let test_signing_identity = test_signing_identity_with_chain(chain.clone());
+        let not_after = MlsTime::from(1767968040);
+
+        let test_provider = test_setup(|_, validator| {
+            validator
+                .expect_not_after()
+                .once()
+                .with(mockall::predicate::eq(chain[0].clone()))
+                .return_once_st(move |_| Ok(not_after));
+        });
+
+        assert_eq!(
+            test_provider.expiration(&test_signing_identity).unwrap(),
+            not_after
+        );
+    }
+
+    #[test]
+    fn failing_validation_exposes_validator_error() {
+        let test_provider = test_setup(|_, validator| {
+            validator
+                .expect_validate_chain()
+                .return_once_st(|_, _| Err(TestError));
+        });
+
+        let error = test_provider
+            .validate(&test_signing_identity(), None)
+            .unwrap_err();
+
+        assert_matches!(error.validation_error::<TestError>(), Some(TestError));
+        assert!(error.validation_error::<std::fmt::Error>().is_none());
+    }
 }
@@ -0,0 +1,77 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use criterion::{BatchSize, BenchmarkId, Criterion};
+use mls_rs::{test_utils::benchmarks::load_group_states, CipherSuite};
+
+const REMOVED_MEMBERS: usize = 100;
+
+fn bench(c: &mut Criterion) {
+    let cipher_suite = CipherSuite::CURVE25519_AES128;
+    let group_states = load_group_states(cipher_suite).pop().unwrap();
+    let mut bench_group = c.benchmark_group("group_remove");
+
+    let kept = [
+        group_states.sender.current_member_index(),
+        group_states.receiver.current_member_index(),
+    ];
+
+    let to_remove = group_states
+        .sender
+        .roster()
+        .members_iter()
+        .map(|member| member.index)
+        .filter(|index| !kept.contains(index))
+        .take(REMOVED_MEMBERS)
+        .collect::<Vec<_>>();
+
+    bench_group.bench_with_input(
+        BenchmarkId::new(format!("{cipher_suite:?}"), "one_commit"),
+        &to_remove,
+        |b, to_remove| {
+            b.iter_batched_ref(
+                || group_states.sender.clone(),
+                |sender| {
+                    to_remove
+                        .iter()
+                        .try_fold(sender.commit_builder(), |builder, index| {
+                            builder.remove_member(*index)
+                        })
+                        .unwrap()
+                        .build()
+                        .unwrap()
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+
+    bench_group.bench_with_input(
+        BenchmarkId::new(format!("{cipher_suite:?}"), "one_commit_per_member"),
+        &to_remove,
+        |b, to_remove| {
+            b.iter_batched_ref(
+                || group_states.sender.clone(),
+                |sender| {
+                    for index in to_remove {
+                        sender
+                            .commit_builder()
+                            .remove_member(*index)
+                            .unwrap()
+                            .build()
+                            .unwrap();
+
+                        sender.apply_pending_commit().unwrap();
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+
+    bench_group.finish();
+}
+
+criterion::criterion_group!(benches, bench);
+criterion::criterion_main!(benches);
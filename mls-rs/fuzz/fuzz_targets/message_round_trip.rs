@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#![no_main]
+
+mod message_round_trip {
+    use libfuzzer_sys::fuzz_target;
+    use mls_rs::test_utils::check_message_round_trip;
+
+    fuzz_target!(|data: &[u8]| {
+        check_message_round_trip(data);
+    });
+}
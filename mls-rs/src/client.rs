@@ -5,11 +5,15 @@
 use crate::cipher_suite::CipherSuite;
 use crate::client_builder::{recreate_config, BaseConfig, ClientBuilder, MakeConfig};
 use crate::client_config::ClientConfig;
+use crate::extension::ApplicationIdExt;
+#[cfg(feature = "last_resort_key_package_ext")]
+use crate::extension::LastResortKeyPackageExt;
 use crate::group::framing::MlsMessage;
 
 use crate::group::{cipher_suite_provider, validate_group_info_joiner, GroupInfo};
 use crate::group::{
-    framing::MlsMessagePayload, snapshot::Snapshot, ExportedTree, Group, NewMemberInfo,
+    framing::MlsMessagePayload, snapshot::Snapshot, ExportedTree, Group, GroupBuilder,
+    NewMemberInfo,
 };
 #[cfg(feature = "by_ref_proposal")]
 use crate::group::{
@@ -19,6 +23,8 @@ use crate::group::{
 };
 use crate::identity::SigningIdentity;
 use crate::key_package::{KeyPackageGeneration, KeyPackageGenerator};
+use crate::keychain::Keychain;
+use crate::mls_rules::ProposalSource;
 use crate::protocol_version::ProtocolVersion;
 use crate::tree_kem::node::NodeIndex;
 use alloc::vec::Vec;
@@ -70,7 +76,10 @@ pub enum MlsError {
     InvalidMembershipTag,
     #[cfg_attr(feature = "std", error("corrupt private key, missing required values"))]
     InvalidTreeKemPrivateKey,
-    #[cfg_attr(feature = "std", error("key package not found, unable to process"))]
+    #[cfg_attr(
+        feature = "std",
+        error("welcome message has no group secrets for any stored key package")
+    )]
     WelcomeKeyPackageNotFound,
     #[cfg_attr(feature = "std", error("leaf not found in tree for index {0}"))]
     LeafNotFound(u32),
@@ -276,9 +285,9 @@ pub enum MlsError {
     MoreThanOneProposalForLeaf(u32),
     #[cfg_attr(
         feature = "std",
-        error("More than one GroupContextExtensions proposal")
+        error("More than one GroupContextExtensions proposal, extra one from {0:?}")
     )]
-    MoreThanOneGroupContextExtensionsProposal,
+    MoreThanOneGroupContextExtensionsProposal(ProposalSource),
     #[cfg_attr(feature = "std", error("Invalid proposal type for sender"))]
     InvalidProposalTypeForSender,
     #[cfg_attr(
@@ -298,8 +307,8 @@ pub enum MlsError {
         error("External commit contains more than one Remove proposal")
     )]
     ExternalCommitWithMoreThanOneRemove,
-    #[cfg_attr(feature = "std", error("Duplicate PSK IDs"))]
-    DuplicatePskIds,
+    #[cfg_attr(feature = "std", error("Duplicate PSK IDs, duplicate from {0:?}"))]
+    DuplicatePskIds(ProposalSource),
     #[cfg_attr(
         feature = "std",
         error("Invalid proposal type {0:?} in external commit")
@@ -338,6 +347,98 @@ pub enum MlsError {
     InvalidGroupInfo,
     #[cfg_attr(feature = "std", error("Invalid welcome message"))]
     InvalidWelcomeMessage,
+    #[cfg_attr(
+        feature = "std",
+        error("commit would grow the group beyond the maximum of {0} members")
+    )]
+    GroupSizeLimitExceeded(u32),
+    #[cfg_attr(
+        feature = "std",
+        error("welcome secrets for the current epoch are not available")
+    )]
+    WelcomeSecretsNotFound,
+    #[cfg_attr(
+        feature = "std",
+        error("epoch {0} referenced by resumption PSK not found, it may have been evicted")
+    )]
+    ResumptionEpochNotFound(u64),
+    #[cfg_attr(feature = "std", error("invalid signature on leaf node at index {0}"))]
+    InvalidLeafNodeSignature(u32),
+    #[cfg_attr(
+        feature = "std",
+        error("extension {0:?} can not be used in the group context")
+    )]
+    InvalidGroupContextExtension(ExtensionType),
+    #[cfg_attr(feature = "std", error("committer is not authorized by the MLS rules"))]
+    UnauthorizedCommitter,
+    #[cfg_attr(feature = "std", error("leaf node is not authorized by the MLS rules"))]
+    UnauthorizedLeafNode,
+    #[cfg_attr(feature = "std", error("invalid PEM encoding"))]
+    InvalidPem,
+    #[cfg_attr(feature = "std", error("lifetime ends before it starts"))]
+    InvalidLifetimeRange,
+    #[cfg_attr(
+        feature = "std",
+        error("chunk size must be positive and yield at most 2^32 chunks")
+    )]
+    InvalidChunkSize,
+    #[cfg_attr(
+        feature = "std",
+        error("duplicate or inconsistent application message chunk")
+    )]
+    InvalidApplicationMessageChunk,
+    #[cfg_attr(
+        feature = "std",
+        error("application message payload exceeds the maximum size")
+    )]
+    ApplicationMessageTooLarge,
+    #[cfg_attr(
+        feature = "std",
+        error("too many incomplete application message payloads")
+    )]
+    TooManyPendingApplicationMessages,
+    #[cfg_attr(
+        feature = "std",
+        error("signing key does not match the group info signer")
+    )]
+    GroupInfoSignerMismatch,
+    #[cfg_attr(
+        feature = "std",
+        error("welcome message is missing group secrets of an added member")
+    )]
+    WelcomeMissingGroupSecrets,
+    #[cfg_attr(
+        feature = "std",
+        error("commit has no proposals and no path update was requested")
+    )]
+    EmptyCommitNotAllowed,
+    #[cfg_attr(
+        feature = "std",
+        error("group info does not match the state of the observed group")
+    )]
+    ObservedStateMismatch,
+    #[cfg_attr(
+        feature = "std",
+        error("commit or proposal from past epoch {0} is authentic but can not be applied")
+    )]
+    PastEpochMessage(u64),
+    #[cfg_attr(
+        feature = "std",
+        error("member is not in the subtree rooted at node {0}")
+    )]
+    NotInSubtree(u32),
+    #[cfg_attr(
+        feature = "std",
+        error("number of ciphertexts does not match the resolution of node {0}")
+    )]
+    SubtreeCiphertextCountMismatch(u32),
+    #[cfg_attr(
+        feature = "std",
+        error("member {0} was not added by the commit that created the current epoch")
+    )]
+    MemberNotAddedByCommit(u32),
+    #[cfg_attr(feature = "std", error(transparent))]
+    ResumptionPskResolverError(AnyError),
 }
 
 impl IntoAnyError for MlsError {
@@ -375,6 +476,7 @@ pub struct Client<C> {
     pub(crate) config: C,
     pub(crate) signing_identity: Option<(SigningIdentity, CipherSuite)>,
     pub(crate) signer: Option<SignatureSecretKey>,
+    pub(crate) keychain: Keychain,
     pub(crate) version: ProtocolVersion,
 }
 
@@ -395,12 +497,14 @@ where
         config: C,
         signer: Option<SignatureSecretKey>,
         signing_identity: Option<(SigningIdentity, CipherSuite)>,
+        keychain: Keychain,
         version: ProtocolVersion,
     ) -> Self {
         Client {
             config,
             signer,
             signing_identity,
+            keychain,
             version,
         }
     }
@@ -411,6 +515,7 @@ where
             self.config.clone(),
             self.signer.clone(),
             self.signing_identity.clone(),
+            self.keychain.clone(),
             self.version,
         ))
     }
@@ -440,13 +545,119 @@ where
             .key_package_message())
     }
 
+    /// Creates a new key package message in the same way as
+    /// [`Client::generate_key_package_message`], for groups using `cipher_suite`.
+    ///
+    /// The key package is signed by the identity held for `cipher_suite` in the
+    /// client's [`Keychain`], or by the identity set with
+    /// [`ClientBuilder::signing_identity`] if it uses `cipher_suite`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_key_package_message_for_cipher_suite(
+        &self,
+        cipher_suite: CipherSuite,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+    ) -> Result<MlsMessage, MlsError> {
+        Ok(self
+            .generate_key_package_internal(
+                cipher_suite,
+                key_package_extensions,
+                leaf_node_extensions,
+                None,
+            )
+            .await?
+            .key_package_message())
+    }
+
+    /// Creates a new key package message in the same way as
+    /// [`Client::generate_key_package_message`], with `application_id` stored in an
+    /// [`ApplicationIdExt`] within the leaf node extensions.
+    ///
+    /// Any application id already present in `leaf_node_extensions` is replaced. The
+    /// identifier is opaque to MLS and is not interpreted during validation.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_key_package_message_with_application_id(
+        &self,
+        key_package_extensions: ExtensionList,
+        mut leaf_node_extensions: ExtensionList,
+        application_id: Vec<u8>,
+    ) -> Result<MlsMessage, MlsError> {
+        leaf_node_extensions.set_from(ApplicationIdExt::new(application_id))?;
+
+        self.generate_key_package_message(key_package_extensions, leaf_node_extensions)
+            .await
+    }
+
+    /// Creates a new last resort key package message in the same way as
+    /// [`Client::generate_key_package_message`], with a [`LastResortKeyPackageExt`]
+    /// added to `key_package_extensions`.
+    ///
+    /// The extension signals to other clients and the Delivery Service that the key
+    /// package may be used more than once. Its secret keys are kept in the
+    /// [KeyPackageStorage](crate::KeyPackageStorage) after
+    /// [joining a group](Client::join_group), whereas regular key packages are deleted.
+    #[cfg(feature = "last_resort_key_package_ext")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_last_resort_key_package_message(
+        &self,
+        mut key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+    ) -> Result<MlsMessage, MlsError> {
+        key_package_extensions.set_from(LastResortKeyPackageExt)?;
+
+        self.generate_key_package_message(key_package_extensions, leaf_node_extensions)
+            .await
+    }
+
+    /// Creates a new key package message whose HPKE key pairs are derived from `init_seed`,
+    /// as opposed to [`Client::generate_key_package_message`] which generates random keys.
+    ///
+    /// This is meant for tests requiring reproducible key packages. The resulting key package
+    /// is deterministic only if the lifetime configured for the client is, and the signature
+    /// scheme of the cipher suite is deterministic.
+    #[cfg(any(test, feature = "test_util"))]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_key_package_message_from_seed(
+        &self,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+        init_seed: &[u8],
+    ) -> Result<MlsMessage, MlsError> {
+        Ok(self
+            .generate_key_package_internal(
+                self.signing_identity()?.1,
+                key_package_extensions,
+                leaf_node_extensions,
+                Some(init_seed),
+            )
+            .await?
+            .key_package_message())
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn generate_key_package(
         &self,
         key_package_extensions: ExtensionList,
         leaf_node_extensions: ExtensionList,
     ) -> Result<KeyPackageGeneration, MlsError> {
-        let (signing_identity, cipher_suite) = self.signing_identity()?;
+        self.generate_key_package_internal(
+            self.signing_identity()?.1,
+            key_package_extensions,
+            leaf_node_extensions,
+            None,
+        )
+        .await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn generate_key_package_internal(
+        &self,
+        cipher_suite: CipherSuite,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+        init_seed: Option<&[u8]>,
+    ) -> Result<KeyPackageGeneration, MlsError> {
+        let (signing_identity, signing_key) = self.identity_for_cipher_suite(cipher_suite)?;
 
         let cipher_suite_provider = self
             .config
@@ -457,18 +668,36 @@ where
         let key_package_generator = KeyPackageGenerator {
             protocol_version: self.version,
             cipher_suite_provider: &cipher_suite_provider,
-            signing_key: self.signer()?,
+            signing_key,
             signing_identity,
         };
 
-        let key_pkg_gen = key_package_generator
-            .generate(
-                self.config.lifetime(),
-                self.config.capabilities(),
-                key_package_extensions,
-                leaf_node_extensions,
-            )
-            .await?;
+        let lifetime = self.config.lifetime();
+        let capabilities = self.config.capabilities();
+
+        let key_pkg_gen = match init_seed {
+            Some(init_seed) => {
+                key_package_generator
+                    .generate_from_seed(
+                        lifetime,
+                        capabilities,
+                        key_package_extensions,
+                        leaf_node_extensions,
+                        init_seed,
+                    )
+                    .await?
+            }
+            None => {
+                key_package_generator
+                    .generate(
+                        lifetime,
+                        capabilities,
+                        key_package_extensions,
+                        leaf_node_extensions,
+                    )
+                    .await?
+            }
+        };
 
         let (id, key_package_data) = key_pkg_gen.to_storage()?;
 
@@ -559,7 +788,30 @@ where
             welcome_message,
             tree_data,
             self.config.clone(),
-            self.signer()?.clone(),
+            |identity| self.signer_for_identity(identity).cloned(),
+            None,
+        )
+        .await
+    }
+
+    /// Join a MLS group via a welcome message bound to `welcome_aad`.
+    ///
+    /// This is the same as [`Client::join_group`] for welcome messages created
+    /// by a commit with [`CommitBuilder::welcome_aad`](crate::group::CommitBuilder::welcome_aad).
+    /// Joining fails if `welcome_aad` does not match the value used by the committer.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn join_group_with_welcome_aad(
+        &self,
+        tree_data: Option<ExportedTree<'_>>,
+        welcome_message: &MlsMessage,
+        welcome_aad: &[u8],
+    ) -> Result<(Group<C>, NewMemberInfo), MlsError> {
+        Group::join(
+            welcome_message,
+            tree_data,
+            self.config.clone(),
+            |identity| self.signer_for_identity(identity).cloned(),
+            Some(welcome_aad),
         )
         .await
     }
@@ -650,6 +902,22 @@ where
         .await
     }
 
+    /// Start building a new group with group context extensions set at epoch 0.
+    ///
+    /// This is a more flexible alternative to [`Client::create_group`] and
+    /// [`Client::create_group_with_id`].
+    pub fn group_builder(&self) -> Result<GroupBuilder<C>, MlsError> {
+        let (signing_identity, cipher_suite) = self.signing_identity()?;
+
+        Ok(GroupBuilder::new(
+            self.config.clone(),
+            self.signer()?.clone(),
+            signing_identity.clone(),
+            cipher_suite,
+            self.version,
+        ))
+    }
+
     pub fn external_commit_builder(&self) -> Result<ExternalCommitBuilder<C>, MlsError> {
         Ok(ExternalCommitBuilder::new(
             self.signer()?.clone(),
@@ -720,13 +988,16 @@ where
         .await?;
 
         let key_package = self
-            .generate_key_package(key_package_extensions, leaf_node_extensions)
+            .generate_key_package_internal(
+                cipher_suite,
+                key_package_extensions,
+                leaf_node_extensions,
+                None,
+            )
             .await?
             .key_package;
 
-        (key_package.cipher_suite == cipher_suite)
-            .then_some(())
-            .ok_or(MlsError::UnsupportedCipherSuite(cipher_suite))?;
+        let (_, signer) = self.identity_for_cipher_suite(cipher_suite)?;
 
         let message = AuthenticatedContent::new_signed(
             &cipher_suite_provider,
@@ -735,7 +1006,7 @@ where
             Content::Proposal(Box::new(Proposal::Add(Box::new(AddProposal {
                 key_package,
             })))),
-            self.signer()?,
+            signer,
             WireFormat::PublicMessage,
             authenticated_data,
         )
@@ -757,6 +1028,43 @@ where
         self.signer.as_ref().ok_or(MlsError::SignerNotFound)
     }
 
+    fn identity_for_cipher_suite(
+        &self,
+        cipher_suite: CipherSuite,
+    ) -> Result<(&SigningIdentity, &SignatureSecretKey), MlsError> {
+        if let Some(identity) = self.keychain.get(cipher_suite) {
+            return Ok(identity);
+        }
+
+        match self.signing_identity()? {
+            (signing_identity, cs) if cs == cipher_suite => Ok((signing_identity, self.signer()?)),
+            _ => Err(MlsError::SignerNotFound),
+        }
+    }
+
+    fn signer_for_identity(
+        &self,
+        signing_identity: &SigningIdentity,
+    ) -> Result<&SignatureSecretKey, MlsError> {
+        match self.keychain.signer_for_identity(signing_identity) {
+            Some(signer) => Ok(signer),
+            None => self.signer(),
+        }
+    }
+
+    /// Cipher suites that the configured [`CryptoProvider`] can be used with.
+    ///
+    /// Groups and key packages can only be created for these cipher suites.
+    pub fn supported_cipher_suites(&self) -> Vec<CipherSuite> {
+        self.config.crypto_provider().supported_cipher_suites()
+    }
+
+    /// The [`Keychain`] holding signing identities for specific cipher suites.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn keychain(&self) -> &Keychain {
+        &self.keychain
+    }
+
     #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
     pub fn signing_identity(&self) -> Result<(&SigningIdentity, CipherSuite), MlsError> {
         self.signing_identity
@@ -856,9 +1164,13 @@ mod tests {
 
     use super::*;
     use crate::{
-        crypto::test_utils::TestCryptoProvider,
+        crypto::test_utils::{test_cipher_suite_provider, TestCryptoProvider},
+        identity::basic::BasicIdentityProvider,
         identity::test_utils::{get_test_basic_credential, get_test_signing_identity},
+        storage_provider::in_memory::InMemoryGroupStateStorage,
+        time::MlsTime,
         tree_kem::leaf_node::LeafNodeSource,
+        KeyPackage,
     };
     use assert_matches::assert_matches;
 
@@ -917,6 +1229,343 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn application_id_round_trips_through_key_package() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let key_package = client
+            .generate_key_package_message_with_application_id(
+                Default::default(),
+                Default::default(),
+                b"app id".to_vec(),
+            )
+            .await
+            .unwrap();
+
+        let key_package = MlsMessage::from_bytes(&key_package.to_bytes().unwrap())
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        assert_eq!(
+            key_package.leaf_node.application_id().unwrap(),
+            Some(ApplicationIdExt::new(b"app id".to_vec()))
+        );
+
+        let time = MlsTime::from(key_package.expiration().unwrap());
+
+        key_package
+            .validate_with_time(&test_cipher_suite_provider(TEST_CIPHER_SUITE), time)
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn keychain_identity_is_used_for_each_cipher_suite() {
+        let cipher_suites = [CipherSuite::P256_AES128, CipherSuite::CURVE25519_AES128];
+        let mut keychain = Keychain::new();
+        let mut identities = Vec::new();
+
+        for cipher_suite in cipher_suites {
+            let (identity, secret_key) = get_test_signing_identity(cipher_suite, b"bob").await;
+            keychain.insert(identity.clone(), secret_key, cipher_suite);
+            identities.push(identity);
+        }
+
+        let bob = TestClientBuilder::new_for_test().keychain(keychain).build();
+
+        for (cipher_suite, identity) in cipher_suites.into_iter().zip(identities) {
+            let key_package = bob
+                .generate_key_package_message_for_cipher_suite(
+                    cipher_suite,
+                    Default::default(),
+                    Default::default(),
+                )
+                .await
+                .unwrap();
+
+            let kp = key_package.as_key_package().unwrap();
+            assert_eq!(kp.cipher_suite, cipher_suite);
+            assert_eq!(kp.leaf_node.signing_identity, identity);
+
+            let mut alice = test_group(TEST_PROTOCOL_VERSION, cipher_suite).await;
+
+            let commit = alice
+                .commit_builder()
+                .add_member(key_package)
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+
+            alice.apply_pending_commit().await.unwrap();
+
+            let (mut bob_group, _) = bob
+                .join_group(None, &commit.welcome_messages[0])
+                .await
+                .unwrap();
+
+            assert_eq!(
+                bob_group.current_member_signing_identity().unwrap(),
+                &identity
+            );
+
+            // Bob signs with the key of the group's cipher suite
+            let update = bob_group.commit(vec![]).await.unwrap();
+            bob_group.apply_pending_commit().await.unwrap();
+
+            alice
+                .process_incoming_message(update.commit_message)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn join_uses_signer_of_key_package_identity_after_rotation() {
+        let (old_identity, old_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let mut keychain = Keychain::new();
+        keychain.insert(old_identity.clone(), old_secret_key, TEST_CIPHER_SUITE);
+
+        let mut bob = TestClientBuilder::new_for_test().keychain(keychain).build();
+
+        let key_package = bob
+            .generate_key_package_message_for_cipher_suite(
+                TEST_CIPHER_SUITE,
+                Default::default(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        // Rotate the identity used for the cipher suite before the welcome arrives
+        let (new_identity, new_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        bob.keychain
+            .insert(new_identity, new_secret_key, TEST_CIPHER_SUITE);
+
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let (mut bob_group, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            bob_group.current_member_signing_identity().unwrap(),
+            &old_identity
+        );
+
+        // Bob signs with the key of the identity in the key package
+        let update = bob_group.commit(vec![]).await.unwrap();
+        bob_group.apply_pending_commit().await.unwrap();
+
+        alice
+            .process_incoming_message(update.commit_message)
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_can_move_to_rotated_keychain_identity() {
+        let (old_identity, old_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let mut keychain = Keychain::new();
+        keychain.insert(old_identity, old_secret_key, TEST_CIPHER_SUITE);
+
+        let mut bob = TestClientBuilder::new_for_test().keychain(keychain).build();
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let key_package = bob
+            .generate_key_package_message_for_cipher_suite(
+                TEST_CIPHER_SUITE,
+                Default::default(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        let commit = alice
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let (mut bob_group, _) = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+
+        let (new_identity, new_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        bob.keychain
+            .insert(new_identity.clone(), new_secret_key, TEST_CIPHER_SUITE);
+
+        let signer = bob
+            .keychain()
+            .signer_for_identity(&new_identity)
+            .unwrap()
+            .clone();
+
+        let update = bob_group
+            .commit_builder()
+            .set_new_signing_identity(signer, new_identity.clone())
+            .build()
+            .await
+            .unwrap();
+
+        bob_group.apply_pending_commit().await.unwrap();
+
+        alice
+            .process_incoming_message(update.commit_message)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            bob_group.current_member_signing_identity().unwrap(),
+            &new_identity
+        );
+
+        // Later messages are signed with the new key
+        let commit = bob_group.commit(vec![]).await.unwrap();
+        bob_group.apply_pending_commit().await.unwrap();
+
+        alice
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn supported_cipher_suites_are_those_of_the_crypto_provider() {
+        let client = TestClientBuilder::new_for_test().build();
+
+        assert_eq!(
+            client.supported_cipher_suites(),
+            TestCryptoProvider::new().supported_cipher_suites()
+        );
+
+        let enabled = vec![CipherSuite::CURVE25519_AES128, CipherSuite::P256_AES128];
+
+        let client = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::with_enabled_cipher_suites(
+                enabled.clone(),
+            ))
+            .identity_provider(BasicIdentityProvider::new())
+            .build();
+
+        assert_eq!(client.supported_cipher_suites(), enabled);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_for_cipher_suite_without_identity_fails() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let client = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let res = client
+            .generate_key_package_message_for_cipher_suite(
+                CipherSuite::CURVE25519_AES128,
+                Default::default(),
+                Default::default(),
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::SignerNotFound));
+    }
+
+    #[cfg(feature = "last_resort_key_package_ext")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn last_resort_key_package_carries_extension_and_is_kept() {
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob = TestClientBuilder::new_for_test()
+            .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let key_package = bob
+            .generate_last_resort_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let key_package = MlsMessage::from_bytes(&key_package.to_bytes().unwrap()).unwrap();
+
+        let extension = key_package
+            .clone()
+            .into_key_package()
+            .unwrap()
+            .extensions
+            .get_as::<LastResortKeyPackageExt>()
+            .unwrap();
+
+        assert_eq!(extension, Some(LastResortKeyPackageExt));
+
+        // The same key package can be used to join two groups.
+        for _ in 0..2 {
+            let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+            let welcome = alice
+                .commit_builder()
+                .add_member(key_package.clone())
+                .unwrap()
+                .build()
+                .await
+                .unwrap()
+                .welcome_messages
+                .remove(0);
+
+            let (mut bob_group, _) = bob.join_group(None, &welcome).await.unwrap();
+            bob_group.write_to_storage().await.unwrap();
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn generated_key_package_is_stored() {
+        let (client, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let bytes = key_package.to_bytes().unwrap();
+
+        let key_package = MlsMessage::from_bytes(&bytes)
+            .unwrap()
+            .into_key_package()
+            .unwrap();
+
+        let key_package_ref = key_package
+            .to_reference(&test_cipher_suite_provider(TEST_CIPHER_SUITE))
+            .await
+            .unwrap();
+
+        let stored = client.key_package_store().get(&key_package_ref).unwrap();
+        let stored_key_package = KeyPackage::mls_decode(&mut &*stored.key_package_bytes).unwrap();
+        assert_eq!(stored_key_package, key_package);
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn new_member_add_proposal_adds_to_group() {
@@ -961,6 +1610,148 @@ mod tests {
             .any(|member| member.signing_identity == bob_identity))
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn new_member_add_proposal_lets_new_member_join() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let (carol_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"carol").await;
+
+        let carol = TestClientBuilder::new_for_test()
+            .signing_identity(carol_identity.clone(), secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let proposal = carol
+            .external_add_proposal(
+                &alice_group.group_info_message(false).await.unwrap(),
+                Some(alice_group.export_tree()),
+                vec![],
+                Default::default(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        alice_group
+            .process_incoming_message(proposal.clone())
+            .await
+            .unwrap();
+
+        bob_group.process_incoming_message(proposal).await.unwrap();
+
+        // Any member can commit the proposal
+        let commit_output = bob_group.commit(vec![]).await.unwrap();
+        bob_group.apply_pending_commit().await.unwrap();
+
+        alice_group
+            .process_incoming_message(commit_output.commit_message)
+            .await
+            .unwrap();
+
+        let (carol_group, _) = carol
+            .join_group(None, &commit_output.welcome_messages[0])
+            .await
+            .unwrap();
+
+        assert_eq!(carol_group.context(), alice_group.context());
+        assert_eq!(carol_group.context(), bob_group.context());
+
+        let carol_member = carol_group
+            .member_at_index(carol_group.current_member_index())
+            .unwrap();
+
+        assert_eq!(carol_member.signing_identity, carol_identity);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn secure_defaults_update_path_and_keep_storage() {
+        let secure_client = |name: &'static str| async move {
+            let (identity, secret_key) =
+                get_test_signing_identity(TEST_CIPHER_SUITE, name.as_bytes()).await;
+
+            let group_state_storage = InMemoryGroupStateStorage::new()
+                .with_max_epoch_retention(1)
+                .unwrap();
+
+            TestClientBuilder::new_for_test()
+                .group_state_storage(group_state_storage)
+                .secure_defaults()
+                .signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+                .build()
+        };
+
+        let alice = secure_client("alice").await;
+        let bob = secure_client("bob").await;
+
+        let mut alice_group = alice
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let alice_key = alice_group
+            .current_user_leaf_node()
+            .unwrap()
+            .public_key
+            .clone();
+
+        let bob_key_package = bob
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let commit_output = alice_group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        // A commit containing only an add still updates the committer's keys
+        let new_alice_key = alice_group
+            .current_user_leaf_node()
+            .unwrap()
+            .public_key
+            .clone();
+        assert_ne!(alice_key, new_alice_key);
+
+        let (mut bob_group, _) = bob
+            .join_group(None, &commit_output.welcome_messages[0])
+            .await
+            .unwrap();
+
+        for _ in 0..3 {
+            let commit = alice_group.commit(vec![]).await.unwrap().commit_message;
+
+            #[cfg(feature = "private_message")]
+            assert_eq!(commit.wire_format(), WireFormat::PrivateMessage);
+
+            alice_group.apply_pending_commit().await.unwrap();
+            bob_group.process_incoming_message(commit).await.unwrap();
+        }
+
+        alice_group.write_to_storage().await.unwrap();
+
+        // The storage configured before `secure_defaults` keeps its retention limit. Prior
+        // epochs are only stored with the `prior_epoch` feature.
+        #[cfg(feature = "prior_epoch")]
+        {
+            let storage = alice.group_state_storage();
+
+            #[cfg(feature = "std")]
+            let lock = storage.inner.lock().unwrap();
+            #[cfg(not(feature = "std"))]
+            let lock = storage.inner.lock();
+
+            let stored = lock.get(alice_group.group_id()).unwrap();
+            assert_eq!(stored.epoch_data.len(), 1);
+        }
+    }
+
     #[cfg(feature = "psk")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn join_via_external_commit(do_remove: bool, with_psk: bool) -> Result<(), MlsError> {
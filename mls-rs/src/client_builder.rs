@@ -9,7 +9,7 @@
 use crate::{
     cipher_suite::CipherSuite,
     client::Client,
-    client_config::ClientConfig,
+    client_config::{ClientConfig, SharedGroupObserver},
     extension::ExtensionType,
     group::{
         mls_rules::{DefaultMlsRules, MlsRules},
@@ -17,8 +17,9 @@ use crate::{
     },
     identity::CredentialType,
     identity::SigningIdentity,
+    keychain::Keychain,
     protocol_version::ProtocolVersion,
-    psk::{ExternalPskId, PreSharedKey},
+    psk::{ExternalPskId, NoResumptionPskResolver, PreSharedKey, ResumptionPskResolver},
     storage_provider::in_memory::{
         InMemoryGroupStateStorage, InMemoryKeyPackageStorage, InMemoryPreSharedKeyStorage,
     },
@@ -29,7 +30,19 @@ use crate::{
 #[cfg(feature = "std")]
 use crate::time::MlsTime;
 
+use crate::group::GroupObserver;
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use portable_atomic_util::Arc;
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use alloc::boxed::Box;
+
 use alloc::vec::Vec;
+use core::fmt::{self, Debug};
 
 #[cfg(feature = "sqlite")]
 use mls_rs_provider_sqlite::{
@@ -51,6 +64,7 @@ pub type BaseConfig = Config<
     Missing,
     DefaultMlsRules,
     Missing,
+    NoResumptionPskResolver,
 >;
 
 /// Base client configuration type when instantiating `ClientBuilder`
@@ -61,9 +75,11 @@ pub type BaseInMemoryConfig = Config<
     Missing,
     Missing,
     Missing,
+    NoResumptionPskResolver,
 >;
 
-pub type EmptyConfig = Config<Missing, Missing, Missing, Missing, Missing, Missing>;
+pub type EmptyConfig =
+    Config<Missing, Missing, Missing, Missing, Missing, Missing, NoResumptionPskResolver>;
 
 /// Base client configuration that is backed by SQLite storage.
 #[cfg(feature = "sqlite")]
@@ -74,6 +90,7 @@ pub type BaseSqlConfig = Config<
     Missing,
     DefaultMlsRules,
     Missing,
+    NoResumptionPskResolver,
 >;
 
 /// Builder for [`Client`]
@@ -200,8 +217,10 @@ impl ClientBuilder<BaseConfig> {
             identity_provider: Missing,
             mls_rules: DefaultMlsRules::new(),
             crypto_provider: Missing,
+            resumption_psk_resolver: NoResumptionPskResolver,
             signer: Default::default(),
             signing_identity: Default::default(),
+            keychain: Default::default(),
             version: ProtocolVersion::MLS_10,
         }))
     }
@@ -217,8 +236,10 @@ impl ClientBuilder<EmptyConfig> {
             identity_provider: Missing,
             mls_rules: Missing,
             crypto_provider: Missing,
+            resumption_psk_resolver: NoResumptionPskResolver,
             signer: Default::default(),
             signing_identity: Default::default(),
+            keychain: Default::default(),
             version: ProtocolVersion::MLS_10,
         }))
     }
@@ -238,8 +259,10 @@ impl ClientBuilder<BaseSqlConfig> {
             identity_provider: Missing,
             mls_rules: DefaultMlsRules::new(),
             crypto_provider: Missing,
+            resumption_psk_resolver: NoResumptionPskResolver,
             signer: Default::default(),
             signing_identity: Default::default(),
+            keychain: Default::default(),
             version: ProtocolVersion::MLS_10,
         })))
     }
@@ -304,6 +327,92 @@ impl<C: IntoConfig> ClientBuilder<C> {
         ClientBuilder(c)
     }
 
+    /// Set the maximum number of members a group may contain.
+    ///
+    /// Commits that would grow a group beyond `max_members`, whether created
+    /// or received by this client, are rejected with
+    /// [`MlsError::GroupSizeLimitExceeded`](crate::client::MlsError::GroupSizeLimitExceeded).
+    /// By default, there is no limit.
+    pub fn max_group_size(self, max_members: u32) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.max_group_size = Some(max_members);
+        ClientBuilder(c)
+    }
+
+    /// Write groups of this client to the configured
+    /// [`GroupStateStorage`] whenever they are
+    /// created, joined or move to a new epoch.
+    ///
+    /// The new epoch is written before it replaces the group's state in memory,
+    /// i.e. before
+    /// [`Group::apply_pending_commit`](crate::Group::apply_pending_commit) or
+    /// [`Group::process_incoming_message`](crate::Group::process_incoming_message)
+    /// return. If writing fails, the error is returned and both the group and
+    /// storage remain at the prior epoch. If the process stops before the write
+    /// completes, the group can be loaded with
+    /// [`Client::load_group`](crate::Client::load_group) and the commit
+    /// processed again.
+    ///
+    /// By default, state is only written by
+    /// [`Group::write_to_storage`](crate::Group::write_to_storage).
+    pub fn persist_on_epoch_change(self, enabled: bool) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.persist_on_epoch_change = enabled;
+        ClientBuilder(c)
+    }
+
+    /// Set the number of past epochs that commits and proposals are still
+    /// validated against.
+    ///
+    /// A commit or proposal sent up to `epochs` epochs before the current one,
+    /// e.g. because it lost a race against another commit, is authenticated
+    /// using the state retained for its epoch and then rejected with
+    /// [`MlsError::PastEpochMessage`](crate::client::MlsError::PastEpochMessage).
+    /// Such a message is never applied. Older messages, and messages for
+    /// epochs that are no longer retained by the
+    /// [`GroupStateStorage`], are rejected without validation.
+    ///
+    /// By default, only messages from the current epoch are accepted.
+    #[cfg(feature = "prior_epoch")]
+    pub fn commit_lookback(self, epochs: u64) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.settings.commit_lookback = epochs;
+        ClientBuilder(c)
+    }
+
+    /// Set the observer notified whenever a group of this client moves to a new epoch.
+    ///
+    /// By default, no observer is set.
+    pub fn group_observer<O>(self, observer: O) -> ClientBuilder<IntoConfigOutput<C>>
+    where
+        O: GroupObserver + 'static,
+    {
+        #[cfg(target_has_atomic = "ptr")]
+        let observer: SharedGroupObserver = Arc::new(observer);
+
+        #[cfg(not(target_has_atomic = "ptr"))]
+        let observer: SharedGroupObserver = Arc::new(Box::new(observer));
+
+        let mut c = self.0.into_config();
+        c.0.settings.group_observer = Some(observer);
+        ClientBuilder(c)
+    }
+
+    /// Apply settings favoring forward secrecy over performance and tolerance
+    /// to message reordering.
+    ///
+    /// This sets the [MLS rules](ClientBuilder::mls_rules) to
+    /// [`DefaultMlsRules::secure_defaults`], forcing an update path in every commit
+    /// and encrypting and padding control messages. All other settings are kept.
+    ///
+    /// The number of prior epochs retained for decrypting late messages is a
+    /// property of the [group state storage](ClientBuilder::group_state_storage).
+    /// For the default in-memory storage, it can be limited with
+    /// [`InMemoryGroupStateStorage::with_max_epoch_retention`].
+    pub fn secure_defaults(self) -> ClientBuilder<WithMlsRules<DefaultMlsRules, C>> {
+        self.mls_rules(DefaultMlsRules::secure_defaults())
+    }
+
     /// Set the key package repository to be used by the client.
     ///
     /// By default, an in-memory repository is used.
@@ -321,8 +430,10 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            resumption_psk_resolver: c.resumption_psk_resolver,
             signer: c.signer,
             signing_identity: c.signing_identity,
+            keychain: c.keychain,
             version: c.version,
         }))
     }
@@ -344,8 +455,10 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            resumption_psk_resolver: c.resumption_psk_resolver,
             signer: c.signer,
             signing_identity: c.signing_identity,
+            keychain: c.keychain,
             version: c.version,
         }))
     }
@@ -370,8 +483,10 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             crypto_provider: c.crypto_provider,
             mls_rules: c.mls_rules,
+            resumption_psk_resolver: c.resumption_psk_resolver,
             signer: c.signer,
             signing_identity: c.signing_identity,
+            keychain: c.keychain,
             version: c.version,
         }))
     }
@@ -394,8 +509,10 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider: c.crypto_provider,
+            resumption_psk_resolver: c.resumption_psk_resolver,
             signer: c.signer,
             signing_identity: c.signing_identity,
+            keychain: c.keychain,
             version: c.version,
         }))
     }
@@ -418,8 +535,10 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules: c.mls_rules,
             crypto_provider,
+            resumption_psk_resolver: c.resumption_psk_resolver,
             signer: c.signer,
             signing_identity: c.signing_identity,
+            keychain: c.keychain,
             version: c.version,
         }))
     }
@@ -445,8 +564,41 @@ impl<C: IntoConfig> ClientBuilder<C> {
             identity_provider: c.identity_provider,
             mls_rules,
             crypto_provider: c.crypto_provider,
+            resumption_psk_resolver: c.resumption_psk_resolver,
             signer: c.signer,
             signing_identity: c.signing_identity,
+            keychain: c.keychain,
+            version: c.version,
+        }))
+    }
+
+    /// Set the resolver providing resumption secrets of epochs referenced by resumption
+    /// PSKs, e.g. when branching or reinitializing a group.
+    ///
+    /// The resolver is only asked for epochs that are not found in the
+    /// [group state storage](ClientBuilder::group_state_storage). By default, no
+    /// resolver is used.
+    pub fn resumption_psk_resolver<R>(
+        self,
+        resumption_psk_resolver: R,
+    ) -> ClientBuilder<WithResumptionPskResolver<R, C>>
+    where
+        R: ResumptionPskResolver,
+    {
+        let Config(c) = self.0.into_config();
+
+        ClientBuilder(Config(ConfigInner {
+            settings: c.settings,
+            key_package_repo: c.key_package_repo,
+            psk_store: c.psk_store,
+            group_state_storage: c.group_state_storage,
+            identity_provider: c.identity_provider,
+            mls_rules: c.mls_rules,
+            crypto_provider: c.crypto_provider,
+            resumption_psk_resolver,
+            signer: c.signer,
+            signing_identity: c.signing_identity,
+            keychain: c.keychain,
             version: c.version,
         }))
     }
@@ -475,6 +627,16 @@ impl<C: IntoConfig> ClientBuilder<C> {
         ClientBuilder(c)
     }
 
+    /// Set the keychain holding signing identities used for specific cipher suites.
+    ///
+    /// For a cipher suite present in `keychain`, its identity is used instead of the one
+    /// set with [`ClientBuilder::signing_identity`].
+    pub fn keychain(self, keychain: Keychain) -> ClientBuilder<IntoConfigOutput<C>> {
+        let mut c = self.0.into_config();
+        c.0.keychain = keychain;
+        ClientBuilder(c)
+    }
+
     /// Set the signer used by the client. This must be called in order to join groups.
     pub fn signer(self, signer: SignatureSecretKey) -> ClientBuilder<IntoConfigOutput<C>> {
         let mut c = self.0.into_config();
@@ -501,6 +663,7 @@ where
     C::IdentityProvider: IdentityProvider + Clone,
     C::MlsRules: MlsRules + Clone,
     C::CryptoProvider: CryptoProvider + Clone,
+    C::ResumptionPskResolver: ResumptionPskResolver + Clone,
 {
     pub(crate) fn build_config(self) -> IntoConfigOutput<C> {
         let mut c = self.0.into_config();
@@ -521,8 +684,9 @@ where
         let version = c.0.version;
         let signer = c.0.signer.take();
         let signing_identity = c.0.signing_identity.take();
+        let keychain = core::mem::take(&mut c.0.keychain);
 
-        Client::new(c, signer, signing_identity, version)
+        Client::new(c, signer, signing_identity, keychain, version)
     }
 }
 
@@ -553,6 +717,7 @@ pub type WithKeyPackageRepo<K, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::ResumptionPskResolver,
 >;
 
 /// Change the PSK store used by a client configuration.
@@ -565,6 +730,7 @@ pub type WithPskStore<P, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::ResumptionPskResolver,
 >;
 
 /// Change the group state storage used by a client configuration.
@@ -577,6 +743,7 @@ pub type WithGroupStateStorage<G, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::ResumptionPskResolver,
 >;
 
 /// Change the identity validator used by a client configuration.
@@ -589,6 +756,7 @@ pub type WithIdentityProvider<I, C> = Config<
     I,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::ResumptionPskResolver,
 >;
 
 /// Change the proposal rules used by a client configuration.
@@ -601,6 +769,7 @@ pub type WithMlsRules<Pr, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     Pr,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::ResumptionPskResolver,
 >;
 
 /// Change the crypto provider used by a client configuration.
@@ -613,6 +782,20 @@ pub type WithCryptoProvider<Cp, C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     Cp,
+    <C as IntoConfig>::ResumptionPskResolver,
+>;
+
+/// Change the resumption PSK resolver used by a client configuration.
+///
+/// See [`ClientBuilder::resumption_psk_resolver`].
+pub type WithResumptionPskResolver<Rr, C> = Config<
+    <C as IntoConfig>::KeyPackageRepository,
+    <C as IntoConfig>::PskStore,
+    <C as IntoConfig>::GroupStateStorage,
+    <C as IntoConfig>::IdentityProvider,
+    <C as IntoConfig>::MlsRules,
+    <C as IntoConfig>::CryptoProvider,
+    Rr,
 >;
 
 /// Helper alias for `Config`.
@@ -623,6 +806,7 @@ pub type IntoConfigOutput<C> = Config<
     <C as IntoConfig>::IdentityProvider,
     <C as IntoConfig>::MlsRules,
     <C as IntoConfig>::CryptoProvider,
+    <C as IntoConfig>::ResumptionPskResolver,
 >;
 
 /// Helper alias to make a `Config` from a `ClientConfig`
@@ -633,9 +817,10 @@ pub type MakeConfig<C> = Config<
     <C as ClientConfig>::IdentityProvider,
     <C as ClientConfig>::MlsRules,
     <C as ClientConfig>::CryptoProvider,
+    <C as ClientConfig>::ResumptionPskResolver,
 >;
 
-impl<Kpr, Ps, Gss, Ip, Pr, Cp> ClientConfig for ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp>
+impl<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> ClientConfig for ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Rr>
 where
     Kpr: KeyPackageStorage + Clone,
     Ps: PreSharedKeyStorage + Clone,
@@ -643,6 +828,7 @@ where
     Ip: IdentityProvider + Clone,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
+    Rr: ResumptionPskResolver + Clone,
 {
     type KeyPackageRepository = Kpr;
     type PskStore = Ps;
@@ -650,6 +836,7 @@ where
     type IdentityProvider = Ip;
     type MlsRules = Pr;
     type CryptoProvider = Cp;
+    type ResumptionPskResolver = Rr;
 
     fn supported_extensions(&self) -> Vec<ExtensionType> {
         self.settings.extension_types.clone()
@@ -683,6 +870,10 @@ where
         self.crypto_provider.clone()
     }
 
+    fn resumption_psk_resolver(&self) -> Self::ResumptionPskResolver {
+        self.resumption_psk_resolver.clone()
+    }
+
     fn lifetime(&self) -> Lifetime {
         #[cfg(feature = "std")]
         let now_timestamp = MlsTime::now().seconds_since_epoch();
@@ -705,11 +896,28 @@ where
     fn supported_custom_proposals(&self) -> Vec<crate::group::proposal::ProposalType> {
         self.settings.custom_proposal_types.clone()
     }
+
+    fn max_group_size(&self) -> Option<u32> {
+        self.settings.max_group_size
+    }
+
+    fn persist_on_epoch_change(&self) -> bool {
+        self.settings.persist_on_epoch_change
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    fn commit_lookback(&self) -> u64 {
+        self.settings.commit_lookback
+    }
+
+    fn group_observer(&self) -> Option<SharedGroupObserver> {
+        self.settings.group_observer.clone()
+    }
 }
 
-impl<Kpr, Ps, Gss, Ip, Pr, Cp> Sealed for Config<Kpr, Ps, Gss, Ip, Pr, Cp> {}
+impl<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> Sealed for Config<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> {}
 
-impl<Kpr, Ps, Gss, Ip, Pr, Cp> MlsConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp>
+impl<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> MlsConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp, Rr>
 where
     Kpr: KeyPackageStorage + Clone,
 
@@ -718,8 +926,9 @@ where
     Ip: IdentityProvider + Clone,
     Pr: MlsRules + Clone,
     Cp: CryptoProvider + Clone,
+    Rr: ResumptionPskResolver + Clone,
 {
-    type Output = ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp>;
+    type Output = ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Rr>;
 
     fn get(&self) -> &Self::Output {
         &self.0
@@ -745,6 +954,7 @@ impl<T: MlsConfig> ClientConfig for T {
     type IdentityProvider = <T::Output as ClientConfig>::IdentityProvider;
     type MlsRules = <T::Output as ClientConfig>::MlsRules;
     type CryptoProvider = <T::Output as ClientConfig>::CryptoProvider;
+    type ResumptionPskResolver = <T::Output as ClientConfig>::ResumptionPskResolver;
 
     fn supported_extensions(&self) -> Vec<ExtensionType> {
         self.get().supported_extensions()
@@ -782,6 +992,10 @@ impl<T: MlsConfig> ClientConfig for T {
         self.get().crypto_provider()
     }
 
+    fn resumption_psk_resolver(&self) -> Self::ResumptionPskResolver {
+        self.get().resumption_psk_resolver()
+    }
+
     fn lifetime(&self) -> Lifetime {
         self.get().lifetime()
     }
@@ -797,18 +1011,62 @@ impl<T: MlsConfig> ClientConfig for T {
     fn supported_credential_types(&self) -> Vec<CredentialType> {
         self.get().supported_credential_types()
     }
+
+    fn max_group_size(&self) -> Option<u32> {
+        self.get().max_group_size()
+    }
+
+    fn persist_on_epoch_change(&self) -> bool {
+        self.get().persist_on_epoch_change()
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    fn commit_lookback(&self) -> u64 {
+        self.get().commit_lookback()
+    }
+
+    fn group_observer(&self) -> Option<SharedGroupObserver> {
+        self.get().group_observer()
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub(crate) struct Settings {
     pub(crate) extension_types: Vec<ExtensionType>,
     pub(crate) protocol_versions: Vec<ProtocolVersion>,
     pub(crate) custom_proposal_types: Vec<ProposalType>,
     pub(crate) lifetime_in_s: u64,
+    pub(crate) max_group_size: Option<u32>,
+    pub(crate) persist_on_epoch_change: bool,
+    #[cfg(feature = "prior_epoch")]
+    pub(crate) commit_lookback: u64,
+    pub(crate) group_observer: Option<SharedGroupObserver>,
     #[cfg(any(test, feature = "test_util"))]
     pub(crate) key_package_not_before: Option<u64>,
 }
 
+impl Debug for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Settings");
+
+        d.field("extension_types", &self.extension_types)
+            .field("protocol_versions", &self.protocol_versions)
+            .field("custom_proposal_types", &self.custom_proposal_types)
+            .field("lifetime_in_s", &self.lifetime_in_s)
+            .field("max_group_size", &self.max_group_size)
+            .field("persist_on_epoch_change", &self.persist_on_epoch_change)
+            .field("group_observer", &self.group_observer.is_some());
+
+        #[cfg(feature = "prior_epoch")]
+        d.field("commit_lookback", &self.commit_lookback);
+
+        #[cfg(any(test, feature = "test_util"))]
+        d.field("key_package_not_before", &self.key_package_not_before);
+
+        d.finish()
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -816,6 +1074,11 @@ impl Default for Settings {
             protocol_versions: Default::default(),
             lifetime_in_s: 365 * 24 * 3600,
             custom_proposal_types: Default::default(),
+            max_group_size: None,
+            persist_on_epoch_change: false,
+            #[cfg(feature = "prior_epoch")]
+            commit_lookback: 0,
+            group_observer: None,
             #[cfg(any(test, feature = "test_util"))]
             key_package_not_before: None,
         }
@@ -826,6 +1089,7 @@ pub(crate) fn recreate_config<T: ClientConfig>(
     c: T,
     signer: Option<SignatureSecretKey>,
     signing_identity: Option<(SigningIdentity, CipherSuite)>,
+    keychain: Keychain,
     version: ProtocolVersion,
 ) -> MakeConfig<T> {
     Config(ConfigInner {
@@ -837,6 +1101,11 @@ pub(crate) fn recreate_config<T: ClientConfig>(
                 let l = c.lifetime();
                 l.not_after - l.not_before
             },
+            max_group_size: c.max_group_size(),
+            persist_on_epoch_change: c.persist_on_epoch_change(),
+            #[cfg(feature = "prior_epoch")]
+            commit_lookback: c.commit_lookback(),
+            group_observer: c.group_observer(),
             #[cfg(any(test, feature = "test_util"))]
             key_package_not_before: None,
         },
@@ -846,8 +1115,10 @@ pub(crate) fn recreate_config<T: ClientConfig>(
         identity_provider: c.identity_provider(),
         mls_rules: c.mls_rules(),
         crypto_provider: c.crypto_provider(),
+        resumption_psk_resolver: c.resumption_psk_resolver(),
         signer,
         signing_identity,
+        keychain,
         version,
     })
 }
@@ -861,13 +1132,18 @@ mod private {
         protocol_version::ProtocolVersion,
     };
 
-    use crate::client_builder::{IntoConfigOutput, Settings};
+    use crate::{
+        client_builder::{IntoConfigOutput, Settings},
+        keychain::Keychain,
+    };
 
     #[derive(Clone, Debug)]
-    pub struct Config<Kpr, Ps, Gss, Ip, Pr, Cp>(pub(crate) ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp>);
+    pub struct Config<Kpr, Ps, Gss, Ip, Pr, Cp, Rr>(
+        pub(crate) ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Rr>,
+    );
 
     #[derive(Clone, Debug)]
-    pub struct ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp> {
+    pub struct ConfigInner<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> {
         pub(crate) settings: Settings,
         pub(crate) key_package_repo: Kpr,
         pub(crate) psk_store: Ps,
@@ -875,8 +1151,10 @@ mod private {
         pub(crate) identity_provider: Ip,
         pub(crate) mls_rules: Pr,
         pub(crate) crypto_provider: Cp,
+        pub(crate) resumption_psk_resolver: Rr,
         pub(crate) signer: Option<SignatureSecretKey>,
         pub(crate) signing_identity: Option<(SigningIdentity, CipherSuite)>,
+        pub(crate) keychain: Keychain,
         pub(crate) version: ProtocolVersion,
     }
 
@@ -887,17 +1165,19 @@ mod private {
         type IdentityProvider;
         type MlsRules;
         type CryptoProvider;
+        type ResumptionPskResolver;
 
         fn into_config(self) -> IntoConfigOutput<Self>;
     }
 
-    impl<Kpr, Ps, Gss, Ip, Pr, Cp> IntoConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp> {
+    impl<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> IntoConfig for Config<Kpr, Ps, Gss, Ip, Pr, Cp, Rr> {
         type KeyPackageRepository = Kpr;
         type PskStore = Ps;
         type GroupStateStorage = Gss;
         type IdentityProvider = Ip;
         type MlsRules = Pr;
         type CryptoProvider = Cp;
+        type ResumptionPskResolver = Rr;
 
         fn into_config(self) -> Self {
             self
@@ -4,18 +4,37 @@
 
 use crate::{
     extension::ExtensionType,
-    group::{mls_rules::MlsRules, proposal::ProposalType},
+    group::{mls_rules::MlsRules, proposal::ProposalType, GroupObserver},
     identity::CredentialType,
     protocol_version::ProtocolVersion,
+    psk::ResumptionPskResolver,
     tree_kem::{leaf_node::ConfigProperties, Capabilities, Lifetime},
     ExtensionList,
 };
 use alloc::vec::Vec;
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use alloc::boxed::Box;
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use portable_atomic_util::Arc;
+
 use mls_rs_core::{
     crypto::CryptoProvider, group::GroupStateStorage, identity::IdentityProvider,
     key_package::KeyPackageStorage, psk::PreSharedKeyStorage,
 };
 
+/// [`GroupObserver`] shared by all groups of a client.
+#[cfg(target_has_atomic = "ptr")]
+pub(crate) type SharedGroupObserver = Arc<dyn GroupObserver>;
+
+// `portable_atomic_util::Arc` can not be coerced to hold a trait object
+#[cfg(not(target_has_atomic = "ptr"))]
+pub(crate) type SharedGroupObserver = Arc<Box<dyn GroupObserver>>;
+
 pub trait ClientConfig: Send + Sync + Clone {
     type KeyPackageRepository: KeyPackageStorage + Clone;
     type PskStore: PreSharedKeyStorage + Clone;
@@ -23,6 +42,7 @@ pub trait ClientConfig: Send + Sync + Clone {
     type IdentityProvider: IdentityProvider + Clone;
     type MlsRules: MlsRules + Clone;
     type CryptoProvider: CryptoProvider + Clone;
+    type ResumptionPskResolver: ResumptionPskResolver + Clone;
 
     fn supported_extensions(&self) -> Vec<ExtensionType>;
     fn supported_custom_proposals(&self) -> Vec<ProposalType>;
@@ -36,6 +56,7 @@ pub trait ClientConfig: Send + Sync + Clone {
     fn group_state_storage(&self) -> Self::GroupStateStorage;
     fn identity_provider(&self) -> Self::IdentityProvider;
     fn crypto_provider(&self) -> Self::CryptoProvider;
+    fn resumption_psk_resolver(&self) -> Self::ResumptionPskResolver;
 
     fn lifetime(&self) -> Lifetime;
 
@@ -57,6 +78,23 @@ pub trait ClientConfig: Send + Sync + Clone {
         self.identity_provider().supported_types()
     }
 
+    fn max_group_size(&self) -> Option<u32> {
+        None
+    }
+
+    fn persist_on_epoch_change(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "prior_epoch")]
+    fn commit_lookback(&self) -> u64 {
+        0
+    }
+
+    fn group_observer(&self) -> Option<SharedGroupObserver> {
+        None
+    }
+
     fn leaf_properties(&self, leaf_node_extensions: ExtensionList) -> ConfigProperties {
         ConfigProperties {
             capabilities: self.capabilities(),
@@ -41,3 +41,47 @@ pub(crate) mod test_utils {
         TestCryptoProvider::new().cipher_suite_provider(CipherSuite::from(cipher_suite))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mls_rs_core::crypto::CipherSuiteProvider;
+
+    use crate::client::test_utils::TEST_CIPHER_SUITE;
+
+    use mls_rs_core::identity::SigningIdentity;
+
+    use super::{
+        test_utils::{test_cipher_suite_provider, TestCryptoProvider},
+        SignaturePublicKey,
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn fingerprint_is_stable_and_unique() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let (_, public) = cs.signature_key_generate().await.unwrap();
+        let (_, other) = cs.signature_key_generate().await.unwrap();
+
+        let fingerprint = public.fingerprint(&cs).await.unwrap();
+        let same = public.clone().fingerprint(&cs).await.unwrap();
+        let different = other.fingerprint(&cs).await.unwrap();
+
+        assert_eq!(fingerprint.len(), 2 * SignaturePublicKey::FINGERPRINT_LEN);
+        assert_eq!(fingerprint, same);
+        assert_ne!(fingerprint, different);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn generated_keys_are_accepted_by_from_basic_parts() {
+        for cipher_suite in TestCryptoProvider::all_supported_cipher_suites() {
+            let cs = test_cipher_suite_provider(cipher_suite);
+            let (_, public) = cs.signature_key_generate().await.unwrap();
+
+            SigningIdentity::from_basic_parts(b"alice".to_vec(), cipher_suite, public.to_vec())
+                .unwrap();
+        }
+    }
+}
@@ -71,6 +71,11 @@ where
     /// at the time the welcome message
     /// was created. `tree_data` can be exported from a group using the
     /// [export tree function](crate::group::Group::export_tree).
+    ///
+    /// If the GroupInfo message contains a `ratchet_tree` extension, the embedded
+    /// tree is used. In either case, the tree hash is validated against the
+    /// group context. If no tree is available,
+    /// [`MlsError::RatchetTreeNotFound`] is returned.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn observe_group(
         &self,
@@ -26,8 +26,8 @@ use crate::{
         snapshot::RawGroupState,
         state::GroupState,
         transcript_hash::InterimTranscriptHash,
-        validate_tree_and_info_joiner, ContentType, ExportedTree, GroupContext, GroupInfo, Roster,
-        Welcome,
+        validate_tree_and_info_joiner, ContentType, ExportedTree, GroupContext, GroupInfo,
+        LeafNode, Roster, Welcome,
     },
     identity::SigningIdentity,
     protocol_version::ProtocolVersion,
@@ -191,6 +191,45 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
         .await
     }
 
+    /// Validate a proposal message and store it for a later commit, regardless of
+    /// the [`cache_proposals`](crate::external_client::builder::ExternalClientBuilder::cache_proposals)
+    /// setting.
+    ///
+    /// The proposal is validated as in [`ExternalGroup::process_incoming_message`],
+    /// including its signature and sender. Messages other than plaintext proposals
+    /// are rejected with [`MlsError::UnexpectedMessageType`]. Tracked proposals are
+    /// listed by [`ExternalGroup::pending_proposals`] and are used when processing a
+    /// commit referencing them.
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn track_proposal(
+        &mut self,
+        message: MlsMessage,
+    ) -> Result<ProposalMessageDescription, MlsError> {
+        match &message.payload {
+            MlsMessagePayload::Plain(p) if p.content.content_type() == ContentType::Proposal => {}
+            _ => return Err(MlsError::UnexpectedMessageType),
+        }
+
+        match MessageProcessor::process_incoming_message(self, message, true).await? {
+            ExternalReceivedMessage::Proposal(description) => Ok(description),
+            _ => Err(MlsError::UnexpectedMessageType),
+        }
+    }
+
+    /// Proposals received in the current epoch that have not yet been committed.
+    ///
+    /// The list is emptied whenever the group moves to a new epoch.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn pending_proposals(&self) -> Vec<(ProposalRef, Proposal, Sender)> {
+        self.state
+            .proposals
+            .sorted_proposals()
+            .into_iter()
+            .map(|(r, p)| (r.clone(), p.proposal.clone(), p.sender))
+            .collect()
+    }
+
     /// Replay a proposal message into the group skipping all validation steps.
     #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -488,11 +527,29 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
     }
 
     /// Get the current group context summarizing various information about the group.
+    ///
+    /// The epoch, tree hash and confirmed transcript hash in the context identify
+    /// the state of the group. Two observers tracking the same epoch of the same
+    /// group have equal contexts. Unlike the
+    /// [epoch authenticator](crate::Group::epoch_authenticator), this does not
+    /// require access to the key schedule.
     #[inline(always)]
     pub fn group_context(&self) -> &GroupContext {
         &self.group_state().context
     }
 
+    /// The current epoch of the group. This value is incremented each
+    /// time a commit message is processed.
+    #[inline(always)]
+    pub fn current_epoch(&self) -> u64 {
+        self.group_context().epoch
+    }
+
+    /// Unique identifier for this group.
+    pub fn group_id(&self) -> &[u8] {
+        &self.group_context().group_id
+    }
+
     /// Export the current ratchet tree used within the group.
     pub fn export_tree(&self) -> Result<Vec<u8>, MlsError> {
         self.group_state()
@@ -508,6 +565,20 @@ impl<C: ExternalClientConfig + Clone> ExternalGroup<C> {
         self.group_state().public_tree.roster()
     }
 
+    /// Leaf node at a specific index in the group state.
+    ///
+    /// The leaf node is returned as it appears in the ratchet tree. Its capabilities and
+    /// extensions are raw and may contain GREASE values, unlike those of the corresponding
+    /// [`Member`].
+    ///
+    /// Returns `None` if the leaf is blank or the index is out of range.
+    pub fn leaf_node(&self, index: u32) -> Option<&LeafNode> {
+        self.group_state()
+            .public_tree
+            .get_leaf_node(LeafIndex(index))
+            .ok()
+    }
+
     /// Get the
     /// [transcript hash](https://messaginglayersecurity.rocks/mls-protocol/draft-ietf-mls-protocol.html#name-transcript-hashes)
     /// for the current epoch that the group is in.
@@ -808,11 +879,11 @@ mod tests {
     use crate::{
         cipher_suite::CipherSuite,
         client::{
-            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            test_utils::{test_client_with_key_pkg, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
             MlsError,
         },
         crypto::{test_utils::TestCryptoProvider, SignatureSecretKey},
-        extension::ExternalSendersExt,
+        extension::{ExternalSendersExt, RatchetTreeExt},
         external_client::{
             group::test_utils::make_external_group_with_config,
             tests_utils::{TestExternalClientBuilder, TestExternalClientConfig},
@@ -899,6 +970,41 @@ mod tests {
         assert_eq!(alice.state, server.state);
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_epoch_follows_processed_commits() {
+        let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut server = make_external_group(&alice).await;
+
+        assert_eq!(server.group_id(), alice.group_id());
+        assert_eq!(server.current_epoch(), 1);
+
+        for epoch in 2..4 {
+            let commit_output = alice.commit(Vec::new()).await.unwrap();
+            alice.apply_pending_commit().await.unwrap();
+
+            server
+                .process_incoming_message(commit_output.commit_message)
+                .await
+                .unwrap();
+
+            assert_eq!(server.current_epoch(), epoch);
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_leaf_node_lookup() {
+        let alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let server = make_external_group(&alice).await;
+
+        assert_eq!(
+            server.leaf_node(0),
+            Some(alice.current_user_leaf_node().unwrap())
+        );
+
+        assert_eq!(server.leaf_node(1), None);
+        assert_eq!(server.leaf_node(u32::MAX), None);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_process_proposals_by_reference() {
         let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -1082,6 +1188,74 @@ mod tests {
         );
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_can_observe_with_embedded_tree() {
+        let alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let group_info = alice.group_info_message(true).await.unwrap();
+        let config = TestExternalClientBuilder::new_for_test().build_config();
+
+        let observed = ExternalGroup::join(config, None, group_info, None)
+            .await
+            .unwrap();
+
+        assert_eq!(observed.state.public_tree, alice.state.public_tree);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_can_observe_with_external_tree() {
+        let alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let group_info = alice.group_info_message(false).await.unwrap();
+        let config = TestExternalClientBuilder::new_for_test().build_config();
+
+        let observed = ExternalGroup::join(config, None, group_info, Some(alice.export_tree()))
+            .await
+            .unwrap();
+
+        assert_eq!(observed.state.public_tree, alice.state.public_tree);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_requires_ratchet_tree_to_observe() {
+        let alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let group_info = alice.group_info_message(false).await.unwrap();
+        let config = TestExternalClientBuilder::new_for_test().build_config();
+
+        let res = ExternalGroup::join(config, None, group_info, None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::RatchetTreeNotFound));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_group_rejects_embedded_tree_not_matching_context() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let stale_tree = alice.export_tree().into_owned();
+
+        alice.commit(Vec::new()).await.unwrap();
+        alice.process_pending_commit().await.unwrap();
+
+        let group_info_message = alice.group_info_message(false).await.unwrap();
+        let version = group_info_message.version;
+        let mut group_info = group_info_message.into_group_info().unwrap();
+
+        group_info
+            .extensions
+            .set_from(RatchetTreeExt {
+                tree_data: stale_tree,
+            })
+            .unwrap();
+
+        let group_info = MlsMessage::new(version, MlsMessagePayload::GroupInfo(group_info));
+        let config = TestExternalClientBuilder::new_for_test().build_config();
+
+        let res = ExternalGroup::join(config, None, group_info, None)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn setup_extern_proposal_test(
@@ -1280,6 +1454,85 @@ mod tests {
             .unwrap();
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn tracked_proposals_are_listed_and_committed() {
+        let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let mut server = make_external_group_with_config(
+            &alice,
+            TestExternalClientBuilder::new_for_test()
+                .cache_proposals(false)
+                .build_config(),
+        )
+        .await;
+
+        let bob_key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let carol_key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        let proposals = [
+            alice.propose_add(bob_key_package, vec![]).await.unwrap(),
+            alice.propose_add(carol_key_package, vec![]).await.unwrap(),
+        ];
+
+        let mut tracked = Vec::new();
+
+        for proposal in proposals {
+            let description = server.track_proposal(proposal).await.unwrap();
+            tracked.push(description.proposal_ref);
+        }
+
+        let mut pending = server
+            .pending_proposals()
+            .into_iter()
+            .map(|(proposal_ref, ..)| proposal_ref)
+            .collect::<Vec<_>>();
+
+        pending.sort();
+        tracked.sort();
+
+        assert_eq!(pending, tracked);
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+
+        let ExternalReceivedMessage::Commit(description) =
+            server.process_incoming_message(commit).await.unwrap()
+        else {
+            panic!("expected commit")
+        };
+
+        let CommitEffect::NewEpoch(new_epoch) = description.effect else {
+            panic!("expected new epoch")
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 2);
+        assert!(server.pending_proposals().is_empty());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn invalid_proposal_is_not_tracked() {
+        let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut server = make_external_group(&alice).await;
+
+        let mut proposal = alice.propose_update(vec![]).await.unwrap();
+
+        match proposal.payload {
+            MlsMessagePayload::Plain(ref mut plain) => plain.auth.signature = Vec::new().into(),
+            _ => panic!("Unexpected non-plaintext data"),
+        };
+
+        let res = server.track_proposal(proposal).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        let res = server.track_proposal(commit).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::UnexpectedMessageType));
+
+        assert!(server.pending_proposals().is_empty());
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_observe_since_creation() {
         let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -1299,6 +1552,79 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn observer_can_join_as_member() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut server = make_external_group(&alice).await;
+
+        for _ in 0..3 {
+            let commit = alice.commit(vec![]).await.unwrap().commit_message;
+            alice.process_pending_commit().await.unwrap();
+            server.process_incoming_message(commit).await.unwrap();
+        }
+
+        let (bob, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        // The group info does not contain the ratchet tree, the observer provides it
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let epoch = server.current_epoch();
+
+        let (bob_group, commit) = bob
+            .external_commit_builder()
+            .unwrap()
+            .with_observer(&server)
+            .build(group_info)
+            .await
+            .unwrap();
+
+        alice
+            .process_incoming_message(commit.clone())
+            .await
+            .unwrap();
+        server.process_incoming_message(commit).await.unwrap();
+
+        assert_eq!(bob_group.current_epoch(), epoch + 1);
+        assert_eq!(server.current_epoch(), epoch + 1);
+        assert_eq!(bob_group.roster().members().len(), 2);
+
+        assert_eq!(
+            bob_group.epoch_authenticator().unwrap(),
+            alice.epoch_authenticator().unwrap()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn observer_behind_the_group_cannot_join() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let server = make_external_group(&alice).await;
+
+        alice.commit(vec![]).await.unwrap();
+        alice.process_pending_commit().await.unwrap();
+
+        let (bob, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let res = bob
+            .external_commit_builder()
+            .unwrap()
+            .with_observer(&server)
+            .build(group_info)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidEpoch));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn external_group_can_be_serialized_to_tls_encoding() {
         let server =
@@ -1366,4 +1692,28 @@ mod tests {
 
         assert_matches!(update, ExternalReceivedMessage::Welcome);
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn members_and_observer_agree_on_each_epoch() {
+        let mut alice = test_group_with_one_commit(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let mut server = make_external_group(&alice).await;
+
+        for _ in 0..3 {
+            let commit = alice.commit(vec![]).await.unwrap().commit_message;
+            alice.apply_pending_commit().await.unwrap();
+            bob.process_message(commit.clone()).await.unwrap();
+            server.process_incoming_message(commit).await.unwrap();
+
+            // The epoch authenticator requires the epoch secret, so only members can compute
+            // it. The observer tracks the public group context it is derived alongside.
+            assert_eq!(
+                alice.epoch_authenticator().unwrap(),
+                bob.epoch_authenticator().unwrap()
+            );
+
+            assert_eq!(server.group_context(), alice.context());
+            assert_eq!(server.group_context(), bob.context());
+        }
+    }
 }
@@ -0,0 +1,251 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+
+use mls_rs_core::{
+    crypto::{CipherSuite, SignatureSecretKey},
+    extension::{ExtensionList, ExtensionType, MlsExtension},
+    identity::SigningIdentity,
+    protocol_version::ProtocolVersion,
+};
+
+use crate::{client::MlsError, client_config::ClientConfig, Group};
+
+/// A builder that aids with the creation of a new group.
+///
+/// Created with [`Client::group_builder`](crate::Client::group_builder).
+#[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::ffi_type(opaque))]
+pub struct GroupBuilder<C: ClientConfig> {
+    config: C,
+    signer: SignatureSecretKey,
+    signing_identity: SigningIdentity,
+    cipher_suite: CipherSuite,
+    protocol_version: ProtocolVersion,
+    group_id: Option<Vec<u8>>,
+    group_context_extensions: ExtensionList,
+    leaf_node_extensions: ExtensionList,
+}
+
+impl<C: ClientConfig> GroupBuilder<C> {
+    pub(crate) fn new(
+        config: C,
+        signer: SignatureSecretKey,
+        signing_identity: SigningIdentity,
+        cipher_suite: CipherSuite,
+        protocol_version: ProtocolVersion,
+    ) -> Self {
+        Self {
+            config,
+            signer,
+            signing_identity,
+            cipher_suite,
+            protocol_version,
+            group_id: None,
+            group_context_extensions: Default::default(),
+            leaf_node_extensions: Default::default(),
+        }
+    }
+
+    #[must_use]
+    /// Use a specific group identifier instead of a random one.
+    ///
+    /// See [`Client::create_group_with_id`](crate::Client::create_group_with_id)
+    /// for why a random identifier is recommended.
+    pub fn with_group_id(self, group_id: Vec<u8>) -> Self {
+        Self {
+            group_id: Some(group_id),
+            ..self
+        }
+    }
+
+    /// Set `extension` in the group context of the new group, replacing any extension of
+    /// the same type that was set before.
+    ///
+    /// Extensions that can only appear in leaf nodes, key packages or group info messages
+    /// are rejected with [`MlsError::InvalidGroupContextExtension`]. Whether the creator
+    /// supports the extension is checked by [`GroupBuilder::build`].
+    pub fn with_group_context_extension<E: MlsExtension>(
+        mut self,
+        extension: E,
+    ) -> Result<Self, MlsError> {
+        let extension_type = E::extension_type();
+
+        if !is_group_context_extension(extension_type) {
+            return Err(MlsError::InvalidGroupContextExtension(extension_type));
+        }
+
+        self.group_context_extensions.set_from(extension)?;
+
+        Ok(self)
+    }
+
+    #[must_use]
+    /// Extensions to include in the leaf node of the creator.
+    pub fn with_leaf_node_extensions(self, leaf_node_extensions: ExtensionList) -> Self {
+        Self {
+            leaf_node_extensions,
+            ..self
+        }
+    }
+
+    /// Create the group.
+    ///
+    /// This fails if the capabilities of the creator do not support every non-default group
+    /// context extension, or do not meet a
+    /// [`RequiredCapabilitiesExt`](crate::extension::built_in::RequiredCapabilitiesExt)
+    /// set with [`GroupBuilder::with_group_context_extension`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn build(self) -> Result<Group<C>, MlsError> {
+        Group::new(
+            self.config,
+            self.group_id,
+            self.cipher_suite,
+            self.protocol_version,
+            self.signing_identity,
+            self.group_context_extensions,
+            self.leaf_node_extensions,
+            self.signer,
+        )
+        .await
+    }
+}
+
+fn is_group_context_extension(extension_type: ExtensionType) -> bool {
+    #[cfg(feature = "last_resort_key_package_ext")]
+    if extension_type == ExtensionType::LAST_RESORT_KEY_PACKAGE {
+        return false;
+    }
+
+    ![
+        ExtensionType::APPLICATION_ID,
+        ExtensionType::RATCHET_TREE,
+        ExtensionType::EXTERNAL_PUB,
+    ]
+    .contains(&extension_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::test_utils::{TestClientBuilder, TEST_CIPHER_SUITE},
+        extension::built_in::{ApplicationIdExt, RequiredCapabilitiesExt},
+        extension::test_utils::TestExtension,
+        ExtensionList,
+    };
+
+    #[cfg(feature = "by_ref_proposal")]
+    use crate::{
+        extension::built_in::ExternalSendersExt, identity::test_utils::get_test_signing_identity,
+    };
+
+    use super::*;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_context_extensions_are_set_at_epoch_zero() {
+        let client = TestClientBuilder::new_for_test()
+            .extension_type(TestExtension::extension_type())
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let required_capabilities =
+            RequiredCapabilitiesExt::new(vec![TestExtension::extension_type()], vec![], vec![]);
+
+        let builder = client
+            .group_builder()
+            .unwrap()
+            .with_group_id(b"group".to_vec())
+            .with_group_context_extension(required_capabilities.clone())
+            .unwrap();
+
+        let mut expected = ExtensionList::new();
+        expected.set_from(required_capabilities).unwrap();
+
+        #[cfg(feature = "by_ref_proposal")]
+        let builder = {
+            let (external_sender, _) =
+                get_test_signing_identity(TEST_CIPHER_SUITE, b"server").await;
+
+            let external_senders = ExternalSendersExt::new(vec![external_sender]);
+            expected.set_from(external_senders.clone()).unwrap();
+
+            builder
+                .with_group_context_extension(external_senders)
+                .unwrap()
+        };
+
+        let group = builder.build().await.unwrap();
+
+        let context = group.context();
+
+        assert_eq!(context.epoch, 0);
+        assert_eq!(context.group_id, b"group");
+
+        assert_eq!(context.extensions, expected);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn non_group_context_extension_is_rejected() {
+        let client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let res = client
+            .group_builder()
+            .unwrap()
+            .with_group_context_extension(ApplicationIdExt::new(b"app".to_vec()))
+            .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::InvalidGroupContextExtension(
+                ExtensionType::APPLICATION_ID
+            ))
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn creator_must_support_group_context_extensions() {
+        let client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let res = client
+            .group_builder()
+            .unwrap()
+            .with_group_context_extension(TestExtension::from(1))
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::UnsupportedGroupExtension(ext)) if ext == TestExtension::extension_type()
+        );
+
+        let required_capabilities =
+            RequiredCapabilitiesExt::new(vec![TestExtension::extension_type()], vec![], vec![]);
+
+        let res = client
+            .group_builder()
+            .unwrap()
+            .with_group_context_extension(required_capabilities)
+            .unwrap()
+            .build()
+            .await
+            .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::RequiredExtensionNotFound(ext)) if ext == TestExtension::extension_type()
+        );
+    }
+}
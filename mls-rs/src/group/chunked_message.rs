@@ -0,0 +1,535 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
+use mls_rs_core::{crypto::CipherSuiteProvider, error::IntoAnyError};
+
+use crate::{client::MlsError, client_config::ClientConfig, map::LargeMap, MlsMessage};
+
+use super::{ApplicationMessageDescription, Group};
+
+const MESSAGE_ID_LEN: usize = 16;
+
+/// Plaintext of one application message created by [`ApplicationMessageChunks`].
+#[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
+struct ApplicationChunk {
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    message_id: Vec<u8>,
+    index: u32,
+    count: u32,
+    #[mls_codec(with = "mls_rs_codec::byte_vec")]
+    data: Vec<u8>,
+}
+
+/// Application messages carrying one payload split into chunks, created with
+/// [`Group::application_message_chunks`].
+///
+/// Each message is encrypted only when requested with
+/// [`ApplicationMessageChunks::next_message`], so at most one chunk is held in
+/// memory at a time. Receivers put the payload back together with an
+/// [`ApplicationMessageReassembler`].
+pub struct ApplicationMessageChunks<'a, C: ClientConfig> {
+    group: &'a mut Group<C>,
+    data: &'a [u8],
+    chunk_size: usize,
+    message_id: Vec<u8>,
+    index: u32,
+    count: u32,
+    authenticated_data: Vec<u8>,
+}
+
+impl<'a, C: ClientConfig> ApplicationMessageChunks<'a, C> {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(super) async fn new(
+        group: &'a mut Group<C>,
+        data: &'a [u8],
+        chunk_size: usize,
+        authenticated_data: Vec<u8>,
+    ) -> Result<Self, MlsError> {
+        if chunk_size == 0 {
+            return Err(MlsError::InvalidChunkSize);
+        }
+
+        // An empty payload is still sent as one empty chunk
+        let count = data.len() / chunk_size + usize::from(data.len() % chunk_size != 0);
+
+        let count = u32::try_from(count.max(1)).map_err(|_| MlsError::InvalidChunkSize)?;
+
+        let message_id = group
+            .cipher_suite_provider
+            .random_bytes_vec(MESSAGE_ID_LEN)
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        Ok(Self {
+            group,
+            data,
+            chunk_size,
+            message_id,
+            index: 0,
+            count,
+            authenticated_data,
+        })
+    }
+
+    /// Total number of messages needed to send the payload.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Encrypt the next chunk of the payload, or return `None` once every chunk
+    /// was encrypted.
+    ///
+    /// Every returned message is a regular application message and is
+    /// authenticated with the `authenticated_data` passed to
+    /// [`Group::application_message_chunks`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn next_message(&mut self) -> Result<Option<MlsMessage>, MlsError> {
+        if self.index == self.count {
+            return Ok(None);
+        }
+
+        let start = self.index as usize * self.chunk_size;
+        let end = self.data.len().min(start + self.chunk_size);
+
+        let chunk = ApplicationChunk {
+            message_id: self.message_id.clone(),
+            index: self.index,
+            count: self.count,
+            data: self.data[start..end].to_vec(),
+        };
+
+        let message = self
+            .group
+            .encrypt_application_message(
+                &chunk.mls_encode_to_vec()?,
+                self.authenticated_data.clone(),
+            )
+            .await?;
+
+        self.index += 1;
+
+        Ok(Some(message))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PendingPayload {
+    count: u32,
+    size: usize,
+    chunks: LargeMap<u32, Vec<u8>>,
+}
+
+/// Puts payloads sent with [`Group::application_message_chunks`] back together.
+///
+/// Chunks may be added in any order. Payloads are tracked separately for each
+/// sender, so chunks of different payloads can be interleaved. Incomplete payloads
+/// are dropped once a chunk from a newer epoch is added.
+///
+/// By default, payloads are limited to
+/// [`DEFAULT_MAX_MESSAGE_SIZE`](ApplicationMessageReassembler::DEFAULT_MAX_MESSAGE_SIZE)
+/// bytes and at most
+/// [`DEFAULT_MAX_PENDING`](ApplicationMessageReassembler::DEFAULT_MAX_PENDING)
+/// incomplete payloads are buffered.
+#[derive(Clone, Debug)]
+pub struct ApplicationMessageReassembler {
+    pending: LargeMap<(u32, Vec<u8>), PendingPayload>,
+    epoch: Option<u64>,
+    max_message_size: usize,
+    max_pending: usize,
+}
+
+impl Default for ApplicationMessageReassembler {
+    fn default() -> Self {
+        Self {
+            pending: Default::default(),
+            epoch: None,
+            max_message_size: Self::DEFAULT_MAX_MESSAGE_SIZE,
+            max_pending: Self::DEFAULT_MAX_PENDING,
+        }
+    }
+}
+
+impl ApplicationMessageReassembler {
+    pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+    pub const DEFAULT_MAX_PENDING: usize = 64;
+
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the maximum size in bytes of a reassembled payload.
+    pub fn with_max_message_size(self, max_message_size: usize) -> Self {
+        Self {
+            max_message_size,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of incomplete payloads that are buffered at a time.
+    pub fn with_max_pending(self, max_pending: usize) -> Self {
+        Self {
+            max_pending,
+            ..self
+        }
+    }
+
+    /// Add a received chunk, returning the full payload once its last missing chunk
+    /// is added.
+    ///
+    /// Fails with [`MlsError::InvalidApplicationMessageChunk`] if the chunk is a
+    /// duplicate, inconsistent with previously added chunks of the same payload or
+    /// from an epoch older than that of a previously added chunk, and with a
+    /// serialization error if `message` was not created by
+    /// [`ApplicationMessageChunks`].
+    ///
+    /// Fails with [`MlsError::ApplicationMessageTooLarge`] if the payload exceeds the
+    /// maximum message size, in which case its chunks are dropped, and with
+    /// [`MlsError::TooManyPendingApplicationMessages`] if the chunk starts a new
+    /// payload while the maximum number of incomplete payloads is buffered.
+    pub fn add(
+        &mut self,
+        message: &ApplicationMessageDescription,
+    ) -> Result<Option<Vec<u8>>, MlsError> {
+        let chunk = ApplicationChunk::mls_decode(&mut message.data())?;
+
+        if chunk.index >= chunk.count {
+            return Err(MlsError::InvalidApplicationMessageChunk);
+        }
+
+        match self.epoch {
+            Some(epoch) if message.epoch < epoch => {
+                return Err(MlsError::InvalidApplicationMessageChunk)
+            }
+            Some(epoch) if message.epoch == epoch => {}
+            _ => {
+                // Sender indexes may refer to different members in a new epoch
+                self.pending.clear();
+                self.epoch = Some(message.epoch);
+            }
+        }
+
+        // Only an empty payload has an empty chunk, so each chunk adds at least one byte
+        if chunk.count as usize > self.max_message_size.max(1) {
+            return Err(MlsError::ApplicationMessageTooLarge);
+        }
+
+        let key = (message.sender_index, chunk.message_id);
+
+        if chunk.count > 1
+            && !self.pending.contains_key(&key)
+            && self.pending.len() >= self.max_pending
+        {
+            return Err(MlsError::TooManyPendingApplicationMessages);
+        }
+
+        let pending = self
+            .pending
+            .entry(key.clone())
+            .or_insert_with(|| PendingPayload {
+                count: chunk.count,
+                size: 0,
+                chunks: Default::default(),
+            });
+
+        if pending.count != chunk.count || pending.chunks.contains_key(&chunk.index) {
+            return Err(MlsError::InvalidApplicationMessageChunk);
+        }
+
+        pending.size += chunk.data.len();
+
+        if pending.size > self.max_message_size {
+            self.pending.remove(&key);
+            return Err(MlsError::ApplicationMessageTooLarge);
+        }
+
+        pending.chunks.insert(chunk.index, chunk.data);
+
+        if pending.chunks.len() < pending.count as usize {
+            return Ok(None);
+        }
+
+        let payload = (0..pending.count)
+            .filter_map(|index| pending.chunks.get(&index))
+            .flatten()
+            .copied()
+            .collect();
+
+        self.pending.remove(&key);
+
+        Ok(Some(payload))
+    }
+
+    /// Number of payloads for which some, but not all, chunks were added.
+    pub fn pending_payloads(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Drop all incomplete payloads.
+    pub fn clear(&mut self) {
+        self.pending.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use assert_matches::assert_matches;
+
+    use crate::{
+        client::{
+            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        group::{
+            test_utils::{random_bytes, test_group, TestGroup},
+            ApplicationMessageDescription, ReceivedMessage,
+        },
+    };
+
+    use super::ApplicationMessageReassembler;
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn send_chunks(
+        sender: &mut TestGroup,
+        receiver: &mut TestGroup,
+        payload: &[u8],
+        chunk_size: usize,
+    ) -> Vec<ApplicationMessageDescription> {
+        let mut chunks = sender
+            .application_message_chunks(payload, chunk_size, vec![])
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+
+        while let Some(message) = chunks.next_message().await.unwrap() {
+            let ReceivedMessage::ApplicationMessage(description) =
+                receiver.process_incoming_message(message).await.unwrap()
+            else {
+                panic!("expected application message")
+            };
+
+            received.push(description);
+        }
+
+        received
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn large_payload_is_reassembled_from_chunks() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let payload = random_bytes(10 * 1024 * 1024);
+
+        let mut chunks = alice
+            .application_message_chunks(&payload, 64 * 1024, b"aad".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(chunks.count(), 160);
+
+        let mut messages = Vec::new();
+
+        while let Some(message) = chunks.next_message().await.unwrap() {
+            messages.push(message);
+        }
+
+        assert_eq!(messages.len(), 160);
+
+        // Reverse the order of arrival, which stays within the out of order window
+        #[cfg(feature = "out_of_order")]
+        messages.reverse();
+
+        let mut reassembler = ApplicationMessageReassembler::new();
+        let mut received = None;
+
+        for message in messages {
+            let ReceivedMessage::ApplicationMessage(description) =
+                bob.process_incoming_message(message).await.unwrap()
+            else {
+                panic!("expected application message")
+            };
+
+            assert_eq!(description.authenticated_data, b"aad");
+            assert_eq!(received, None);
+
+            received = reassembler.add(&description).unwrap();
+        }
+
+        assert_eq!(received, Some(payload));
+        assert_eq!(reassembler.pending_payloads(), 0);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn interleaved_and_empty_payloads_are_reassembled() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let mut messages = Vec::new();
+
+        for payload in [b"first payload".as_slice(), b"second", b""] {
+            let mut chunks = alice
+                .application_message_chunks(payload, 4, vec![])
+                .await
+                .unwrap();
+
+            while let Some(message) = chunks.next_message().await.unwrap() {
+                messages.push(message);
+            }
+        }
+
+        // first payload has 4 chunks, second 2 and the empty one 1
+        assert_eq!(messages.len(), 7);
+
+        let mut reassembler = ApplicationMessageReassembler::new();
+        let mut received = Vec::new();
+
+        for i in [0, 4, 1, 6, 2, 5, 3] {
+            let ReceivedMessage::ApplicationMessage(description) = bob
+                .process_incoming_message(messages[i].clone())
+                .await
+                .unwrap()
+            else {
+                panic!("expected application message")
+            };
+
+            received.extend(reassembler.add(&description).unwrap());
+        }
+
+        assert_eq!(
+            received,
+            vec![b"".to_vec(), b"second".to_vec(), b"first payload".to_vec()]
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn duplicate_chunk_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let mut chunks = alice
+            .application_message_chunks(b"payload", 4, vec![])
+            .await
+            .unwrap();
+
+        let message = chunks.next_message().await.unwrap().unwrap();
+
+        let ReceivedMessage::ApplicationMessage(description) =
+            bob.process_incoming_message(message).await.unwrap()
+        else {
+            panic!("expected application message")
+        };
+
+        let mut reassembler = ApplicationMessageReassembler::new();
+
+        assert_eq!(reassembler.add(&description).unwrap(), None);
+
+        assert_matches!(
+            reassembler.add(&description),
+            Err(MlsError::InvalidApplicationMessageChunk)
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn chunk_count_above_max_message_size_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let chunks = send_chunks(&mut alice, &mut bob, &[0; 16], 4).await;
+
+        let mut reassembler = ApplicationMessageReassembler::new().with_max_message_size(3);
+
+        assert_matches!(
+            reassembler.add(&chunks[0]),
+            Err(MlsError::ApplicationMessageTooLarge)
+        );
+
+        assert_eq!(reassembler.pending_payloads(), 0);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn payload_above_max_message_size_is_dropped() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let chunks = send_chunks(&mut alice, &mut bob, &[0; 16], 4).await;
+
+        let mut reassembler = ApplicationMessageReassembler::new().with_max_message_size(8);
+
+        assert_eq!(reassembler.add(&chunks[0]).unwrap(), None);
+        assert_eq!(reassembler.add(&chunks[1]).unwrap(), None);
+
+        assert_matches!(
+            reassembler.add(&chunks[2]),
+            Err(MlsError::ApplicationMessageTooLarge)
+        );
+
+        assert_eq!(reassembler.pending_payloads(), 0);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn new_payload_above_max_pending_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let first = send_chunks(&mut alice, &mut bob, b"first", 4).await;
+        let second = send_chunks(&mut alice, &mut bob, b"second", 4).await;
+        let single = send_chunks(&mut alice, &mut bob, b"one", 4).await;
+
+        let mut reassembler = ApplicationMessageReassembler::new().with_max_pending(1);
+
+        assert_eq!(reassembler.add(&first[0]).unwrap(), None);
+
+        assert_matches!(
+            reassembler.add(&second[0]),
+            Err(MlsError::TooManyPendingApplicationMessages)
+        );
+
+        // Payloads sent in a single chunk are never buffered
+        assert_eq!(reassembler.add(&single[0]).unwrap(), Some(b"one".to_vec()));
+
+        assert_eq!(reassembler.add(&first[1]).unwrap(), Some(b"first".to_vec()));
+        assert_eq!(reassembler.add(&second[0]).unwrap(), None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn pending_payloads_are_dropped_on_epoch_change() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let old_epoch = send_chunks(&mut alice, &mut bob, b"old epoch", 4).await;
+
+        let mut reassembler = ApplicationMessageReassembler::new();
+        assert_eq!(reassembler.add(&old_epoch[0]).unwrap(), None);
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_incoming_message(commit).await.unwrap();
+
+        let new_epoch = send_chunks(&mut alice, &mut bob, b"new epoch", 4).await;
+
+        assert_eq!(reassembler.add(&new_epoch[0]).unwrap(), None);
+        assert_eq!(reassembler.pending_payloads(), 1);
+
+        assert_matches!(
+            reassembler.add(&old_epoch[1]),
+            Err(MlsError::InvalidApplicationMessageChunk)
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn zero_chunk_size_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let res = alice
+            .application_message_chunks(b"payload", 0, vec![])
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidChunkSize));
+    }
+}
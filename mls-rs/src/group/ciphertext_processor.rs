@@ -95,12 +95,14 @@ where
             .await
     }
 
+    /// Encrypt `auth_content`, returning the message along with the generation of the
+    /// message key written to the sender data.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn seal(
         &mut self,
         auth_content: AuthenticatedContent,
         padding: PaddingMode,
-    ) -> Result<PrivateMessage, MlsError> {
+    ) -> Result<(PrivateMessage, u32), MlsError> {
         if Sender::Member(*self.group_state.self_index()) != auth_content.content.sender {
             return Err(MlsError::InvalidSender);
         }
@@ -181,14 +183,16 @@ where
 
         let encrypted_sender_data = sender_data_key.seal(&sender_data, &sender_data_aad).await?;
 
-        Ok(PrivateMessage {
+        let message = PrivateMessage {
             group_id: self.group_state.group_context().group_id.clone(),
             epoch: self.group_state.group_context().epoch,
             content_type,
             authenticated_data,
             encrypted_sender_data,
             ciphertext,
-        })
+        };
+
+        Ok((message, generation))
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -272,7 +276,7 @@ mod test {
             CipherSuiteProvider,
         },
         group::{
-            framing::{ApplicationData, Content, Sender, WireFormat},
+            framing::{ApplicationData, Content, ContentType, Sender, WireFormat},
             message_signature::AuthenticatedContent,
             padding::PaddingMode,
             test_utils::{random_bytes, test_group, TestGroup},
@@ -280,7 +284,12 @@ mod test {
         tree_kem::node::LeafIndex,
     };
 
-    use super::{CiphertextProcessor, GroupStateProvider, MlsError};
+    use super::{
+        sender_data_key::{SenderDataAAD, SenderDataKey},
+        CiphertextProcessor, GroupStateProvider, MlsError,
+    };
+
+    use crate::group::framing::MlsMessagePayload;
 
     use alloc::vec;
     use assert_matches::assert_matches;
@@ -326,7 +335,7 @@ mod test {
 
             let mut ciphertext_processor = test_processor(&mut test_data.group, cipher_suite);
 
-            let ciphertext = ciphertext_processor
+            let (ciphertext, _) = ciphertext_processor
                 .seal(test_data.content.clone(), PaddingMode::StepFunction)
                 .await
                 .unwrap();
@@ -346,12 +355,12 @@ mod test {
         let mut test_data = test_data(TEST_CIPHER_SUITE).await;
         let mut ciphertext_processor = test_processor(&mut test_data.group, TEST_CIPHER_SUITE);
 
-        let ciphertext_step = ciphertext_processor
+        let (ciphertext_step, _) = ciphertext_processor
             .seal(test_data.content.clone(), PaddingMode::StepFunction)
             .await
             .unwrap();
 
-        let ciphertext_no_pad = ciphertext_processor
+        let (ciphertext_no_pad, _) = ciphertext_processor
             .seal(test_data.content.clone(), PaddingMode::None)
             .await
             .unwrap();
@@ -379,7 +388,7 @@ mod test {
 
         let mut ciphertext_processor = test_processor(&mut test_data.group, TEST_CIPHER_SUITE);
 
-        let ciphertext = ciphertext_processor
+        let (ciphertext, _) = ciphertext_processor
             .seal(test_data.content, PaddingMode::None)
             .await
             .unwrap();
@@ -395,7 +404,7 @@ mod test {
         let mut receiver_group = test_data.group.clone();
         let mut ciphertext_processor = test_processor(&mut test_data.group, TEST_CIPHER_SUITE);
 
-        let mut ciphertext = ciphertext_processor
+        let (mut ciphertext, _) = ciphertext_processor
             .seal(test_data.content.clone(), PaddingMode::StepFunction)
             .await
             .unwrap();
@@ -407,4 +416,150 @@ mod test {
 
         assert!(res.is_err());
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn modified_header_fails_decryption() {
+        let mut test_data = test_data(TEST_CIPHER_SUITE).await;
+        let mut receiver_group = test_data.group.clone();
+        receiver_group.private_tree.self_index = LeafIndex::new(1);
+
+        let mut ciphertext_processor = test_processor(&mut test_data.group, TEST_CIPHER_SUITE);
+
+        let (ciphertext, _) = ciphertext_processor
+            .seal(test_data.content.clone(), PaddingMode::StepFunction)
+            .await
+            .unwrap();
+
+        let mut modified = vec![ciphertext.clone(); 4];
+        modified[0].group_id.push(0);
+        modified[1].epoch += 1;
+        modified[2].content_type = ContentType::Commit;
+        modified[3].authenticated_data = b"other".to_vec();
+
+        // Each of the header fields is authenticated as associated data
+        for ciphertext in modified {
+            let mut receiver_group = receiver_group.clone();
+            let mut receiver_processor = test_processor(&mut receiver_group, TEST_CIPHER_SUITE);
+            let res = receiver_processor.open(&ciphertext).await;
+            assert!(res.is_err());
+        }
+
+        let mut receiver_processor = test_processor(&mut receiver_group, TEST_CIPHER_SUITE);
+        let decrypted = receiver_processor.open(&ciphertext).await.unwrap();
+        assert_eq!(decrypted, test_data.content);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn message_cannot_be_replayed_into_other_epoch() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let mut message = alice
+            .encrypt_application_message(b"hello", vec![])
+            .await
+            .unwrap();
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_incoming_message(commit).await.unwrap();
+
+        let MlsMessagePayload::Cipher(ref mut ciphertext) = message.payload else {
+            panic!("expected private message")
+        };
+
+        ciphertext.epoch = bob.context().epoch;
+
+        let res = bob.process_incoming_message(message).await.map(|_| ());
+        assert!(res.is_err());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn sent_generation_matches_sender_data() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        for expected_generation in 0..3 {
+            let (message, generation) = alice
+                .encrypt_application_message_with_generation(b"hello", vec![])
+                .await
+                .unwrap();
+
+            assert_eq!(generation, expected_generation);
+            assert_eq!(message.epoch(), Some(1));
+
+            let MlsMessagePayload::Cipher(ciphertext) = &message.payload else {
+                panic!("expected private message")
+            };
+
+            let sender_data_key = SenderDataKey::new(
+                &bob.epoch_secrets.sender_data_secret,
+                &ciphertext.ciphertext,
+                &cs,
+            )
+            .await
+            .unwrap();
+
+            let aad = SenderDataAAD {
+                group_id: ciphertext.group_id.clone(),
+                epoch: ciphertext.epoch,
+                content_type: ciphertext.content_type,
+            };
+
+            let sender_data = sender_data_key
+                .open(&ciphertext.encrypted_sender_data, &aad)
+                .await
+                .unwrap();
+
+            assert_eq!(sender_data.generation, generation);
+
+            bob.process_incoming_message(message).await.unwrap();
+        }
+
+        // Generations restart in a new epoch
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_incoming_message(commit).await.unwrap();
+
+        let (message, generation) = alice
+            .encrypt_application_message_with_generation(b"hello", vec![])
+            .await
+            .unwrap();
+
+        assert_eq!(generation, 0);
+        assert_eq!(message.epoch(), Some(2));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn sender_data_nonces_are_not_reused() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let mut nonces = alloc::collections::BTreeSet::new();
+
+        for _ in 0..100 {
+            // Identical plaintexts make sure that freshness comes from the key schedule
+            let message = alice
+                .encrypt_application_message(b"hello", vec![])
+                .await
+                .unwrap();
+
+            let MlsMessagePayload::Cipher(ciphertext) = &message.payload else {
+                panic!("expected private message")
+            };
+
+            let sender_data_key = SenderDataKey::new(
+                &alice.epoch_secrets.sender_data_secret,
+                &ciphertext.ciphertext,
+                &cs,
+            )
+            .await
+            .unwrap();
+
+            assert!(nonces.insert(sender_data_key.nonce.to_vec()));
+
+            bob.process_incoming_message(message).await.unwrap();
+        }
+    }
 }
@@ -66,14 +66,101 @@ mod test_utils {
 #[cfg(test)]
 mod tests {
     use alloc::vec::Vec;
+    use mls_rs_codec::{MlsDecode, MlsEncode};
     use mls_rs_core::crypto::CipherSuiteProvider;
+    use zeroize::Zeroizing;
 
     use crate::{
-        client::test_utils::TEST_CIPHER_SUITE, crypto::test_utils::test_cipher_suite_provider,
+        client::test_utils::TEST_CIPHER_SUITE,
+        crypto::test_utils::test_cipher_suite_provider,
+        group::{ciphertext_processor::message_key::MessageKey, secret_tree::MessageKeyData},
     };
 
     use super::{ReuseGuard, REUSE_GUARD_SIZE};
 
+    #[test]
+    fn reuse_guard_is_xored_into_first_four_bytes() {
+        let nonce = [0xaa; 12];
+        let guard = ReuseGuard::from([0x01, 0x02, 0x03, 0xff]);
+
+        let guarded = guard.apply(&nonce);
+
+        assert_eq!(guarded[..4], [0xab, 0xa8, 0xa9, 0x55]);
+        assert_eq!(guarded[4..], nonce[4..]);
+
+        // Applying the same guard again restores the nonce
+        assert_eq!(guard.apply(&guarded), nonce);
+    }
+
+    #[test]
+    fn different_reuse_guards_give_different_nonces() {
+        let nonce = [0x42; 12];
+
+        let guards = [[0, 0, 0, 1], [0, 0, 1, 0], [0, 1, 0, 0], [1, 0, 0, 0]];
+
+        let nonces = guards
+            .into_iter()
+            .map(|guard| ReuseGuard::from(guard).apply(&nonce))
+            .collect::<Vec<_>>();
+
+        for (i, nonce) in nonces.iter().enumerate() {
+            assert!(nonces[i + 1..].iter().all(|other| other != nonce));
+        }
+
+        assert_eq!(ReuseGuard::from([0; REUSE_GUARD_SIZE]).apply(&nonce), nonce);
+    }
+
+    #[test]
+    fn reuse_guard_is_encoded_as_four_bytes() {
+        let guard = ReuseGuard::from([1, 2, 3, 4]);
+
+        // `opaque reuse_guard[4]` has no length prefix
+        assert_eq!(guard.mls_encode_to_vec().unwrap(), [1, 2, 3, 4]);
+
+        assert!(ReuseGuard::mls_decode(&mut [1u8, 2, 3].as_slice()).is_err());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn same_generation_with_different_reuse_guards_gives_different_ciphertexts() {
+        let provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let key_data = MessageKeyData {
+            nonce: Zeroizing::new(
+                provider
+                    .random_bytes_vec(provider.aead_nonce_size())
+                    .unwrap(),
+            ),
+            key: Zeroizing::new(provider.random_bytes_vec(provider.aead_key_size()).unwrap()),
+            generation: 3,
+        };
+
+        let key = MessageKey::new(key_data);
+        let first_guard = ReuseGuard::from([1, 2, 3, 4]);
+        let second_guard = ReuseGuard::from([4, 3, 2, 1]);
+
+        let first = key
+            .encrypt(&provider, b"message", b"aad", &first_guard)
+            .await
+            .unwrap();
+
+        let second = key
+            .encrypt(&provider, b"message", b"aad", &second_guard)
+            .await
+            .unwrap();
+
+        assert_ne!(first, second);
+
+        let decrypted = key
+            .decrypt(&provider, &first, b"aad", &first_guard)
+            .await
+            .unwrap();
+
+        assert_eq!(decrypted.as_slice(), b"message");
+
+        let res = key.decrypt(&provider, &first, b"aad", &second_guard).await;
+        assert!(res.is_err());
+    }
+
     #[test]
     fn test_random_generation() {
         let test_guard =
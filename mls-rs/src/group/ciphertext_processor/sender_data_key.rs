@@ -54,8 +54,8 @@ pub(crate) struct SenderDataKey<'a, CP: CipherSuiteProvider> {
 impl<CP: CipherSuiteProvider + Debug> Debug for SenderDataKey<'_, CP> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SenderDataKey")
-            .field("key", &mls_rs_core::debug::pretty_bytes(&self.key))
-            .field("nonce", &mls_rs_core::debug::pretty_bytes(&self.nonce))
+            .field("key", &mls_rs_core::debug::pretty_secret(&self.key))
+            .field("nonce", &mls_rs_core::debug::pretty_secret(&self.nonce))
             .field("cipher_suite_provider", self.cipher_suite_provider)
             .finish()
     }
@@ -69,7 +69,8 @@ impl<'a, CP: CipherSuiteProvider> SenderDataKey<'a, CP> {
         cipher_suite_provider: &'a CP,
     ) -> Result<SenderDataKey<'a, CP>, MlsError> {
         // Sample the first extract_size bytes of the ciphertext, and if it is shorter, just use
-        // the ciphertext itself
+        // the ciphertext itself. Every message is encrypted with a fresh key and nonce from the
+        // secret tree, so the sample and the derived sender data nonce differ between messages.
         let extract_size = cipher_suite_provider.kdf_extract_size();
         let ciphertext_sample = ciphertext.get(0..extract_size).unwrap_or(ciphertext);
 
@@ -195,17 +196,24 @@ mod tests {
 
     use crate::{
         crypto::test_utils::try_test_cipher_suite_provider,
-        group::{ciphertext_processor::reuse_guard::ReuseGuard, framing::ContentType},
+        group::{
+            ciphertext_processor::reuse_guard::ReuseGuard, framing::ContentType,
+            key_schedule::kdf_derive_secret,
+        },
         tree_kem::node::LeafIndex,
     };
 
     use super::{SenderData, SenderDataAAD, SenderDataKey};
 
-    #[cfg(not(mls_build_async))]
     use crate::{
-        cipher_suite::CipherSuite, crypto::test_utils::test_cipher_suite_provider,
-        group::test_utils::random_bytes, CipherSuiteProvider,
+        client::test_utils::TEST_CIPHER_SUITE, crypto::test_utils::test_cipher_suite_provider,
+        CipherSuiteProvider,
     };
+    use alloc::{format, vec};
+    use mls_rs_codec::MlsEncode;
+
+    #[cfg(not(mls_build_async))]
+    use crate::{cipher_suite::CipherSuite, group::test_utils::random_bytes};
 
     #[derive(serde::Deserialize, serde::Serialize)]
     struct TestCase {
@@ -321,6 +329,115 @@ mod tests {
         load_test_case_json!(sender_data_key_test_vector, generate_test_vector())
     }
 
+    // Builds the RFC 9420 KDFLabel by hand so that the labels used by `SenderDataKey` are checked
+    // independently of the crate's own `kdf_expand_with_label`.
+    fn rfc_kdf_label(label: &str, context: &[u8], length: usize) -> Vec<u8> {
+        let mut kdf_label = (length as u16).to_be_bytes().to_vec();
+
+        [format!("MLS 1.0 {label}").as_bytes(), context]
+            .into_iter()
+            .for_each(|data| kdf_label.extend(data.to_vec().mls_encode_to_vec().unwrap()));
+
+        kdf_label
+    }
+
+    #[derive(serde::Deserialize)]
+    struct KeyScheduleTestCase {
+        cipher_suite: u16,
+        epochs: Vec<KeyScheduleEpoch>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct KeyScheduleEpoch {
+        #[serde(with = "hex::serde")]
+        group_context: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        joiner_secret: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        psk_secret: Vec<u8>,
+        #[serde(with = "hex::serde")]
+        sender_data_secret: Vec<u8>,
+    }
+
+    // The key schedule interop vector is not generated by this crate, so it serves as a known
+    // answer for the "sender data" label.
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn sender_data_secret_uses_rfc_label() {
+        let test_cases: Vec<KeyScheduleTestCase> =
+            load_test_case_json!(key_schedule_test_vector, Vec::<()>::new());
+
+        for test_case in test_cases {
+            let Some(provider) = try_test_cipher_suite_provider(test_case.cipher_suite) else {
+                continue;
+            };
+
+            let extract_size = provider.kdf_extract_size();
+
+            for epoch in test_case.epochs {
+                let member_secret = provider
+                    .kdf_extract(&epoch.joiner_secret, &epoch.psk_secret)
+                    .await
+                    .unwrap();
+
+                let epoch_label = rfc_kdf_label("epoch", &epoch.group_context, extract_size);
+
+                let epoch_secret = provider
+                    .kdf_expand(&member_secret, &epoch_label, extract_size)
+                    .await
+                    .unwrap();
+
+                let sender_data_label = rfc_kdf_label("sender data", &[], extract_size);
+
+                let sender_data_secret = provider
+                    .kdf_expand(&epoch_secret, &sender_data_label, extract_size)
+                    .await
+                    .unwrap();
+
+                assert_eq!(*sender_data_secret, epoch.sender_data_secret);
+
+                let derived = kdf_derive_secret(&provider, &epoch_secret, b"sender data")
+                    .await
+                    .unwrap();
+
+                assert_eq!(*derived, epoch.sender_data_secret);
+            }
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn sender_data_key_uses_rfc_labels() {
+        let provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let extract_size = provider.kdf_extract_size();
+        let secret = vec![0x42; extract_size];
+
+        for ciphertext_size in [extract_size - 5, extract_size, extract_size + 5] {
+            let ciphertext = (0..ciphertext_size as u8).collect::<Vec<_>>();
+            let sample = &ciphertext[..ciphertext_size.min(extract_size)];
+
+            let sender_data_key =
+                SenderDataKey::new(&secret.clone().into(), &ciphertext, &provider)
+                    .await
+                    .unwrap();
+
+            let key_size = provider.aead_key_size();
+            let key_label = rfc_kdf_label("key", sample, key_size);
+            let key = provider
+                .kdf_expand(&secret, &key_label, key_size)
+                .await
+                .unwrap();
+
+            let nonce_size = provider.aead_nonce_size();
+            let nonce_label = rfc_kdf_label("nonce", sample, nonce_size);
+            let nonce = provider
+                .kdf_expand(&secret, &nonce_label, nonce_size)
+                .await
+                .unwrap();
+
+            assert_eq!(sender_data_key.key, key);
+            assert_eq!(sender_data_key.nonce, nonce);
+        }
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn sender_data_key_test_vector() {
         for test_case in load_test_cases() {
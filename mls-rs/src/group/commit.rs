@@ -47,7 +47,7 @@ use super::{
     mls_rules::CommitDirection,
     proposal::{Proposal, ProposalOrRef},
     ConfirmedTranscriptHash, EncryptedGroupSecrets, ExportedTree, Group, GroupContext, GroupInfo,
-    Welcome,
+    Welcome, WelcomeSecrets,
 };
 
 #[cfg(not(feature = "by_ref_proposal"))]
@@ -179,6 +179,7 @@ where
     new_signer: Option<SignatureSecretKey>,
     new_signing_identity: Option<SigningIdentity>,
     new_leaf_node_extensions: Option<ExtensionList>,
+    welcome_aad: Option<Vec<u8>>,
 }
 
 impl<'a, C> CommitBuilder<'a, C>
@@ -315,6 +316,9 @@ where
     /// and results in the same
     /// [identity](crate::IdentityProvider::identity)
     /// being used.
+    ///
+    /// The signer of an identity held in a client's keychain can be found with
+    /// [`Keychain::signer_for_identity`](crate::keychain::Keychain::signer_for_identity).
     pub fn set_new_signing_identity(
         self,
         signer: SignatureSecretKey,
@@ -327,6 +331,19 @@ where
         }
     }
 
+    /// Bind the welcome messages produced by this commit to `welcome_aad`.
+    ///
+    /// The group secrets of each new member are encrypted with `welcome_aad` as
+    /// additional authenticated data. New members must provide the same value to
+    /// [`Client::join_group_with_welcome_aad`](crate::Client::join_group_with_welcome_aad)
+    /// in order to join. By default, no additional authenticated data is used.
+    pub fn welcome_aad(self, welcome_aad: Vec<u8>) -> Self {
+        Self {
+            welcome_aad: Some(welcome_aad),
+            ..self
+        }
+    }
+
     /// Change the committer's leaf node extensions as part of making this commit.
     pub fn set_leaf_node_extensions(self, new_leaf_node_extensions: ExtensionList) -> Self {
         Self {
@@ -355,6 +372,7 @@ where
                 self.new_signer,
                 self.new_signing_identity,
                 self.new_leaf_node_extensions,
+                self.welcome_aad,
             )
             .await?;
 
@@ -379,6 +397,7 @@ where
                 self.new_signer,
                 self.new_signing_identity,
                 self.new_leaf_node_extensions,
+                self.welcome_aad,
             )
             .await?;
 
@@ -417,7 +436,10 @@ where
     /// Sending a commit that contains no proposals is a valid operation
     /// within the MLS protocol. It is useful for providing stronger forward
     /// secrecy and post-compromise security, especially for long running
-    /// groups when group membership does not change often.
+    /// groups when group membership does not change often. Applications that
+    /// consider an empty commit a mistake can disable them with the
+    /// `allow_empty_commits` option returned by
+    /// [`MlsRules::commit_options`](`crate::MlsRules::commit_options`).
     ///
     /// # Path Updates
     ///
@@ -464,6 +486,7 @@ where
             new_signer: Default::default(),
             new_signing_identity: Default::default(),
             new_leaf_node_extensions: Default::default(),
+            welcome_aad: Default::default(),
         }
     }
 
@@ -480,6 +503,7 @@ where
         new_signer: Option<SignatureSecretKey>,
         new_signing_identity: Option<SigningIdentity>,
         new_leaf_node_extensions: Option<ExtensionList>,
+        welcome_aad: Option<Vec<u8>>,
     ) -> Result<(CommitOutput, CommitGeneration), MlsError> {
         if self.pending_commit.is_some() {
             return Err(MlsError::ExistingPendingCommit);
@@ -532,6 +556,8 @@ where
             )
             .await?;
 
+        provisional_state.check_group_size(self.config.max_group_size())?;
+
         let (mut provisional_private_tree, _) =
             self.provisional_private_tree(&provisional_state)?;
 
@@ -556,6 +582,19 @@ where
             )
             .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
 
+        let changes_own_leaf = new_signer.is_some()
+            || new_signing_identity.is_some()
+            || new_leaf_node_extensions.is_some();
+
+        if !commit_options.allow_empty_commits
+            && !is_external
+            && !commit_options.path_required
+            && !changes_own_leaf
+            && provisional_state.applied_proposals.length() == 0
+        {
+            return Err(MlsError::EmptyCommitNotAllowed);
+        }
+
         let perform_path_update = commit_options.path_required
             || path_update_required(&provisional_state.applied_proposals);
 
@@ -747,8 +786,21 @@ where
             .encrypt(&welcome_group_info.mls_encode_to_vec()?)
             .await?;
 
+        // Only commits that add members need to keep the secrets for welcome messages
+        self.pending_welcome_secrets = (!added_key_pkgs.is_empty()).then(|| WelcomeSecrets {
+            confirmed_transcript_hash: provisional_group_context.confirmed_transcript_hash.clone(),
+            added_leaves: provisional_state.indexes_of_added_kpkgs.clone(),
+            joiner_secret: key_schedule_result.joiner_secret.clone(),
+            path_secrets: path_secrets.clone(),
+            #[cfg(feature = "psk")]
+            psks: psks.clone(),
+            encrypted_group_info: encrypted_group_info.clone(),
+            welcome_aad: welcome_aad.clone(),
+        });
+
         // Encrypt path secrets and joiner secret to new members
         let path_secrets = path_secrets.as_ref();
+        let added_count = added_key_pkgs.len();
 
         #[cfg(not(any(mls_build_async, not(feature = "rayon"))))]
         let encrypted_path_secrets: Vec<_> = added_key_pkgs
@@ -763,6 +815,7 @@ where
                     #[cfg(feature = "psk")]
                     psks.clone(),
                     &encrypted_group_info,
+                    welcome_aad.as_deref(),
                 )
             })
             .try_collect()?;
@@ -784,6 +837,7 @@ where
                         #[cfg(feature = "psk")]
                         psks.clone(),
                         &encrypted_group_info,
+                        welcome_aad.as_deref(),
                     )
                     .await?,
                 );
@@ -792,6 +846,11 @@ where
             secrets
         };
 
+        // Every added member must be able to join using the welcome messages
+        if encrypted_path_secrets.len() != added_count {
+            return Err(MlsError::WelcomeMissingGroupSecrets);
+        }
+
         let welcome_messages =
             if commit_options.single_welcome_message && !encrypted_path_secrets.is_empty() {
                 vec![self.make_welcome_message(encrypted_path_secrets, encrypted_group_info)]
@@ -859,7 +918,7 @@ where
         Ok(group_info)
     }
 
-    fn make_welcome_message(
+    pub(super) fn make_welcome_message(
         &self,
         secrets: Vec<EncryptedGroupSecrets>,
         encrypted_group_info: Vec<u8>,
@@ -937,7 +996,6 @@ mod tests {
     use crate::crypto::test_utils::test_cipher_suite_provider;
     #[cfg(feature = "by_ref_proposal")]
     use crate::extension::ExternalSendersExt;
-    #[cfg(feature = "by_ref_proposal")]
     use crate::group::mls_rules::DefaultMlsRules;
 
     #[cfg(feature = "psk")]
@@ -1288,6 +1346,55 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_covers_every_added_member() {
+        let mut group = test_commit_builder_group().await;
+        let cs = crate::crypto::test_utils::test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let mut joiners = Vec::new();
+
+        for name in ["a", "b", "c"] {
+            let (client, kp) =
+                test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
+
+            let kp_ref = kp.key_package_reference(&cs).await.unwrap().unwrap();
+            joiners.push((client, kp, kp_ref));
+        }
+
+        let mut builder = group.commit_builder();
+
+        for (_, kp, _) in &joiners {
+            builder = builder.add_member(kp.clone()).unwrap();
+        }
+
+        let mut output = builder.build().await.unwrap();
+
+        assert_eq!(output.welcome_messages.len(), 1);
+        let mut welcome = output.welcome_messages.remove(0);
+
+        let refs = welcome.welcome_key_package_references();
+        assert_eq!(refs.len(), 3);
+
+        assert!(joiners.iter().all(|(_, _, kp_ref)| refs.contains(&kp_ref)));
+
+        // Drop the secrets of the last joiner
+        let (excluded, _, excluded_ref) = joiners.pop().unwrap();
+
+        let MlsMessagePayload::Welcome(w) = &mut welcome.payload else {
+            panic!("expected welcome")
+        };
+
+        w.secrets.retain(|secret| secret.new_member != excluded_ref);
+
+        for (client, _, _) in joiners {
+            client.join_group(None, &welcome).await.unwrap();
+        }
+
+        let res = excluded.join_group(None, &welcome).await.map(|_| ());
+
+        assert!(matches!(res, Err(MlsError::WelcomeKeyPackageNotFound)));
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn commit_can_change_credential() {
         let cs = TEST_CIPHER_SUITE;
@@ -1653,4 +1760,73 @@ mod tests {
         group.apply_detached_commit(secrets).await.unwrap();
         assert_eq!(group.context().epoch, 1);
     }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn group_with_commit_options(options: CommitOptions) -> Group<TestClientConfig> {
+        test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.mls_rules(DefaultMlsRules::new().with_commit_options(options))
+        })
+        .await
+        .group
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn empty_commit_is_allowed_by_default() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let commit_output = group.commit(vec![]).await.unwrap();
+        assert!(commit_output.commit_message.into_plaintext().is_some());
+
+        group.apply_pending_commit().await.unwrap();
+        assert_eq!(group.context().epoch, 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn empty_commit_is_rejected_if_not_allowed() {
+        let options = CommitOptions::new().with_allow_empty_commits(false);
+        let mut group = group_with_commit_options(options).await;
+
+        let res = group.commit(vec![]).await.map(|_| ());
+        assert!(matches!(res, Err(MlsError::EmptyCommitNotAllowed)));
+        assert!(group.pending_commit.is_none());
+
+        // A commit changing the committer's leaf node is not empty
+        let res = group
+            .commit_builder()
+            .set_leaf_node_extensions(Default::default())
+            .build()
+            .await;
+
+        assert!(res.is_ok());
+        group.clear_pending_commit();
+
+        // Neither is a commit with proposals
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        group.apply_pending_commit().await.unwrap();
+        assert_eq!(group.context().epoch, 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn empty_commit_with_required_path_is_allowed() {
+        let options = CommitOptions::new()
+            .with_allow_empty_commits(false)
+            .with_path_required(true);
+
+        let mut group = group_with_commit_options(options).await;
+
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+
+        assert_eq!(group.context().epoch, 1);
+    }
 }
@@ -7,7 +7,9 @@ use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 
-use crate::{cipher_suite::CipherSuite, protocol_version::ProtocolVersion, ExtensionList};
+use crate::{
+    cipher_suite::CipherSuite, client::MlsError, protocol_version::ProtocolVersion, ExtensionList,
+};
 
 use super::ConfirmedTranscriptHash;
 
@@ -92,7 +94,28 @@ impl GroupContext {
         self.epoch
     }
 
+    /// Get the tree hash of the ratchet tree in the group's current state.
+    pub fn tree_hash(&self) -> &[u8] {
+        &self.tree_hash
+    }
+
     pub fn extensions(&self) -> &ExtensionList {
         &self.extensions
     }
+
+    /// Get the confirmed transcript hash of the group's current state.
+    pub fn confirmed_transcript_hash(&self) -> &[u8] {
+        &self.confirmed_transcript_hash
+    }
+
+    /// Serialize the group context to the encoding used by the key schedule and
+    /// by signed [`GroupInfo`](crate::group::GroupInfo) messages.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        Ok(self.mls_encode_to_vec()?)
+    }
+
+    /// Deserialize a group context from bytes produced by [`GroupContext::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
+        Ok(Self::mls_decode(&mut &*bytes)?)
+    }
 }
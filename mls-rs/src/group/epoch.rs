@@ -86,7 +86,7 @@ pub(crate) struct SenderDataSecret(
 
 impl Debug for SenderDataSecret {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        mls_rs_core::debug::pretty_bytes(&self.0)
+        mls_rs_core::debug::pretty_secret(&self.0)
             .named("SenderDataSecret")
             .fmt(f)
     }
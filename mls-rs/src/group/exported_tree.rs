@@ -5,7 +5,7 @@
 use alloc::{borrow::Cow, vec::Vec};
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 
-use crate::{client::MlsError, tree_kem::node::NodeVec};
+use crate::{client::MlsError, tree_kem::node::NodeVec, CipherSuiteProvider};
 
 #[cfg_attr(
     all(feature = "ffi", not(test)),
@@ -35,6 +35,21 @@ impl<'a> ExportedTree<'a> {
     pub fn into_owned(self) -> ExportedTree<'static> {
         ExportedTree(Cow::Owned(self.0.into_owned()))
     }
+
+    /// Compute the
+    /// [tree hash](https://www.rfc-editor.org/rfc/rfc9420.html#name-tree-hashes)
+    /// of this tree.
+    ///
+    /// The result can be compared with [`GroupContext::tree_hash`](crate::group::GroupContext::tree_hash)
+    /// to check that a tree matches a group's state. This does not validate the tree.
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn tree_hash<P: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &P,
+    ) -> Result<Vec<u8>, MlsError> {
+        self.0.tree_hash(cipher_suite_provider).await
+    }
 }
 
 #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen)]
@@ -41,6 +41,12 @@ use crate::group::{
     PreSharedKeyProposal, {JustPreSharedKeyID, PreSharedKeyID},
 };
 
+#[cfg(feature = "external_client")]
+use crate::external_client::{ExternalClientConfig, ExternalGroup};
+
+#[cfg(feature = "external_client")]
+use super::GroupContext;
+
 use super::{validate_tree_and_info_joiner, ExportedTree};
 
 /// A builder that aids with the construction of an external commit.
@@ -51,6 +57,8 @@ pub struct ExternalCommitBuilder<C: ClientConfig> {
     leaf_node_extensions: ExtensionList,
     config: C,
     tree_data: Option<ExportedTree<'static>>,
+    #[cfg(feature = "external_client")]
+    observed_context: Option<GroupContext>,
     to_remove: Option<u32>,
     #[cfg(feature = "psk")]
     external_psks: Vec<ExternalPskId>,
@@ -69,6 +77,8 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
     ) -> Self {
         Self {
             tree_data: None,
+            #[cfg(feature = "external_client")]
+            observed_context: None,
             to_remove: None,
             authenticated_data: Vec::new(),
             signer,
@@ -94,6 +104,27 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
         }
     }
 
+    #[cfg(feature = "external_client")]
+    #[must_use]
+    /// Join a group that was so far tracked by `observer`, reusing its ratchet
+    /// tree instead of the one in the GroupInfo message or provided with
+    /// [`Self::with_tree_data`].
+    ///
+    /// The GroupInfo message passed to [`Self::build`] is still required as it
+    /// provides the external public key of the group, but it does not need to
+    /// contain the ratchet tree. Building fails if the GroupInfo message is not
+    /// for the current epoch of `observer`, which must therefore have processed
+    /// all commits up to that epoch.
+    pub fn with_observer<E: ExternalClientConfig>(self, observer: &ExternalGroup<E>) -> Self {
+        let state = &observer.state;
+
+        Self {
+            tree_data: Some(ExportedTree::new(state.public_tree.nodes.clone())),
+            observed_context: Some(state.context.clone()),
+            ..self
+        }
+    }
+
     #[must_use]
     /// Propose the removal of an old version of the client as part of the external commit.
     /// Only one such proposal is allowed.
@@ -175,6 +206,23 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
             .get_as::<ExternalPubExt>()?
             .ok_or(MlsError::MissingExternalPubExtension)?;
 
+        #[cfg(feature = "external_client")]
+        if let Some(observed) = &self.observed_context {
+            let context = &group_info.group_context;
+
+            if context.group_id != observed.group_id {
+                return Err(MlsError::GroupIdMismatch);
+            }
+
+            if context.epoch != observed.epoch {
+                return Err(MlsError::InvalidEpoch);
+            }
+
+            if context != observed {
+                return Err(MlsError::ObservedStateMismatch);
+            }
+        }
+
         let public_tree = validate_tree_and_info_joiner(
             protocol_version,
             &group_info,
@@ -267,6 +315,7 @@ impl<C: ClientConfig> ExternalCommitBuilder<C> {
                 None,
                 None,
                 None,
+                None,
             )
             .await?;
 
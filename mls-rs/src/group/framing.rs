@@ -6,7 +6,11 @@ use core::ops::Deref;
 
 use crate::{client::MlsError, tree_kem::node::LeafIndex, KeyPackage, KeyPackageRef};
 
-use super::{Commit, FramedContentAuthData, GroupInfo, MembershipTag, Welcome};
+use super::{
+    message_signature::AuthenticatedContent,
+    message_verifier::{verify_auth_content_signature, SignaturePublicKeysContainer},
+    Commit, ExportedTree, FramedContentAuthData, GroupContext, GroupInfo, MembershipTag, Welcome,
+};
 
 #[cfg(feature = "by_ref_proposal")]
 use crate::{group::Proposal, mls_rules::ProposalRef};
@@ -165,11 +169,16 @@ impl MlsSize for PublicMessage {
 impl MlsEncode for PublicMessage {
     fn mls_encode(&self, writer: &mut Vec<u8>) -> Result<(), mls_rs_codec::Error> {
         self.content.mls_encode(writer)?;
-        self.auth.mls_encode(writer)?;
-
-        self.membership_tag
-            .as_ref()
-            .map_or(Ok(()), |tag| tag.mls_encode(writer))
+        self.auth
+            .mls_encode_for(writer, self.content.content_type())?;
+
+        // The membership tag is decoded based on the sender, so it must be present exactly
+        // when the sender is a member.
+        match (&self.content.sender, &self.membership_tag) {
+            (Sender::Member(_), Some(tag)) => tag.mls_encode(writer),
+            (Sender::Member(_), None) | (_, Some(_)) => Err(mls_rs_codec::Error::Custom(7)),
+            (_, None) => Ok(()),
+        }
     }
 }
 
@@ -222,7 +231,8 @@ impl MlsEncode for PrivateMessageContent {
             Content::Commit(c) => c.mls_encode(writer),
         }?;
 
-        self.auth.mls_encode(writer)?;
+        self.auth
+            .mls_encode_for(writer, self.content.content_type())?;
 
         Ok(())
     }
@@ -464,6 +474,32 @@ impl MlsMessage {
         }
     }
 
+    /// Whether this message is a public or private commit.
+    pub(crate) fn is_commit(&self) -> bool {
+        match &self.payload {
+            MlsMessagePayload::Plain(p) => p.content.content_type() == ContentType::Commit,
+            #[cfg(feature = "private_message")]
+            MlsMessagePayload::Cipher(c) => c.content_type == ContentType::Commit,
+            _ => false,
+        }
+    }
+
+    /// The sender of this message if it is a public commit.
+    ///
+    /// Returns `None` for any other message, including private commits whose sender
+    /// can only be learned by decrypting them. The sender of any processed commit is
+    /// available as
+    /// [`CommitMessageDescription::committer`](super::CommitMessageDescription::committer).
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    pub fn commit_sender(&self) -> Option<Sender> {
+        match &self.payload {
+            MlsMessagePayload::Plain(p) if p.content.content_type() == ContentType::Commit => {
+                Some(p.content.sender)
+            }
+            _ => None,
+        }
+    }
+
     /// Deserialize a message from transport.
     #[inline(never)]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, MlsError> {
@@ -475,6 +511,12 @@ impl MlsMessage {
         self.mls_encode_to_vec().map_err(Into::into)
     }
 
+    /// Length in bytes of the message serialized with [`Self::to_bytes`],
+    /// computed without serializing the message.
+    pub fn serialized_len(&self) -> usize {
+        self.mls_encoded_len()
+    }
+
     /// If this is a plaintext commit message, return all custom proposals committed by value.
     /// If this is not a plaintext or not a commit, this returns an empty list.
     #[cfg(feature = "custom_proposal")]
@@ -511,6 +553,54 @@ impl MlsMessage {
         kp.to_reference(cipher_suite).await.map(Some)
     }
 
+    /// If this is a [`WireFormat::PublicMessage`], verify the signature of its content
+    /// without processing it.
+    ///
+    /// The signature is checked using the public key of the sender found in `tree`, or in the
+    /// [external senders extension](crate::extension::built_in::ExternalSendersExt) of
+    /// `group_context` for external senders. Both `tree` and `group_context` must belong to
+    /// the epoch of the message. The membership tag is not checked as it requires the
+    /// group's secrets.
+    ///
+    /// Returns [`MlsError::TreeHashMismatch`] if the tree hash of `tree` does not match
+    /// `group_context` and [`MlsError::UnexpectedMessageType`] for any other wire format.
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn verify_content_signature<P: CipherSuiteProvider>(
+        &self,
+        tree: &ExportedTree<'_>,
+        group_context: &GroupContext,
+        cipher_suite_provider: &P,
+    ) -> Result<(), MlsError> {
+        let MlsMessagePayload::Plain(plaintext) = &self.payload else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        if plaintext.content.group_id != group_context.group_id {
+            return Err(MlsError::GroupIdMismatch);
+        }
+
+        if plaintext.content.epoch != group_context.epoch {
+            return Err(MlsError::InvalidEpoch);
+        }
+
+        if tree.tree_hash(cipher_suite_provider).await? != group_context.tree_hash {
+            return Err(MlsError::TreeHashMismatch);
+        }
+
+        let auth_content = AuthenticatedContent::from(plaintext.clone());
+
+        verify_auth_content_signature(
+            cipher_suite_provider,
+            SignaturePublicKeysContainer::RatchetTree(&tree.0),
+            group_context,
+            &auth_content,
+            #[cfg(feature = "by_ref_proposal")]
+            &super::message_verifier::external_signers(group_context),
+        )
+        .await
+    }
+
     /// If this is a plaintext proposal, return the proposal reference that can be matched e.g. with
     /// [`NewEpoch::unused_proposals`](super::NewEpoch::unused_proposals).
     #[cfg(feature = "by_ref_proposal")]
@@ -675,17 +765,27 @@ pub(crate) mod test_utils {
 #[cfg(feature = "private_message")]
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
     use assert_matches::assert_matches;
 
     use crate::{
-        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client::test_utils::{test_client_with_key_pkg, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
         crypto::test_utils::test_cipher_suite_provider,
         group::{
             framing::test_utils::get_test_ciphertext_content,
-            proposal_ref::test_utils::auth_content_from_proposal, RemoveProposal,
+            proposal_ref::test_utils::auth_content_from_proposal,
+            test_utils::{process_commit, test_group, test_n_member_group},
+            CommitMessageDescription, ConfirmationTag, ReceivedMessage, RemoveProposal,
         },
     };
 
+    #[cfg(feature = "private_message")]
+    use crate::group::{
+        mls_rules::{DefaultMlsRules, EncryptionOptions},
+        padding::PaddingMode,
+        test_utils::test_group_custom_config,
+    };
+
     use super::*;
 
     #[test]
@@ -745,4 +845,403 @@ mod tests {
 
         assert_eq!(computed_ref, expected_ref.to_vec());
     }
+
+    fn assert_round_trip(message: &MlsMessage) {
+        let bytes = message.to_bytes().unwrap();
+        let decoded = MlsMessage::from_bytes(&bytes).unwrap();
+
+        assert_eq!(&decoded, message);
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn message_encoding_round_trips() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        assert_round_trip(&key_package);
+
+        // Proposal sent by a member carries a membership tag
+        #[cfg(feature = "by_ref_proposal")]
+        {
+            let proposal = group.propose_update(vec![]).await.unwrap();
+            assert_round_trip(&proposal);
+        }
+
+        // Commit carries a confirmation tag
+        let commit_output = group
+            .commit_builder()
+            .add_member(key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert_round_trip(&commit_output.commit_message);
+        assert_round_trip(&commit_output.welcome_messages[0]);
+
+        group.apply_pending_commit().await.unwrap();
+
+        let application = group
+            .encrypt_application_message(b"hello", vec![])
+            .await
+            .unwrap();
+
+        assert_round_trip(&application);
+
+        let group_info = group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+        assert_round_trip(&group_info);
+
+        // External commit has a confirmation tag but no membership tag
+        let (carol, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        let (_, external_commit) = carol.commit_external(group_info).await.unwrap();
+        assert_round_trip(&external_commit);
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[test]
+    fn message_with_unknown_proposal_type_is_preserved() {
+        use crate::group::proposal::ProposalType;
+
+        let proposal = Proposal::Custom(CustomProposal::new(
+            ProposalType::new(0x7fff),
+            b"opaque body".to_vec(),
+        ));
+
+        let auth_content = auth_content_from_proposal(proposal.clone(), Sender::External(0));
+
+        let message = MlsMessage {
+            version: TEST_PROTOCOL_VERSION,
+            payload: MlsMessagePayload::Plain(PublicMessage {
+                content: auth_content.content,
+                auth: auth_content.auth,
+                membership_tag: None,
+            }),
+        };
+
+        let bytes = message.to_bytes().unwrap();
+        let decoded = MlsMessage::from_bytes(&bytes).unwrap();
+
+        assert_matches!(
+            &decoded.payload,
+            MlsMessagePayload::Plain(PublicMessage {
+                content: FramedContent { content: Content::Proposal(p), .. },
+                ..
+            }) if **p == proposal
+        );
+
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn encoding_rejects_tags_that_would_not_be_decoded() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let tag: MembershipTag = cs.mac(&[1, 2, 3], &[1, 2, 3]).await.unwrap().into();
+
+        let external = auth_content_from_proposal(
+            Proposal::Remove(RemoveProposal {
+                to_remove: LeafIndex(0),
+            }),
+            Sender::External(0),
+        );
+
+        let message = PublicMessage {
+            content: external.content.clone(),
+            auth: external.auth.clone(),
+            membership_tag: Some(tag.clone()),
+        };
+
+        assert_matches!(
+            message.mls_encode_to_vec(),
+            Err(mls_rs_codec::Error::Custom(_))
+        );
+
+        let mut member = PublicMessage {
+            content: external.content,
+            auth: external.auth,
+            membership_tag: None,
+        };
+
+        member.content.sender = Sender::Member(0);
+
+        assert_matches!(
+            member.mls_encode_to_vec(),
+            Err(mls_rs_codec::Error::Custom(_))
+        );
+
+        member.membership_tag = Some(tag);
+        member.auth.confirmation_tag = Some(ConfirmationTag::empty(&cs).await);
+
+        assert_matches!(
+            member.mls_encode_to_vec(),
+            Err(mls_rs_codec::Error::Custom(_))
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn content_signature_of_handshake_message_is_verified() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (bob, _) = alice.join("bob").await;
+
+        let commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+        commit
+            .verify_content_signature(
+                &bob.export_tree(),
+                bob.context(),
+                &bob.cipher_suite_provider,
+            )
+            .await
+            .unwrap();
+
+        // Verification does not change the state of the verifier
+        assert_eq!(bob.context().epoch, 1);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn content_signature_of_tampered_message_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (bob, _) = alice.join("bob").await;
+
+        let mut commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+
+        let MlsMessagePayload::Plain(ref mut plaintext) = commit.payload else {
+            panic!("expected public message");
+        };
+
+        plaintext.content.authenticated_data = b"tampered".to_vec();
+
+        let res = commit
+            .verify_content_signature(
+                &bob.export_tree(),
+                bob.context(),
+                &bob.cipher_suite_provider,
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn content_signature_with_stale_tree_is_rejected() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let stale_tree = bob.export_tree().into_owned();
+
+        let (_, add) = alice.join("carol").await;
+        bob.process_message(add).await.unwrap();
+
+        let commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+
+        let res = commit
+            .verify_content_signature(&stale_tree, bob.context(), &bob.cipher_suite_provider)
+            .await;
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn content_signature_from_blank_leaf_is_rejected() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let remove = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
+
+        groups[0].apply_pending_commit().await.unwrap();
+        process_commit(&mut groups[1..], remove, 1).await;
+
+        let mut commit = groups[2].commit(Vec::new()).await.unwrap().commit_message;
+
+        let MlsMessagePayload::Plain(ref mut plaintext) = commit.payload else {
+            panic!("expected public message");
+        };
+
+        plaintext.content.sender = Sender::Member(1);
+
+        let res = commit
+            .verify_content_signature(
+                &groups[0].export_tree(),
+                groups[0].context(),
+                &groups[0].cipher_suite_provider,
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::ExpectedNode));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn message_round_trip_regression_corpus() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit_output = alice
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let group_info = alice.group_info_message(true).await.unwrap();
+
+        let mut messages = vec![
+            key_package,
+            commit_output.commit_message,
+            commit_output.welcome_messages[0].clone(),
+            group_info,
+        ];
+
+        #[cfg(feature = "private_message")]
+        {
+            let application_message = alice
+                .encrypt_application_message(b"hello", b"aad".to_vec())
+                .await
+                .unwrap();
+
+            messages.push(application_message);
+        }
+
+        #[cfg(feature = "by_ref_proposal")]
+        messages.push(alice.propose_update(vec![]).await.unwrap());
+
+        let mut corpus = vec![
+            // Empty input and a bare protocol version
+            vec![],
+            vec![0x00, 0x01],
+            // Unknown wire format
+            vec![0x00, 0x01, 0xff, 0xff],
+            // Key package with a non-minimal varint length prefix
+            vec![0x00, 0x01, 0x00, 0x05, 0x40, 0x01],
+        ];
+
+        for message in messages {
+            let bytes = message.to_bytes().unwrap();
+
+            // Every truncation, trailing data and a flipped byte in each position
+            corpus.extend((0..bytes.len()).map(|len| bytes[..len].to_vec()));
+            corpus.push([bytes.as_slice(), &[0xff; 4]].concat());
+
+            corpus.extend((0..bytes.len()).map(|i| {
+                let mut bytes = bytes.clone();
+                bytes[i] ^= 0x01;
+                bytes
+            }));
+
+            corpus.push(bytes);
+        }
+
+        corpus
+            .iter()
+            .for_each(|data| crate::test_utils::check_message_round_trip(data));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn public_commit_sender_is_readable() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+
+        assert_eq!(commit.commit_sender(), Some(Sender::Member(1)));
+
+        let processed = groups[0].process_message(commit).await.unwrap();
+
+        assert_matches!(
+            processed,
+            ReceivedMessage::Commit(CommitMessageDescription { committer: 1, .. })
+        );
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn private_commit_sender_requires_processing() {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.mls_rules(
+                DefaultMlsRules::default()
+                    .with_encryption_options(EncryptionOptions::new(true, PaddingMode::None)),
+            )
+        })
+        .await;
+
+        let (mut bob, _) = alice.join("bob").await;
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+
+        assert_eq!(commit.wire_format(), WireFormat::PrivateMessage);
+        assert_eq!(commit.commit_sender(), None);
+
+        let processed = bob.process_message(commit).await.unwrap();
+
+        assert_matches!(
+            processed,
+            ReceivedMessage::Commit(CommitMessageDescription { committer: 0, .. })
+        );
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn application_message_has_no_commit_sender() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let message = group
+            .encrypt_application_message(b"hello", vec![])
+            .await
+            .unwrap();
+
+        assert_eq!(message.commit_sender(), None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn serialized_len_matches_encoding() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit_output = alice
+            .commit_builder()
+            .add_member(key_package.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let group_info = alice.group_info_message(true).await.unwrap();
+
+        let mut messages = vec![
+            key_package,
+            commit_output.commit_message,
+            commit_output.welcome_messages[0].clone(),
+            group_info,
+        ];
+
+        #[cfg(feature = "private_message")]
+        messages.push(
+            alice
+                .encrypt_application_message(b"hello", vec![])
+                .await
+                .unwrap(),
+        );
+
+        for message in messages {
+            assert_eq!(message.serialized_len(), message.to_bytes().unwrap().len());
+        }
+    }
 }
@@ -5,11 +5,18 @@
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
-use mls_rs_core::extension::ExtensionList;
+use mls_rs_core::{
+    crypto::{CipherSuiteProvider, SignatureSecretKey},
+    error::IntoAnyError,
+    extension::ExtensionList,
+};
 
-use crate::{signer::Signable, tree_kem::node::LeafIndex};
+use crate::{
+    client::MlsError, extension::RatchetTreeExt, protocol_version::ProtocolVersion,
+    signer::Signable, tree_kem::node::LeafIndex,
+};
 
-use super::{ConfirmationTag, GroupContext};
+use super::{ConfirmationTag, ExportedTree, GroupContext};
 
 #[derive(Clone, PartialEq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -58,6 +65,57 @@ impl GroupInfo {
     pub fn sender(&self) -> u32 {
         *self.signer
     }
+
+    /// Replace the group info extensions.
+    ///
+    /// This invalidates the signature, so the group info must be signed again with
+    /// [`GroupInfo::resign`] before it is published.
+    pub fn set_extensions(&mut self, extensions: ExtensionList) {
+        self.extensions = extensions;
+    }
+
+    /// Sign this group info again with `signer`, for instance after replacing its
+    /// extensions with [`GroupInfo::set_extensions`].
+    ///
+    /// `tree` is the ratchet tree of the group. It is only used if the group info
+    /// extensions don't contain the tree. The tree must match the tree hash of the group
+    /// context, and `signer` must be the secret key of the member at
+    /// [`GroupInfo::sender`] in that tree, otherwise
+    /// [`MlsError::GroupInfoSignerMismatch`] is returned.
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn resign<P: CipherSuiteProvider>(
+        &mut self,
+        cipher_suite_provider: &P,
+        signer: &SignatureSecretKey,
+        tree: Option<ExportedTree<'_>>,
+    ) -> Result<(), MlsError> {
+        if cipher_suite_provider.cipher_suite() != self.group_context.cipher_suite {
+            return Err(MlsError::CipherSuiteMismatch);
+        }
+
+        let tree = match self.extensions.get_as::<RatchetTreeExt>()? {
+            Some(ext) => ext.tree_data,
+            None => tree.ok_or(MlsError::RatchetTreeNotFound)?,
+        };
+
+        if tree.tree_hash(cipher_suite_provider).await? != self.group_context.tree_hash {
+            return Err(MlsError::TreeHashMismatch);
+        }
+
+        let public_key = cipher_suite_provider
+            .signature_key_derive_public(signer)
+            .await
+            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+        let leaf = tree.0.borrow_as_leaf(self.signer)?;
+
+        if leaf.signing_identity.signature_key != public_key {
+            return Err(MlsError::GroupInfoSignerMismatch);
+        }
+
+        self.sign(cipher_suite_provider, signer, &()).await
+    }
 }
 
 #[derive(MlsEncode, MlsSize)]
@@ -92,4 +150,8 @@ impl<'a> Signable<'a> for GroupInfo {
     fn write_signature(&mut self, signature: Vec<u8>) {
         self.signature = signature
     }
+
+    fn protocol_version(&self, _context: &Self::SigningContext) -> ProtocolVersion {
+        self.group_context.protocol_version
+    }
 }
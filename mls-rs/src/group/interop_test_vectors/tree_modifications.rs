@@ -9,7 +9,7 @@ use mls_rs_codec::{MlsDecode, MlsEncode};
 use mls_rs_core::crypto::CipherSuite;
 
 use crate::{
-    client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+    client::test_utils::TEST_PROTOCOL_VERSION,
     crypto::test_utils::{test_cipher_suite_provider, try_test_cipher_suite_provider},
     group::{
         proposal::{AddProposal, Proposal, ProposalOrRef, RemoveProposal, UpdateProposal},
@@ -32,21 +32,30 @@ struct TreeModsTestCase {
     #[serde(with = "hex::serde")]
     pub proposal: Vec<u8>,
     pub proposal_sender: u32,
+    // The hashes in the committed vectors are computed independently of this crate by
+    // `test_data/tree_hashes.py`, which can also check or refill them.
+    #[serde(with = "hex::serde")]
+    pub tree_hash_before: Vec<u8>,
     #[serde(with = "hex::serde")]
     pub tree_after: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    pub tree_hash_after: Vec<u8>,
 }
 
 impl TreeModsTestCase {
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     #[cfg_attr(coverage_nightly, coverage(off))]
-    async fn new(tree_before: TreeKemPublic, proposal: Proposal, proposal_sender: u32) -> Self {
-        let tree_after = apply_proposal(proposal.clone(), proposal_sender, &tree_before).await;
+    async fn new(mut tree_before: TreeKemPublic, proposal: Proposal, proposal_sender: u32) -> Self {
+        let cs = test_cipher_suite_provider(CipherSuite::CURVE25519_AES128);
+        let mut tree_after = apply_proposal(proposal.clone(), proposal_sender, &tree_before).await;
 
         Self {
             tree_before: tree_before.nodes.mls_encode_to_vec().unwrap(),
             proposal: proposal.mls_encode_to_vec().unwrap(),
-            tree_after: tree_after.nodes.mls_encode_to_vec().unwrap(),
             proposal_sender,
+            tree_hash_before: tree_before.tree_hash(&cs).await.unwrap(),
+            tree_after: tree_after.nodes.mls_encode_to_vec().unwrap(),
+            tree_hash_after: tree_after.tree_hash(&cs).await.unwrap(),
         }
     }
 }
@@ -55,7 +64,7 @@ impl TreeModsTestCase {
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn generate_tree_mods_tests() -> Vec<TreeModsTestCase> {
     let mut test_vector = vec![];
-    let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+    let cs = test_cipher_suite_provider(CipherSuite::CURVE25519_AES128);
 
     // Update
     let tree_before = TreeWithSigners::make_full_tree(8, &cs).await;
@@ -107,24 +116,54 @@ async fn tree_modifications_interop() {
     let test_cases: Vec<TreeModsTestCase> =
         load_test_case_json!(tree_modifications_interop, generate_tree_mods_tests().await);
 
-    for test_case in test_cases.into_iter() {
+    let cs = test_cipher_suite_provider(CipherSuite::CURVE25519_AES128);
+
+    for (i, test_case) in test_cases.into_iter().enumerate() {
         let nodes = NodeVec::mls_decode(&mut &*test_case.tree_before).unwrap();
 
-        let tree_before =
+        let mut tree_before =
             TreeKemPublic::import_node_data(nodes, &BasicIdentityProvider, &Default::default())
                 .await
                 .unwrap();
 
+        assert_tree_hash(
+            i,
+            "before",
+            &test_case.tree_hash_before,
+            &tree_before.tree_hash(&cs).await.unwrap(),
+        );
+
         let proposal = Proposal::mls_decode(&mut &*test_case.proposal).unwrap();
 
-        let tree_after = apply_proposal(proposal, test_case.proposal_sender, &tree_before).await;
+        let mut tree_after =
+            apply_proposal(proposal, test_case.proposal_sender, &tree_before).await;
+
+        assert_tree_hash(
+            i,
+            "after",
+            &test_case.tree_hash_after,
+            &tree_after.tree_hash(&cs).await.unwrap(),
+        );
 
         let tree_after = tree_after.nodes.mls_encode_to_vec().unwrap();
 
-        assert_eq!(tree_after, test_case.tree_after);
+        assert_eq!(
+            tree_after, test_case.tree_after,
+            "tree diverged at step {i}"
+        );
     }
 }
 
+fn assert_tree_hash(step: usize, stage: &str, expected: &[u8], actual: &[u8]) {
+    assert_eq!(
+        expected,
+        actual,
+        "tree hash {stage} step {step} diverged: expected {}, actual {}",
+        hex::encode(expected),
+        hex::encode(actual)
+    );
+}
+
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 async fn apply_proposal(
     proposal: Proposal,
@@ -145,7 +184,12 @@ async fn apply_proposal(
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn generate_add() -> Proposal {
-    let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "Roger").await;
+    let key_package = test_key_package(
+        TEST_PROTOCOL_VERSION,
+        CipherSuite::CURVE25519_AES128,
+        "Roger",
+    )
+    .await;
     Proposal::Add(Box::new(AddProposal { key_package }))
 }
 
@@ -163,7 +207,7 @@ async fn generate_update(i: u32, tree: &TreeWithSigners) -> Proposal {
 
     leaf_node
         .update(
-            &test_cipher_suite_provider(TEST_CIPHER_SUITE),
+            &test_cipher_suite_provider(CipherSuite::CURVE25519_AES128),
             TEST_GROUP,
             i,
             Some(default_properties()),
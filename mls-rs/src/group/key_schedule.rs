@@ -5,6 +5,7 @@
 use crate::client::MlsError;
 use crate::extension::ExternalPubExt;
 use crate::group::{GroupContext, MembershipTag};
+use crate::label::mls_label;
 use crate::psk::secret::PskSecret;
 #[cfg(feature = "psk")]
 use crate::psk::PreSharedKey;
@@ -49,19 +50,19 @@ impl Debug for KeySchedule {
         f.debug_struct("KeySchedule")
             .field(
                 "exporter_secret",
-                &mls_rs_core::debug::pretty_bytes(&self.exporter_secret),
+                &mls_rs_core::debug::pretty_secret(&self.exporter_secret),
             )
             .field(
                 "authentication_secret",
-                &mls_rs_core::debug::pretty_bytes(&self.authentication_secret),
+                &mls_rs_core::debug::pretty_secret(&self.authentication_secret),
             )
             .field(
                 "external_secret",
-                &mls_rs_core::debug::pretty_bytes(&self.external_secret),
+                &mls_rs_core::debug::pretty_secret(&self.external_secret),
             )
             .field(
                 "membership_key",
-                &mls_rs_core::debug::pretty_bytes(&self.membership_key),
+                &mls_rs_core::debug::pretty_secret(&self.membership_key),
             )
             .field("init_secret", &self.init_secret)
             .finish()
@@ -292,10 +293,15 @@ struct Label<'a> {
 }
 
 impl<'a> Label<'a> {
-    fn new(length: u16, label: &'a [u8], context: &'a [u8]) -> Self {
+    fn new<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        length: u16,
+        label: &'a [u8],
+        context: &'a [u8],
+    ) -> Self {
         Self {
             length,
-            label: [b"MLS 1.0 ", label].concat(),
+            label: mls_label(cipher_suite_provider, label),
             context,
         }
     }
@@ -311,7 +317,7 @@ pub(crate) async fn kdf_expand_with_label<P: CipherSuiteProvider>(
 ) -> Result<Zeroizing<Vec<u8>>, MlsError> {
     let extract_size = cipher_suite_provider.kdf_extract_size();
     let len = len.unwrap_or(extract_size);
-    let label = Label::new(len as u16, label, context);
+    let label = Label::new(cipher_suite_provider, len as u16, label, context);
 
     cipher_suite_provider
         .kdf_expand(secret, &label.mls_encode_to_vec()?, len)
@@ -333,7 +339,7 @@ pub(crate) struct JoinerSecret(#[mls_codec(with = "mls_rs_codec::byte_vec")] Zer
 
 impl Debug for JoinerSecret {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        mls_rs_core::debug::pretty_bytes(&self.0)
+        mls_rs_core::debug::pretty_secret(&self.0)
             .named("JoinerSecret")
             .fmt(f)
     }
@@ -379,8 +385,6 @@ impl<'a, P: CipherSuiteProvider> SecretsProducer<'a, P> {
     }
 }
 
-const EXPORTER_CONTEXT: &[u8] = b"MLS 1.0 external init secret";
-
 #[derive(Clone, Eq, PartialEq, MlsEncode, MlsDecode, MlsSize, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InitSecret(
@@ -391,7 +395,7 @@ pub struct InitSecret(
 
 impl Debug for InitSecret {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        mls_rs_core::debug::pretty_bytes(&self.0)
+        mls_rs_core::debug::pretty_secret(&self.0)
             .named("InitSecret")
             .fmt(f)
     }
@@ -410,7 +414,10 @@ impl InitSecret {
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
         let init_secret = context
-            .export(EXPORTER_CONTEXT, cipher_suite.kdf_extract_size())
+            .export(
+                &mls_label(cipher_suite, b"external init secret"),
+                cipher_suite.kdf_extract_size(),
+            )
             .await
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
@@ -430,7 +437,10 @@ impl InitSecret {
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
         context
-            .export(EXPORTER_CONTEXT, cipher_suite.kdf_extract_size())
+            .export(
+                &mls_label(cipher_suite, b"external init secret"),
+                cipher_suite.kdf_extract_size(),
+            )
             .await
             .map(Zeroizing::new)
             .map(InitSecret)
@@ -492,6 +502,39 @@ impl<'a, P: CipherSuiteProvider> WelcomeSecret<'a, P> {
     }
 }
 
+/// Joiner and welcome secrets from which the current epoch was entered.
+#[cfg(feature = "test_exports")]
+#[derive(Clone)]
+pub(crate) struct JoinSecrets {
+    joiner_secret: JoinerSecret,
+    welcome_secret: Zeroizing<Vec<u8>>,
+}
+
+#[cfg(feature = "test_exports")]
+impl JoinSecrets {
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn new<P: CipherSuiteProvider>(
+        cipher_suite: &P,
+        joiner_secret: &JoinerSecret,
+        psk_secret: &PskSecret,
+    ) -> Result<Self, MlsError> {
+        let welcome_secret = get_welcome_secret(cipher_suite, joiner_secret, psk_secret).await?;
+
+        Ok(Self {
+            joiner_secret: joiner_secret.clone(),
+            welcome_secret,
+        })
+    }
+
+    pub(crate) fn joiner_secret(&self) -> &[u8] {
+        &self.joiner_secret.0
+    }
+
+    pub(crate) fn welcome_secret(&self) -> &[u8] {
+        &self.welcome_secret
+    }
+}
+
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 async fn get_welcome_secret<P: CipherSuiteProvider>(
     cipher_suite: &P,
@@ -570,6 +613,7 @@ mod tests {
         get_welcome_secret, kdf_derive_secret, kdf_expand_with_label,
     };
     use crate::group::GroupContext;
+    use alloc::format;
     use alloc::string::String;
     use alloc::vec::Vec;
     use mls_rs_codec::MlsEncode;
@@ -579,12 +623,11 @@ mod tests {
     #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
     use crate::{
         crypto::test_utils::{test_cipher_suite_provider, TestCryptoProvider},
-        group::{
-            key_schedule::KeyScheduleDerivationResult, test_utils::random_bytes, InitSecret,
-            PskSecret,
-        },
+        group::{test_utils::random_bytes, InitSecret, PskSecret},
     };
 
+    use crate::group::key_schedule::KeyScheduleDerivationResult;
+
     #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
     use alloc::{string::ToString, vec};
 
@@ -680,15 +723,15 @@ mod tests {
                     cipher_suite: cs_provider.cipher_suite(),
                     group_id: test_case.group_id.clone(),
                     epoch: i as u64,
-                    tree_hash: epoch.tree_hash,
-                    confirmed_transcript_hash: epoch.confirmed_transcript_hash.into(),
+                    tree_hash: epoch.tree_hash.clone(),
+                    confirmed_transcript_hash: epoch.confirmed_transcript_hash.clone().into(),
                     extensions: ExtensionList::new(),
                 };
 
                 assert_eq!(context.mls_encode_to_vec().unwrap(), epoch.group_context);
 
-                let psk = epoch.psk_secret.into();
-                let commit = epoch.commit_secret.into();
+                let psk = epoch.psk_secret.clone().into();
+                let commit = epoch.commit_secret.clone().into();
 
                 let key_schedule_res = KeySchedule::from_key_schedule(
                     &key_schedule,
@@ -702,8 +745,6 @@ mod tests {
                 .await
                 .unwrap();
 
-                key_schedule = key_schedule_res.key_schedule;
-
                 let welcome =
                     get_welcome_secret(&cs_provider, &key_schedule_res.joiner_secret, &psk)
                         .await
@@ -711,63 +752,89 @@ mod tests {
 
                 assert_eq!(*welcome, epoch.welcome_secret);
 
-                let expected: Vec<u8> = key_schedule_res.joiner_secret.into();
+                let expected: Vec<u8> = key_schedule_res.joiner_secret.0.to_vec();
                 assert_eq!(epoch.joiner_secret, expected);
 
-                assert_eq!(&key_schedule.init_secret.0.to_vec(), &epoch.init_secret);
-
-                assert_eq!(
-                    epoch.sender_data_secret,
-                    *key_schedule_res.epoch_secrets.sender_data_secret.to_vec()
-                );
+                // A new member joining through a welcome message starts from the joiner secret
+                // and must arrive at the same epoch secrets as the committer.
+                let joiner_res = KeySchedule::from_joiner(
+                    &cs_provider,
+                    &key_schedule_res.joiner_secret,
+                    &context,
+                    #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+                    32,
+                    &psk,
+                )
+                .await
+                .unwrap();
 
-                #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
-                assert_eq!(
-                    epoch.encryption_secret,
-                    *key_schedule_res.epoch_secrets.secret_tree.get_root_secret()
-                );
+                assert_epoch_secrets(&joiner_res, &epoch, &cs_provider).await;
+                assert_epoch_secrets(&key_schedule_res, &epoch, &cs_provider).await;
 
-                assert_eq!(epoch.exporter_secret, key_schedule.exporter_secret.to_vec());
+                key_schedule = key_schedule_res.key_schedule;
+            }
+        }
+    }
 
-                assert_eq!(
-                    epoch.epoch_authenticator,
-                    key_schedule.authentication_secret.to_vec()
-                );
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn assert_epoch_secrets<P: CipherSuiteProvider>(
+        key_schedule_res: &KeyScheduleDerivationResult,
+        epoch: &KeyScheduleEpoch,
+        cs_provider: &P,
+    ) {
+        let key_schedule = &key_schedule_res.key_schedule;
 
-                assert_eq!(epoch.external_secret, key_schedule.external_secret.to_vec());
+        assert_eq!(&key_schedule.init_secret.0.to_vec(), &epoch.init_secret);
 
-                assert_eq!(
-                    epoch.confirmation_key,
-                    key_schedule_res.confirmation_key.to_vec()
-                );
+        assert_eq!(
+            epoch.sender_data_secret,
+            *key_schedule_res.epoch_secrets.sender_data_secret.to_vec()
+        );
 
-                assert_eq!(epoch.membership_key, key_schedule.membership_key.to_vec());
+        #[cfg(any(feature = "secret_tree_access", feature = "private_message"))]
+        assert_eq!(
+            epoch.encryption_secret,
+            *key_schedule_res.epoch_secrets.secret_tree.get_root_secret()
+        );
 
-                #[cfg(feature = "psk")]
-                {
-                    let expected: Vec<u8> =
-                        key_schedule_res.epoch_secrets.resumption_secret.to_vec();
+        assert_eq!(epoch.exporter_secret, key_schedule.exporter_secret.to_vec());
 
-                    assert_eq!(epoch.resumption_psk, expected);
-                }
+        assert_eq!(
+            epoch.epoch_authenticator,
+            key_schedule.authentication_secret.to_vec()
+        );
 
-                let (_external_sec, external_pub) = key_schedule
-                    .get_external_key_pair(&cs_provider)
-                    .await
-                    .unwrap();
+        assert_eq!(epoch.external_secret, key_schedule.external_secret.to_vec());
 
-                assert_eq!(epoch.external_pub, *external_pub);
+        assert_eq!(
+            epoch.confirmation_key,
+            key_schedule_res.confirmation_key.to_vec()
+        );
 
-                let exp = epoch.exporter;
+        assert_eq!(epoch.membership_key, key_schedule.membership_key.to_vec());
 
-                let exported = key_schedule
-                    .export_secret(exp.label.as_bytes(), &exp.context, exp.length, &cs_provider)
-                    .await
-                    .unwrap();
+        #[cfg(feature = "psk")]
+        {
+            let expected: Vec<u8> = key_schedule_res.epoch_secrets.resumption_secret.to_vec();
 
-                assert_eq!(exported.to_vec(), exp.secret);
-            }
+            assert_eq!(epoch.resumption_psk, expected);
         }
+
+        let (_external_sec, external_pub) = key_schedule
+            .get_external_key_pair(cs_provider)
+            .await
+            .unwrap();
+
+        assert_eq!(epoch.external_pub, *external_pub);
+
+        let exp = &epoch.exporter;
+
+        let exported = key_schedule
+            .export_secret(exp.label.as_bytes(), &exp.context, exp.length, cs_provider)
+            .await
+            .unwrap();
+
+        assert_eq!(exported.to_vec(), exp.secret);
     }
 
     #[cfg(all(not(mls_build_async), feature = "rfc_compliant"))]
@@ -985,4 +1052,16 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn debug_output_does_not_contain_secrets() {
+        let key_schedule = get_test_key_schedule(crate::client::test_utils::TEST_CIPHER_SUITE);
+        let secret = hex::encode(&*key_schedule.exporter_secret);
+        let init_secret = hex::encode(&*key_schedule.init_secret.0);
+
+        let output = format!("{key_schedule:?} {key_schedule:#?}");
+
+        assert!(!output.contains(&secret));
+        assert!(!output.contains(&init_secret));
+    }
 }
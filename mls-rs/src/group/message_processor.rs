@@ -14,7 +14,7 @@ use super::{
     state::GroupState,
     transcript_hash::InterimTranscriptHash,
     transcript_hashes, validate_group_info_member, GroupContext, GroupInfo, ReInitProposal,
-    RemoveProposal, Welcome,
+    RemoveProposal, RosterUpdate, Welcome,
 };
 use crate::{
     client::MlsError,
@@ -24,7 +24,7 @@ use crate::{
         leaf_node_validator::{LeafNodeValidator, ValidationContext},
         node::LeafIndex,
         path_secret::PathSecret,
-        validate_update_path, TreeKemPrivate, TreeKemPublic, ValidatedUpdatePath,
+        validate_update_path, TreeKemPrivate, TreeKemPublic, UpdatePath, ValidatedUpdatePath,
     },
     CipherSuiteProvider, KeyPackage,
 };
@@ -35,7 +35,8 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use mls_rs_core::{
-    identity::IdentityProvider, protocol_version::ProtocolVersion, psk::PreSharedKeyStorage,
+    error::IntoAnyError, identity::IdentityProvider, protocol_version::ProtocolVersion,
+    psk::PreSharedKeyStorage,
 };
 
 #[cfg(feature = "by_ref_proposal")]
@@ -60,6 +61,17 @@ pub(crate) struct ProvisionalState {
     pub(crate) unused_proposals: Vec<ProposalInfo<Proposal>>,
 }
 
+impl ProvisionalState {
+    pub(crate) fn check_group_size(&self, max_group_size: Option<u32>) -> Result<(), MlsError> {
+        match max_group_size {
+            Some(max) if self.public_tree.occupied_leaf_count() > max => {
+                Err(MlsError::GroupSizeLimitExceeded(max))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 //By default, the path field of a Commit MUST be populated. The path field MAY be omitted if
 //(a) it covers at least one proposal and (b) none of the proposals covered by the Commit are
 //of "path required" types. A proposal type requires a path if it cannot change the group
@@ -92,12 +104,32 @@ pub struct NewEpoch {
     pub prior_state: GroupState,
     pub applied_proposals: Vec<ProposalInfo<Proposal>>,
     pub unused_proposals: Vec<ProposalInfo<Proposal>>,
+    /// Members added, removed and updated by the commit.
+    pub roster_update: RosterUpdate,
+    /// True if the commit changed the group context extensions.
+    pub group_context_extensions_changed: bool,
+    /// Number of HPKE encryptions of path secrets in the commit's update path,
+    /// i.e. the total size of the resolutions of the committer's copath.
+    pub path_encryptions: usize,
 }
 
 impl NewEpoch {
-    fn new(prior_state: GroupState, provisional_state: &ProvisionalState) -> NewEpoch {
+    fn new(
+        prior_state: GroupState,
+        provisional_state: &ProvisionalState,
+        update_path: Option<&UpdatePath>,
+    ) -> NewEpoch {
         NewEpoch {
             epoch: provisional_state.group_context.epoch,
+            path_encryptions: update_path.map_or(0, |path| {
+                path.nodes
+                    .iter()
+                    .map(|node| node.encrypted_path_secret.len())
+                    .sum()
+            }),
+            roster_update: RosterUpdate::new(&prior_state.public_tree, provisional_state),
+            group_context_extensions_changed: provisional_state.group_context.extensions
+                != prior_state.context.extensions,
             prior_state,
             unused_proposals: provisional_state.unused_proposals.clone(),
             applied_proposals: provisional_state
@@ -127,6 +159,18 @@ impl NewEpoch {
     pub fn unused_proposals(&self) -> &[ProposalInfo<Proposal>] {
         &self.unused_proposals
     }
+
+    pub fn roster_update(&self) -> &RosterUpdate {
+        &self.roster_update
+    }
+
+    pub fn group_context_extensions_changed(&self) -> bool {
+        self.group_context_extensions_changed
+    }
+
+    pub fn path_encryptions(&self) -> usize {
+        self.path_encryptions
+    }
 }
 
 #[cfg_attr(
@@ -213,6 +257,8 @@ impl From<KeyPackage> for ReceivedMessage {
 pub struct ApplicationMessageDescription {
     /// Index of this user in the group state.
     pub sender_index: u32,
+    /// Epoch in which the message was sent.
+    pub epoch: u64,
     /// Received application data.
     data: ApplicationData,
     /// Plaintext authenticated data in the received MLS packet.
@@ -223,6 +269,7 @@ impl Debug for ApplicationMessageDescription {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ApplicationMessageDescription")
             .field("sender_index", &self.sender_index)
+            .field("epoch", &self.epoch)
             .field("data", &self.data)
             .field(
                 "authenticated_data",
@@ -271,6 +318,31 @@ impl Debug for CommitMessageDescription {
     }
 }
 
+#[cfg_attr(
+    all(feature = "ffi", not(test)),
+    safer_ffi_gen::ffi_type(clone, opaque)
+)]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+/// Result of previewing a commit with
+/// [`Group::preview_commit`](crate::group::Group::preview_commit).
+///
+/// The preview can be inspected and then either discarded or passed to
+/// [`Group::apply_previewed_commit`](crate::group::Group::apply_previewed_commit).
+pub struct CommitPreview {
+    /// Description of the commit as it would be processed by the group.
+    pub description: CommitMessageDescription,
+    pub(crate) message: MlsMessage,
+}
+
+#[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen)]
+impl CommitPreview {
+    /// The commit message that was previewed.
+    pub fn message(&self) -> &MlsMessage {
+        &self.message
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, MlsEncode, MlsDecode, MlsSize)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
@@ -526,13 +598,26 @@ pub(crate) trait MessageProcessor: Send + Sync {
         #[cfg(feature = "by_ref_proposal")] cache_proposal: bool,
         time_sent: Option<MlsTime>,
     ) -> Result<Self::OutputType, MlsError> {
+        let epoch = auth_content.content.epoch;
+
+        // Commits and proposals can only be applied in the epoch they were sent in
+        let is_handshake = matches!(auth_content.content.content, Content::Commit(_));
+
+        #[cfg(feature = "by_ref_proposal")]
+        let is_handshake =
+            is_handshake || matches!(auth_content.content.content, Content::Proposal(_));
+
+        if is_handshake && epoch != self.group_state().context.epoch {
+            return Err(MlsError::PastEpochMessage(epoch));
+        }
+
         let event = match auth_content.content.content {
             #[cfg(feature = "private_message")]
             Content::Application(data) => {
                 let authenticated_data = auth_content.content.authenticated_data;
                 let sender = auth_content.content.sender;
 
-                self.process_application_message(data, sender, authenticated_data)
+                self.process_application_message(data, sender, epoch, authenticated_data)
                     .and_then(Self::OutputType::try_from)
             }
             Content::Commit(_) => self
@@ -554,6 +639,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
         &self,
         data: ApplicationData,
         sender: Sender,
+        epoch: u64,
         authenticated_data: Vec<u8>,
     ) -> Result<ApplicationMessageDescription, MlsError> {
         let Sender::Member(sender_index) = sender else {
@@ -563,6 +649,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
         Ok(ApplicationMessageDescription {
             authenticated_data,
             sender_index,
+            epoch,
             data,
         })
     }
@@ -646,6 +733,8 @@ pub(crate) trait MessageProcessor: Send + Sync {
             )
             .await?;
 
+        provisional_state.check_group_size(self.max_group_size())?;
+
         let sender = commit_sender(&auth_content.content.sender, &provisional_state)?;
 
         //Verify that the path value is populated if the proposals vector contains any Update
@@ -655,7 +744,11 @@ pub(crate) trait MessageProcessor: Send + Sync {
         }
 
         if let Some(remove_proposal) = self.removal_proposal(&provisional_state) {
-            let new_epoch = NewEpoch::new(self.group_state().clone(), &provisional_state);
+            let new_epoch = NewEpoch::new(
+                self.group_state().clone(),
+                &provisional_state,
+                commit.path.as_ref(),
+            );
 
             return Ok(CommitMessageDescription {
                 is_external: matches!(auth_content.content.sender, Sender::NewMemberCommit),
@@ -676,6 +769,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 CommitEffect::NewEpoch(Box::new(NewEpoch::new(
                     self.group_state().clone(),
                     &provisional_state,
+                    commit.path.as_ref(),
                 )))
             };
 
@@ -694,6 +788,22 @@ pub(crate) trait MessageProcessor: Send + Sync {
             None => None,
         };
 
+        if let Some(update_path) = &update_path {
+            let authorized = self
+                .mls_rules()
+                .authorize_leaf_node(
+                    CommitDirection::Receive,
+                    &update_path.leaf_node,
+                    &self.group_state().public_tree.roster(),
+                    &provisional_state.group_context.extensions,
+                )
+                .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
+
+            if !authorized {
+                return Err(MlsError::UnauthorizedLeafNode);
+            }
+        }
+
         let new_secrets = match update_path {
             Some(update_path) => {
                 self.apply_update_path(sender, &update_path, &mut provisional_state)
@@ -753,6 +863,17 @@ pub(crate) trait MessageProcessor: Send + Sync {
     #[cfg(feature = "private_message")]
     fn min_epoch_available(&self) -> Option<u64>;
 
+    /// Number of past epochs that commits and proposals are still
+    /// authenticated against. Such messages are never applied.
+    #[cfg(feature = "prior_epoch")]
+    fn commit_lookback(&self) -> u64 {
+        0
+    }
+
+    fn max_group_size(&self) -> Option<u32> {
+        None
+    }
+
     fn check_metadata(&self, message: &MlsMessage) -> Result<(), MlsError> {
         let context = &self.group_state().context;
 
@@ -778,9 +899,20 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 return Err(MlsError::GroupIdMismatch);
             }
 
+            #[cfg(feature = "prior_epoch")]
+            let lookback = self.commit_lookback();
+
+            #[cfg(not(feature = "prior_epoch"))]
+            let lookback = 0;
+
+            // Commits and proposals from a past epoch within the lookback window
+            // are authenticated and then rejected in `process_auth_content`
+            let expected_epoch = epoch == context.epoch
+                || (epoch < context.epoch && context.epoch - epoch <= lookback);
+
             match content_type {
                 ContentType::Commit => {
-                    if context.epoch != epoch {
+                    if !expected_epoch {
                         Err(MlsError::InvalidEpoch)
                     } else {
                         Ok(())
@@ -788,7 +920,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
                 }
                 #[cfg(feature = "by_ref_proposal")]
                 ContentType::Proposal => {
-                    if context.epoch != epoch {
+                    if !expected_epoch {
                         Err(MlsError::InvalidEpoch)
                     } else {
                         Ok(())
@@ -814,7 +946,7 @@ pub(crate) trait MessageProcessor: Send + Sync {
             #[cfg(feature = "by_ref_proposal")]
             let check_epoch = check_epoch || content_type == ContentType::Proposal;
 
-            if check_epoch && epoch != context.epoch {
+            if check_epoch && !expected_epoch {
                 return Err(MlsError::InvalidEpoch);
             }
 
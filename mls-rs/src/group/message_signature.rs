@@ -49,6 +49,22 @@ impl MlsEncode for FramedContentAuthData {
 }
 
 impl FramedContentAuthData {
+    /// Encode this auth data in the form that [`Self::mls_decode`] expects for `content_type`.
+    ///
+    /// A confirmation tag is present on the wire if and only if the content is a commit, so
+    /// any other combination could not be decoded back to the same value.
+    pub(crate) fn mls_encode_for(
+        &self,
+        writer: &mut Vec<u8>,
+        content_type: ContentType,
+    ) -> Result<(), mls_rs_codec::Error> {
+        if self.confirmation_tag.is_some() != (content_type == ContentType::Commit) {
+            return Err(mls_rs_codec::Error::Custom(6));
+        }
+
+        self.mls_encode(writer)
+    }
+
     pub(crate) fn mls_decode(
         reader: &mut &[u8],
         content_type: ContentType,
@@ -234,6 +250,10 @@ impl<'a> Signable<'a> for AuthenticatedContent {
     fn write_signature(&mut self, signature: Vec<u8>) {
         self.auth.signature = MessageSignature::from(signature)
     }
+
+    fn protocol_version(&self, context: &MessageSigningContext) -> ProtocolVersion {
+        context.protocol_version
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
@@ -10,7 +10,7 @@ use crate::{
     crypto::SignaturePublicKey,
     group::{GroupContext, PublicMessage, Sender},
     signer::Signable,
-    tree_kem::{node::LeafIndex, TreeKemPublic},
+    tree_kem::node::{LeafIndex, NodeVec},
     CipherSuiteProvider,
 };
 
@@ -28,8 +28,8 @@ use super::proposal::Proposal;
 
 #[derive(Debug)]
 pub(crate) enum SignaturePublicKeysContainer<'a> {
-    RatchetTree(&'a TreeKemPublic),
-    #[cfg(feature = "private_message")]
+    RatchetTree(&'a NodeVec),
+    #[cfg(feature = "prior_epoch")]
     List(&'a [Option<SignaturePublicKey>]),
 }
 
@@ -75,7 +75,7 @@ pub(crate) async fn verify_plaintext_authentication<P: CipherSuiteProvider>(
     // from the credential stored at the leaf in the tree indicated by the sender field.
     verify_auth_content_signature(
         cipher_suite_provider,
-        SignaturePublicKeysContainer::RatchetTree(current_tree),
+        SignaturePublicKeysContainer::RatchetTree(&current_tree.nodes),
         context,
         &auth_content,
         #[cfg(feature = "by_ref_proposal")]
@@ -87,7 +87,7 @@ pub(crate) async fn verify_plaintext_authentication<P: CipherSuiteProvider>(
 }
 
 #[cfg(feature = "by_ref_proposal")]
-fn external_signers(context: &GroupContext) -> Vec<SigningIdentity> {
+pub(crate) fn external_signers(context: &GroupContext) -> Vec<SigningIdentity> {
     context
         .extensions
         .get_as::<ExternalSendersExt>()
@@ -151,11 +151,11 @@ fn signing_identity_for_member(
 ) -> Result<SignaturePublicKey, MlsError> {
     match signature_keys_container {
         SignaturePublicKeysContainer::RatchetTree(tree) => Ok(tree
-            .get_leaf_node(leaf_index)?
+            .borrow_as_leaf(leaf_index)?
             .signing_identity
             .signature_key
             .clone()), // TODO: We can probably get rid of this clone
-        #[cfg(feature = "private_message")]
+        #[cfg(feature = "prior_epoch")]
         SignaturePublicKeysContainer::List(list) => list
             .get(leaf_index.0 as usize)
             .cloned()
@@ -304,7 +304,8 @@ mod tests {
                 &commit_output.welcome_messages[0],
                 None,
                 bob_client.config,
-                bob_client.signer.unwrap(),
+                |_| Ok(bob_client.signer.unwrap()),
+                None,
             )
             .await
             .unwrap();
@@ -340,7 +341,7 @@ mod tests {
 
         verify_auth_content_signature(
             &env.bob.cipher_suite_provider,
-            super::SignaturePublicKeysContainer::RatchetTree(&env.bob.state.public_tree),
+            super::SignaturePublicKeysContainer::RatchetTree(&env.bob.state.public_tree.nodes),
             env.bob.context(),
             &message,
             #[cfg(feature = "by_ref_proposal")]
@@ -475,6 +476,28 @@ mod tests {
         .unwrap();
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn proposal_from_new_member_must_be_signed_by_key_package_owner() {
+        let test_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (key_pkg_gen, _) = test_member(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, b"bob").await;
+        let (_, mallory_signer) =
+            test_member(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, b"mallory").await;
+
+        let message =
+            test_new_member_proposal(key_pkg_gen, &mallory_signer, &test_group, |_| {}).await;
+
+        let res = verify_plaintext_authentication(
+            &test_group.cipher_suite_provider,
+            message,
+            Some(&test_group.key_schedule),
+            &test_group.state,
+        )
+        .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn proposal_from_new_member_must_not_have_membership_tag() {
@@ -610,6 +633,67 @@ mod tests {
         assert_matches!(res, Err(MlsError::UnknownSigningIdentityForExternalSender));
     }
 
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_proposal_is_bound_to_sender_index() {
+        let (ted_signing, ted_secret) = get_test_signing_identity(TEST_CIPHER_SUITE, b"ted").await;
+        let (zoe_signing, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"zoe").await;
+
+        let mut test_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut extensions = ExtensionList::default();
+
+        extensions
+            .set_from(ExternalSendersExt {
+                allowed_senders: vec![zoe_signing, ted_signing],
+            })
+            .unwrap();
+
+        test_group
+            .commit_builder()
+            .set_group_context_ext(extensions)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        test_group.apply_pending_commit().await.unwrap();
+
+        let res = verify_external_proposal(&test_group, &ted_secret, 1).await;
+        assert_matches!(res, Ok(()));
+
+        // Ted claims the index of zoe
+        let res = verify_external_proposal(&test_group, &ted_secret, 0).await;
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+
+        let res = verify_external_proposal(&test_group, &ted_secret, 2).await;
+        assert_matches!(res, Err(MlsError::UnknownSigningIdentityForExternalSender));
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn verify_external_proposal(
+        test_group: &TestGroup,
+        signer: &SignatureSecretKey,
+        sender_index: u32,
+    ) -> Result<(), MlsError> {
+        let (bob_key_pkg_gen, _) =
+            test_member(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, b"bob").await;
+
+        let message = test_new_member_proposal(bob_key_pkg_gen, signer, test_group, |msg| {
+            msg.content.sender = Sender::External(sender_index)
+        })
+        .await;
+
+        verify_plaintext_authentication(
+            &test_group.cipher_suite_provider,
+            message,
+            Some(&test_group.key_schedule),
+            &test_group.state,
+        )
+        .await
+        .map(|_| ())
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn proposal_from_external_sender_must_not_have_membership_tag() {
@@ -2,7 +2,10 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use crate::group::{proposal_filter::ProposalBundle, Roster};
+use crate::{
+    group::{proposal_filter::ProposalBundle, Roster},
+    tree_kem::leaf_node::LeafNode,
+};
 
 #[cfg(feature = "private_message")]
 use crate::{
@@ -38,6 +41,10 @@ pub struct CommitOptions {
     pub ratchet_tree_extension: bool,
     pub single_welcome_message: bool,
     pub allow_external_commit: bool,
+    /// If `false`, creating a commit with no proposals fails with
+    /// [`MlsError::EmptyCommitNotAllowed`](crate::client::MlsError::EmptyCommitNotAllowed)
+    /// unless `path_required` is set or the commit changes the committer's leaf node.
+    pub allow_empty_commits: bool,
 }
 
 impl Default for CommitOptions {
@@ -47,6 +54,7 @@ impl Default for CommitOptions {
             ratchet_tree_extension: true,
             single_welcome_message: true,
             allow_external_commit: false,
+            allow_empty_commits: true,
         }
     }
 }
@@ -83,6 +91,13 @@ impl CommitOptions {
             ..self
         }
     }
+
+    pub fn with_allow_empty_commits(self, allow_empty_commits: bool) -> Self {
+        Self {
+            allow_empty_commits,
+            ..self
+        }
+    }
 }
 
 /// Options controlling encryption of control and application messages
@@ -170,6 +185,49 @@ pub trait MlsRules: Send + Sync {
         current_roster: &Roster,
         current_extension_list: &ExtensionList,
     ) -> Result<EncryptionOptions, Self::Error>;
+
+    /// This is called when preparing or receiving a commit, before any proposal is applied,
+    /// to decide whether `committer` may commit at all. If `false` is returned, the commit
+    /// fails with [`MlsError::UnauthorizedCommitter`](crate::client::MlsError::UnauthorizedCommitter)
+    /// and the group state is not changed.
+    ///
+    /// Received commits are passed to this function after their signature is verified. The
+    /// `current_roster` and `current_extension_list` describe the group state before the commit.
+    ///
+    /// The default implementation allows any committer.
+    fn authorize_committer(
+        &self,
+        _direction: CommitDirection,
+        _committer: &CommitSource,
+        _current_roster: &Roster,
+        _current_extension_list: &ExtensionList,
+    ) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    /// This is called when preparing or receiving a commit for every leaf node it inserts
+    /// into the tree, after the credential in the leaf node was validated by the
+    /// [`IdentityProvider`](crate::IdentityProvider). This covers the leaf nodes of added
+    /// key packages, of update proposals and of the update path of a received commit,
+    /// including the leaf node of a new member joining by external commit.
+    ///
+    /// If `false` is returned for a received commit, the commit fails with
+    /// [`MlsError::UnauthorizedLeafNode`](crate::client::MlsError::UnauthorizedLeafNode).
+    /// When preparing a commit, proposals received by reference are filtered out instead.
+    /// The `current_roster` describes the group state before the commit and
+    /// `extension_list` the group context extensions the leaf node must be valid for,
+    /// i.e. including any change made by the commit.
+    ///
+    /// The default implementation allows any leaf node.
+    fn authorize_leaf_node(
+        &self,
+        _direction: CommitDirection,
+        _leaf_node: &LeafNode,
+        _current_roster: &Roster,
+        _extension_list: &ExtensionList,
+    ) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
 }
 
 macro_rules! delegate_mls_rules {
@@ -209,6 +267,26 @@ macro_rules! delegate_mls_rules {
             ) -> Result<EncryptionOptions, Self::Error> {
                 (**self).encryption_options(roster, extension_list)
             }
+
+            fn authorize_committer(
+                &self,
+                direction: CommitDirection,
+                committer: &CommitSource,
+                roster: &Roster,
+                extension_list: &ExtensionList,
+            ) -> Result<bool, Self::Error> {
+                (**self).authorize_committer(direction, committer, roster, extension_list)
+            }
+
+            fn authorize_leaf_node(
+                &self,
+                direction: CommitDirection,
+                leaf_node: &LeafNode,
+                roster: &Roster,
+                extension_list: &ExtensionList,
+            ) -> Result<bool, Self::Error> {
+                (**self).authorize_leaf_node(direction, leaf_node, roster, extension_list)
+            }
         }
     };
 }
@@ -231,6 +309,24 @@ impl DefaultMlsRules {
         Default::default()
     }
 
+    /// Create new MLS rules favoring forward secrecy and metadata protection over
+    /// performance.
+    ///
+    /// Every commit includes an update path, so that the committer's leaf and
+    /// direct path keys are refreshed in each epoch. This costs a path
+    /// encryption per commit, i.e. O(log n) HPKE operations for the committer and
+    /// one decryption per member. With the `private_message` feature, proposals and
+    /// commits sent by members are also encrypted and padded.
+    pub fn secure_defaults() -> Self {
+        Self {
+            commit_options: CommitOptions::new().with_path_required(true),
+            #[cfg(feature = "private_message")]
+            encryption_options: EncryptionOptions::new(true, PaddingMode::StepFunction),
+            #[cfg(not(feature = "private_message"))]
+            encryption_options: EncryptionOptions::default(),
+        }
+    }
+
     /// Set commit options.
     pub fn with_commit_options(self, commit_options: CommitOptions) -> Self {
         Self {
@@ -15,7 +15,7 @@ use mls_rs_core::time::MlsTime;
 use crate::cipher_suite::CipherSuite;
 use crate::client::MlsError;
 use crate::client_config::ClientConfig;
-use crate::crypto::{HpkeCiphertext, SignatureSecretKey};
+use crate::crypto::{HpkeCiphertext, HpkePublicKey, HpkeSecretKey, SignatureSecretKey};
 #[cfg(feature = "last_resort_key_package_ext")]
 use crate::extension::LastResortKeyPackageExt;
 use crate::extension::RatchetTreeExt;
@@ -27,20 +27,15 @@ use crate::psk::PreSharedKeyID;
 use crate::signer::Signable;
 use crate::tree_kem::hpke_encryption::HpkeEncryptable;
 use crate::tree_kem::kem::TreeKem;
-use crate::tree_kem::node::LeafIndex;
+use crate::tree_kem::leaf_node_validator::{LeafNodeValidator, ValidationContext};
+use crate::tree_kem::node::{LeafIndex, NodeTypeResolver};
 use crate::tree_kem::path_secret::PathSecret;
 pub use crate::tree_kem::Capabilities;
-use crate::tree_kem::{
-    leaf_node::LeafNode,
-    leaf_node_validator::{LeafNodeValidator, ValidationContext},
-};
 use crate::tree_kem::{math as tree_math, ValidatedUpdatePath};
 use crate::tree_kem::{TreeKemPrivate, TreeKemPublic};
-use crate::{CipherSuiteProvider, CryptoProvider};
+use crate::{CipherSuiteProvider, CryptoProvider, IdentityProvider};
 pub use state::GroupState;
-
-#[cfg(feature = "by_ref_proposal")]
-use crate::crypto::{HpkePublicKey, HpkeSecretKey};
+use zeroize::Zeroizing;
 
 use crate::extension::ExternalPubExt;
 
@@ -87,7 +82,7 @@ use self::epoch::PriorEpoch;
 
 use self::epoch::EpochSecrets;
 pub use self::message_processor::{
-    ApplicationMessageDescription, CommitEffect, CommitMessageDescription, NewEpoch,
+    ApplicationMessageDescription, CommitEffect, CommitMessageDescription, CommitPreview, NewEpoch,
     ProposalMessageDescription, ProposalSender, ReceivedMessage,
 };
 use self::message_processor::{EventOrContent, MessageProcessor, ProvisionalState};
@@ -97,9 +92,18 @@ use self::state_repo::GroupStateRepository;
 pub use group_info::GroupInfo;
 
 pub use self::framing::{ContentType, Sender};
+pub use builder::GroupBuilder;
+#[cfg(feature = "private_message")]
+pub use chunked_message::{ApplicationMessageChunks, ApplicationMessageReassembler};
 pub use commit::*;
 pub use context::GroupContext;
 pub use roster::*;
+#[cfg(feature = "test_exports")]
+pub use state_digest::{StateDigest, StateDigestField};
+
+pub use crate::tree_kem::leaf_node::{LeafNode, LeafNodeSource};
+
+pub use observer::{EpochTransition, GroupObserver};
 
 pub(crate) use transcript_hash::ConfirmedTranscriptHash;
 pub(crate) use util::*;
@@ -107,6 +111,9 @@ pub(crate) use util::*;
 #[cfg(all(feature = "by_ref_proposal", feature = "external_client"))]
 pub use self::message_processor::CachedProposal;
 
+mod builder;
+#[cfg(feature = "private_message")]
+mod chunked_message;
 #[cfg(feature = "private_message")]
 mod ciphertext_processor;
 
@@ -123,6 +130,7 @@ pub(crate) mod message_processor;
 pub(crate) mod message_signature;
 pub(crate) mod message_verifier;
 pub mod mls_rules;
+mod observer;
 #[cfg(feature = "private_message")]
 pub(crate) mod padding;
 /// Proposals to evolve a MLS [`Group`]
@@ -136,6 +144,8 @@ mod resumption;
 mod roster;
 pub(crate) mod snapshot;
 pub(crate) mod state;
+#[cfg(feature = "test_exports")]
+mod state_digest;
 
 #[cfg(feature = "prior_epoch")]
 pub(crate) mod state_repo;
@@ -170,6 +180,21 @@ struct GroupSecrets {
     psks: Vec<PreSharedKeyID>,
 }
 
+/// Secrets used to create the welcome messages of a commit sent by this member that adds
+/// members. They are kept for the epoch created by that commit so that the welcome message
+/// of each added member can be produced again, and dropped once that was done for all of them.
+#[derive(Clone)]
+struct WelcomeSecrets {
+    confirmed_transcript_hash: ConfirmedTranscriptHash,
+    added_leaves: Vec<LeafIndex>,
+    joiner_secret: JoinerSecret,
+    path_secrets: Option<Vec<Option<PathSecret>>>,
+    #[cfg(feature = "psk")]
+    psks: Vec<PreSharedKeyID>,
+    encrypted_group_info: Vec<u8>,
+    welcome_aad: Option<Vec<u8>>,
+}
+
 impl HpkeEncryptable for GroupSecrets {
     const ENCRYPT_LABEL: &'static str = "Welcome";
 
@@ -182,6 +207,20 @@ impl HpkeEncryptable for GroupSecrets {
     }
 }
 
+struct SubtreePlaintext(Zeroizing<Vec<u8>>);
+
+impl HpkeEncryptable for SubtreePlaintext {
+    const ENCRYPT_LABEL: &'static str = "SubtreeData";
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, MlsError> {
+        Ok(Self(Zeroizing::new(bytes)))
+    }
+
+    fn get_bytes(&self) -> Result<Vec<u8>, MlsError> {
+        Ok(self.0.to_vec())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct EncryptedGroupSecrets {
@@ -276,8 +315,12 @@ where
     pending_updates:
         crate::map::SmallMap<HpkePublicKey, (HpkeSecretKey, Option<SignatureSecretKey>)>,
     pending_commit: Option<CommitGeneration>,
+    pending_welcome_secrets: Option<WelcomeSecrets>,
+    welcome_secrets: Option<WelcomeSecrets>,
     #[cfg(feature = "psk")]
     previous_psk: Option<PskSecretInput>,
+    #[cfg(feature = "test_exports")]
+    join_secrets: Option<JoinSecrets>,
     #[cfg(test)]
     pub(crate) commit_modifiers: CommitModifiers,
     pub(crate) signer: SignatureSecretKey,
@@ -376,7 +419,7 @@ where
         )
         .await?;
 
-        Ok(Self {
+        let mut group = Self {
             config,
             state: GroupState::new(context, public_tree, interim_hash, confirmation_tag),
             private_tree,
@@ -384,6 +427,8 @@ where
             #[cfg(feature = "by_ref_proposal")]
             pending_updates: Default::default(),
             pending_commit: None,
+            pending_welcome_secrets: None,
+            welcome_secrets: None,
             #[cfg(test)]
             commit_modifiers: Default::default(),
             epoch_secrets: key_schedule_result.epoch_secrets,
@@ -391,17 +436,29 @@ where
             cipher_suite_provider,
             #[cfg(feature = "psk")]
             previous_psk: None,
+            #[cfg(feature = "test_exports")]
+            join_secrets: None,
             signer,
-        })
+        };
+
+        if group.config.persist_on_epoch_change() {
+            group.write_to_storage().await?;
+        }
+
+        Ok(group)
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    pub(crate) async fn join(
+    pub(crate) async fn join<F>(
         welcome: &MlsMessage,
         tree_data: Option<ExportedTree<'_>>,
         config: C,
-        signer: SignatureSecretKey,
-    ) -> Result<(Self, NewMemberInfo), MlsError> {
+        signer: F,
+        welcome_aad: Option<&[u8]>,
+    ) -> Result<(Self, NewMemberInfo), MlsError>
+    where
+        F: FnOnce(&SigningIdentity) -> Result<SignatureSecretKey, MlsError>,
+    {
         Self::from_welcome_message(
             welcome,
             tree_data,
@@ -409,24 +466,30 @@ where
             signer,
             #[cfg(feature = "psk")]
             None,
+            welcome_aad,
         )
         .await
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn from_welcome_message(
+    async fn from_welcome_message<F>(
         welcome: &MlsMessage,
         tree_data: Option<ExportedTree<'_>>,
         config: C,
-        signer: SignatureSecretKey,
+        signer: F,
         #[cfg(feature = "psk")] additional_psk: Option<PskSecretInput>,
-    ) -> Result<(Self, NewMemberInfo), MlsError> {
+        welcome_aad: Option<&[u8]>,
+    ) -> Result<(Self, NewMemberInfo), MlsError>
+    where
+        F: FnOnce(&SigningIdentity) -> Result<SignatureSecretKey, MlsError>,
+    {
         let (group_info, key_package_generation, group_secrets, psk_secret) =
             Self::decrypt_group_info_internal(
                 welcome,
                 &config,
                 #[cfg(feature = "psk")]
                 additional_psk,
+                welcome_aad,
             )
             .await?;
 
@@ -448,6 +511,10 @@ where
 
         let key_package = key_package_generation.key_package;
 
+        // The key package may have been generated with an identity that has since been
+        // replaced, so pick the signer matching the identity it was generated with.
+        let signer = signer(&key_package.leaf_node.signing_identity)?;
+
         // Identify a leaf in the tree array (any even-numbered node) whose leaf_node is identical
         // to the leaf_node field of the KeyPackage. If no such field exists, return an error. Let
         // index represent the index of this node among the leaves in the tree, namely the index of
@@ -506,7 +573,16 @@ where
             return Err(MlsError::InvalidConfirmationTag);
         }
 
-        Self::join_with(
+        #[cfg(feature = "test_exports")]
+        let join_secrets = JoinSecrets::new(
+            &cipher_suite_provider,
+            &group_secrets.joiner_secret,
+            &psk_secret,
+        )
+        .await?;
+
+        #[cfg_attr(not(feature = "test_exports"), allow(unused_mut))]
+        let (mut group, new_member_info) = Self::join_with(
             config,
             group_info,
             public_tree,
@@ -516,7 +592,18 @@ where
             used_key_package_ref,
             signer,
         )
-        .await
+        .await?;
+
+        #[cfg(feature = "test_exports")]
+        {
+            group.join_secrets = Some(join_secrets);
+        }
+
+        if group.config.persist_on_epoch_change() {
+            group.write_to_storage().await?;
+        }
+
+        Ok((group, new_member_info))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -568,6 +655,8 @@ where
             #[cfg(feature = "by_ref_proposal")]
             pending_updates: Default::default(),
             pending_commit: None,
+            pending_welcome_secrets: None,
+            welcome_secrets: None,
             #[cfg(test)]
             commit_modifiers: Default::default(),
             epoch_secrets,
@@ -575,6 +664,8 @@ where
             cipher_suite_provider: cs,
             #[cfg(feature = "psk")]
             previous_psk: None,
+            #[cfg(feature = "test_exports")]
+            join_secrets: None,
             signer,
         };
 
@@ -605,7 +696,7 @@ where
         self.private_tree.self_index.0
     }
 
-    fn current_user_leaf_node(&self) -> Result<&LeafNode, MlsError> {
+    pub(crate) fn current_user_leaf_node(&self) -> Result<&LeafNode, MlsError> {
         self.current_epoch_tree()
             .get_leaf_node(self.private_tree.self_index)
     }
@@ -624,6 +715,139 @@ where
         self.group_state().member_at_index(index)
     }
 
+    /// Leaf node at a specific index in the group state.
+    ///
+    /// The leaf node is returned as it appears in the ratchet tree. Its capabilities and
+    /// extensions are raw and may contain GREASE values, unlike those of the corresponding
+    /// [`Member`].
+    ///
+    /// Returns `None` if the leaf is blank or the index is out of range.
+    pub fn leaf_node(&self, index: u32) -> Option<&LeafNode> {
+        self.group_state()
+            .public_tree
+            .get_leaf_node(LeafIndex(index))
+            .ok()
+    }
+
+    /// HPKE public key of the leaf at a specific index in the group state.
+    ///
+    /// This is the key that path secrets are encrypted to, which is distinct
+    /// from the signature key of the member. Returns `None` if the leaf is
+    /// blank or the index is out of range.
+    pub fn leaf_encryption_key(&self, index: u32) -> Option<HpkePublicKey> {
+        self.leaf_node(index).map(|leaf| leaf.public_key.clone())
+    }
+
+    /// Encrypt `plaintext` to the members within the subtree rooted at
+    /// tree node `root`.
+    ///
+    /// As for path secrets in an update path, the plaintext is sealed with
+    /// HPKE once to the public key of every node in the resolution of
+    /// `root`. This covers members added to the subtree since its root was
+    /// last updated, as well as subtrees whose root is blank. Only members
+    /// whose leaf is a descendant of `root` can recover the plaintext with
+    /// [`Group::open_from_subtree`]. The ciphertext is bound to the current
+    /// group context and can only be opened in the current epoch.
+    ///
+    /// Returns [`MlsError::ExpectedNode`] if the subtree has no members.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn seal_to_subtree(&self, root: u32, plaintext: &[u8]) -> Result<Vec<u8>, MlsError> {
+        let nodes = &self.state.public_tree.nodes;
+
+        // Reject indexes outside of the tree
+        nodes.borrow_node(root)?;
+
+        let resolution = nodes.get_resolution_index(root)?;
+
+        if resolution.is_empty() {
+            return Err(MlsError::ExpectedNode);
+        }
+
+        let context = self.context().mls_encode_to_vec()?;
+        let plaintext = SubtreePlaintext(Zeroizing::new(plaintext.to_vec()));
+        let mut ciphertexts = Vec::with_capacity(resolution.len());
+
+        for index in resolution {
+            let public_key = nodes.borrow_node(index)?.as_non_empty()?.public_key();
+
+            let ciphertext = plaintext
+                .encrypt(&self.cipher_suite_provider, public_key, &context)
+                .await?;
+
+            ciphertexts.push(ciphertext);
+        }
+
+        ciphertexts.mls_encode_to_vec().map_err(Into::into)
+    }
+
+    /// Decrypt a ciphertext created by [`Group::seal_to_subtree`] for the
+    /// subtree rooted at tree node `root`.
+    ///
+    /// Returns [`MlsError::NotInSubtree`] if the leaf of the current member
+    /// is not a descendant of `root`.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn open_from_subtree(
+        &self,
+        root: u32,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, MlsError> {
+        let nodes = &self.state.public_tree.nodes;
+
+        // Reject indexes outside of the tree
+        nodes.borrow_node(root)?;
+
+        let resolution = nodes.get_resolution_index(root)?;
+        let ciphertexts = Vec::<HpkeCiphertext>::mls_decode(&mut &*ciphertext)?;
+
+        if ciphertexts.len() != resolution.len() {
+            return Err(MlsError::SubtreeCiphertextCountMismatch(root));
+        }
+
+        // A member of the subtree holds the private key of exactly one node
+        // of its resolution, either its own leaf or one of its ancestors
+        let (position, index, secret_key) = resolution
+            .iter()
+            .enumerate()
+            .find_map(|(i, &index)| Some((i, index, self.node_secret_key(index)?)))
+            .ok_or(MlsError::NotInSubtree(root))?;
+
+        let public_key = nodes.borrow_node(index)?.as_non_empty()?.public_key();
+        let context = self.context().mls_encode_to_vec()?;
+
+        let plaintext = SubtreePlaintext::decrypt(
+            &self.cipher_suite_provider,
+            secret_key,
+            public_key,
+            &context,
+            &ciphertexts[position],
+        )
+        .await?;
+
+        Ok(plaintext.0.to_vec())
+    }
+
+    /// Private key of the current member for tree node `index`, if it is
+    /// the member's leaf or a node of its direct path whose key it knows.
+    fn node_secret_key(&self, index: u32) -> Option<&HpkeSecretKey> {
+        let self_index = self.private_tree.self_index;
+
+        let key_position = if index == u32::from(self_index) {
+            Some(0)
+        } else {
+            self.state
+                .public_tree
+                .nodes
+                .direct_copath(self_index)
+                .iter()
+                .position(|n| n.path == index)
+                .map(|i| i + 1)
+        };
+
+        key_position
+            .and_then(|i| self.private_tree.secret_keys.get(i))
+            .and_then(Option::as_ref)
+    }
+
     #[cfg(feature = "by_ref_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn proposal_message(
@@ -725,6 +949,7 @@ where
         Ok((provisional_private_tree, new_signer))
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn encrypt_group_secrets(
         &self,
@@ -734,6 +959,7 @@ where
         path_secrets: Option<&Vec<Option<PathSecret>>>,
         #[cfg(feature = "psk")] psks: Vec<PreSharedKeyID>,
         encrypted_group_info: &[u8],
+        welcome_aad: Option<&[u8]>,
     ) -> Result<EncryptedGroupSecrets, MlsError> {
         let path_secret = path_secrets
             .map(|secrets| {
@@ -759,10 +985,11 @@ where
         };
 
         let encrypted_group_secrets = group_secrets
-            .encrypt(
+            .encrypt_with_aad(
                 &self.cipher_suite_provider,
                 &key_package.hpke_init_key,
                 encrypted_group_info,
+                welcome_aad,
             )
             .await?;
 
@@ -774,6 +1001,69 @@ where
         })
     }
 
+    /// Create a welcome message for the current epoch targeting `key_package`.
+    ///
+    /// This can be used to welcome a member that was added by the most recent commit
+    /// but did not receive a welcome message, e.g. because the message was lost. The
+    /// current epoch must have been created by a commit sent and applied by this group
+    /// instance, and `key_package` must have been added by that commit.
+    ///
+    /// A welcome message can be created once for each added member. The secrets needed
+    /// for this are dropped afterwards, or when the group moves to the next epoch, and
+    /// are never persisted by [`Group::write_to_storage`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn encrypt_group_secrets_for(
+        &mut self,
+        key_package: MlsMessage,
+    ) -> Result<MlsMessage, MlsError> {
+        let key_package = key_package
+            .into_key_package()
+            .ok_or(MlsError::UnexpectedMessageType)?;
+
+        let leaf_index = self
+            .state
+            .public_tree
+            .find_leaf_node(&key_package.leaf_node)
+            .ok_or(MlsError::MemberNotFound)?;
+
+        let secrets = self
+            .welcome_secrets
+            .as_ref()
+            .ok_or(MlsError::WelcomeSecretsNotFound)?;
+
+        if !secrets.added_leaves.contains(&leaf_index) {
+            return Err(MlsError::MemberNotAddedByCommit(*leaf_index));
+        }
+
+        let encrypted_group_secrets = self
+            .encrypt_group_secrets(
+                &key_package,
+                leaf_index,
+                &secrets.joiner_secret,
+                secrets.path_secrets.as_ref(),
+                #[cfg(feature = "psk")]
+                secrets.psks.clone(),
+                &secrets.encrypted_group_info,
+                secrets.welcome_aad.as_deref(),
+            )
+            .await?;
+
+        let welcome = self.make_welcome_message(
+            vec![encrypted_group_secrets],
+            secrets.encrypted_group_info.clone(),
+        );
+
+        if let Some(secrets) = self.welcome_secrets.as_mut() {
+            secrets.added_leaves.retain(|l| *l != leaf_index);
+
+            if secrets.added_leaves.is_empty() {
+                self.welcome_secrets = None;
+            }
+        }
+
+        Ok(welcome)
+    }
+
     /// Create a proposal message that adds a new member to the group.
     ///
     /// `authenticated_data` will be sent unencrypted along with the contents
@@ -828,7 +1118,9 @@ where
     /// by and matches the output of the
     /// [identity](crate::IdentityProvider)
     /// function of the current
-    /// [`IdentityProvider`](crate::IdentityProvider).
+    /// [`IdentityProvider`](crate::IdentityProvider). The signer of an identity
+    /// held in a client's keychain can be found with
+    /// [`Keychain::signer_for_identity`](crate::keychain::Keychain::signer_for_identity).
     ///
     /// `authenticated_data` will be sent unencrypted along with the contents
     /// of the proposal message.
@@ -912,6 +1204,40 @@ where
         }))
     }
 
+    /// Remove proposals for all members whose credential has expired at `now`.
+    ///
+    /// A member is considered expired if the
+    /// [`IdentityProvider::expiration`](crate::IdentityProvider::expiration)
+    /// of its signing identity, as reported by the identity provider in use by
+    /// this group, is before `now`. Identities without an expiration, such as
+    /// basic credentials, never expire and the current member is never included.
+    ///
+    /// The returned proposals can be committed with
+    /// [`CommitBuilder::raw_proposals`](commit::CommitBuilder::raw_proposals).
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn propose_remove_expired(&self, now: MlsTime) -> Result<Vec<Proposal>, MlsError> {
+        let identity_provider = self.identity_provider();
+        let mut proposals = Vec::new();
+
+        for (index, leaf) in self.state.public_tree.non_empty_leaves() {
+            if index == self.private_tree.self_index {
+                continue;
+            }
+
+            let expiration = identity_provider
+                .expiration(&leaf.signing_identity)
+                .await
+                .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))?;
+
+            if matches!(expiration, Some(expiration) if expiration < now) {
+                proposals.push(Proposal::Remove(RemoveProposal { to_remove: index }));
+            }
+        }
+
+        Ok(proposals)
+    }
+
     /// Create a proposal message that adds an external pre shared key to the group.
     ///
     /// Each group member will need to have the PSK associated with
@@ -1054,6 +1380,10 @@ where
     }
 
     /// Delete all sent and received proposals cached for commit.
+    ///
+    /// Proposals that are cleared are not included in the next commit created
+    /// by this member. Processing a commit from another member that references
+    /// a cleared proposal fails with [`MlsError::ProposalNotFound`].
     #[cfg(feature = "by_ref_proposal")]
     pub fn clear_proposal_cache(&mut self) {
         self.state.proposals.clear()
@@ -1066,7 +1396,7 @@ where
     ) -> Result<MlsMessage, MlsError> {
         #[cfg(feature = "private_message")]
         let payload = if content.wire_format == WireFormat::PrivateMessage {
-            MlsMessagePayload::Cipher(self.create_ciphertext(content).await?)
+            MlsMessagePayload::Cipher(self.create_ciphertext(content).await?.0)
         } else {
             MlsMessagePayload::Plain(self.create_plaintext(content).await?)
         };
@@ -1104,7 +1434,7 @@ where
     async fn create_ciphertext(
         &mut self,
         auth_content: AuthenticatedContent,
-    ) -> Result<PrivateMessage, MlsError> {
+    ) -> Result<(PrivateMessage, u32), MlsError> {
         let padding_mode = self.encryption_options()?.padding_mode;
 
         let mut encryptor = CiphertextProcessor::new(self, self.cipher_suite_provider.clone());
@@ -1123,6 +1453,47 @@ where
         message: &[u8],
         authenticated_data: Vec<u8>,
     ) -> Result<MlsMessage, MlsError> {
+        self.encrypt_application_message_with_generation(message, authenticated_data)
+            .await
+            .map(|(message, _)| message)
+    }
+
+    /// Split `data` into application messages carrying at most `chunk_size` bytes
+    /// of it each.
+    ///
+    /// The messages are encrypted one by one with
+    /// [`ApplicationMessageChunks::next_message`]. Each message is a regular
+    /// application message that receivers process as usual before adding it to an
+    /// [`ApplicationMessageReassembler`]. The chunk header, i.e. a random payload
+    /// identifier and the position of the chunk, is encrypted along with the chunk.
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn application_message_chunks<'a>(
+        &'a mut self,
+        data: &'a [u8],
+        chunk_size: usize,
+        authenticated_data: Vec<u8>,
+    ) -> Result<ApplicationMessageChunks<'a, C>, MlsError> {
+        ApplicationMessageChunks::new(self, data, chunk_size, authenticated_data).await
+    }
+
+    /// Encrypt an application message like
+    /// [`Group::encrypt_application_message`], also returning the generation
+    /// of the message key that was used.
+    ///
+    /// Together with the epoch of the returned message, the generation identifies
+    /// the message within the group, as it is the same generation that receivers
+    /// read from the encrypted sender data. Generations start at 0 in each epoch and
+    /// increase by one with each application message sent by this member.
+    #[cfg(feature = "private_message")]
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn encrypt_application_message_with_generation(
+        &mut self,
+        message: &[u8],
+        authenticated_data: Vec<u8>,
+    ) -> Result<(MlsMessage, u32), MlsError> {
         // A group member that has observed one or more proposals within an epoch MUST send a Commit message
         // before sending application data
         #[cfg(feature = "by_ref_proposal")]
@@ -1141,7 +1512,14 @@ where
         )
         .await?;
 
-        self.format_for_wire(auth_content).await
+        let (ciphertext, generation) = self.create_ciphertext(auth_content).await?;
+
+        let message = MlsMessage::new(
+            self.protocol_version(),
+            MlsMessagePayload::Cipher(ciphertext),
+        );
+
+        Ok((message, generation))
     }
 
     #[cfg(feature = "private_message")]
@@ -1159,7 +1537,7 @@ where
 
             verify_auth_content_signature(
                 &self.cipher_suite_provider,
-                SignaturePublicKeysContainer::RatchetTree(&self.state.public_tree),
+                SignaturePublicKeysContainer::RatchetTree(&self.state.public_tree.nodes),
                 self.context(),
                 &content,
                 #[cfg(feature = "by_ref_proposal")]
@@ -1205,6 +1583,19 @@ where
     /// [`CommitBuilder::build`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn apply_pending_commit(&mut self) -> Result<CommitMessageDescription, MlsError> {
+        let mut next_epoch = self.next_epoch_state(true);
+        let group = next_epoch.as_mut().unwrap_or(&mut *self);
+
+        let description = group.apply_pending_commit_unobserved().await?;
+        self.on_epoch_change(next_epoch, &description).await?;
+
+        Ok(description)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn apply_pending_commit_unobserved(
+        &mut self,
+    ) -> Result<CommitMessageDescription, MlsError> {
         let content = self
             .pending_commit
             .as_ref()
@@ -1215,6 +1606,47 @@ where
         self.process_commit(content, None).await
     }
 
+    /// Copy of this group that a commit is applied to if the resulting epoch
+    /// must be persisted before it replaces the current state.
+    fn next_epoch_state(&self, is_commit: bool) -> Option<Self> {
+        (is_commit && self.config.persist_on_epoch_change()).then(|| self.clone())
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn on_epoch_change(
+        &mut self,
+        next_epoch: Option<Self>,
+        description: &CommitMessageDescription,
+    ) -> Result<(), MlsError> {
+        let removed = matches!(description.effect, CommitEffect::Removed { .. });
+
+        // If writing fails, the current state is kept and storage still holds the prior epoch.
+        if let Some(mut next_epoch) = next_epoch {
+            if !removed {
+                next_epoch.write_to_storage().await?;
+            }
+
+            *self = next_epoch;
+        }
+
+        if !removed {
+            self.notify_epoch_transition();
+        }
+
+        Ok(())
+    }
+
+    fn notify_epoch_transition(&self) {
+        if let Some(observer) = self.config.group_observer() {
+            observer.epoch_transition(&EpochTransition {
+                group_id: &self.state.context.group_id,
+                epoch: self.state.context.epoch,
+                tree_hash: &self.state.context.tree_hash,
+                member_count: self.state.public_tree.occupied_leaf_count(),
+            });
+        }
+    }
+
     /// Apply a detached commit that was created by [`Group::commit_detached`] or
     /// [`CommitBuilder::build_detached`].
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -1238,7 +1670,8 @@ where
     /// commit message is processed using [`Group::process_incoming_message`]
     /// before [`Group::apply_pending_commit`] is called.
     pub fn clear_pending_commit(&mut self) {
-        self.pending_commit = None
+        self.pending_commit = None;
+        self.pending_welcome_secrets = None;
     }
 
     /// Returns true if the client has received or issued a proposal
@@ -1249,6 +1682,22 @@ where
         !self.state.proposals.is_empty()
     }
 
+    /// Proposals sent or received in the current epoch that have not yet been
+    /// committed.
+    ///
+    /// These are the proposals that a call to [`Group::commit`] would include
+    /// by reference, in the same order. The list is emptied whenever the group
+    /// moves to a new epoch.
+    #[cfg(feature = "by_ref_proposal")]
+    pub fn pending_proposals(&self) -> Vec<(ProposalRef, Proposal, Sender)> {
+        self.state
+            .proposals
+            .sorted_proposals()
+            .into_iter()
+            .map(|(r, p)| (r.clone(), p.proposal.clone(), p.sender))
+            .collect()
+    }
+
     /// Process an inbound message for this group.
     ///
     /// # Warning
@@ -1256,18 +1705,37 @@ where
     /// Changes to the group's state as a result of processing `message` will
     /// not be persisted by the
     /// [`GroupStateStorage`](crate::GroupStateStorage)
-    /// in use by this group until [`Group::write_to_storage`] is called.
+    /// in use by this group until [`Group::write_to_storage`] is called,
+    /// unless a new epoch is reached and the client was built with
+    /// [`persist_on_epoch_change`](crate::client_builder::ClientBuilder::persist_on_epoch_change).
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     #[inline(never)]
     pub async fn process_incoming_message(
         &mut self,
         message: MlsMessage,
+    ) -> Result<ReceivedMessage, MlsError> {
+        let mut next_epoch = self.next_epoch_state(message.is_commit());
+        let group = next_epoch.as_mut().unwrap_or(&mut *self);
+
+        let received = group.process_incoming_message_unobserved(message).await?;
+
+        if let ReceivedMessage::Commit(description) = &received {
+            self.on_epoch_change(next_epoch, description).await?;
+        }
+
+        Ok(received)
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn process_incoming_message_unobserved(
+        &mut self,
+        message: MlsMessage,
     ) -> Result<ReceivedMessage, MlsError> {
         if let Some(pending) = &self.pending_commit {
             let message_hash = MessageHash::compute(&self.cipher_suite_provider, &message).await?;
 
             if message_hash == pending.commit_message_hash {
-                let message_description = self.apply_pending_commit().await?;
+                let message_description = self.apply_pending_commit_unobserved().await?;
 
                 return Ok(ReceivedMessage::Commit(message_description));
             }
@@ -1310,21 +1778,82 @@ where
     /// Changes to the group's state as a result of processing `message` will
     /// not be persisted by the
     /// [`GroupStateStorage`](crate::GroupStateStorage)
-    /// in use by this group until [`Group::write_to_storage`] is called.
+    /// in use by this group until [`Group::write_to_storage`] is called,
+    /// unless a new epoch is reached and the client was built with
+    /// [`persist_on_epoch_change`](crate::client_builder::ClientBuilder::persist_on_epoch_change).
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn process_incoming_message_with_time(
         &mut self,
         message: MlsMessage,
         time: MlsTime,
     ) -> Result<ReceivedMessage, MlsError> {
-        MessageProcessor::process_incoming_message_with_time(
-            self,
+        let mut next_epoch = self.next_epoch_state(message.is_commit());
+        let group = next_epoch.as_mut().unwrap_or(&mut *self);
+
+        let received = MessageProcessor::process_incoming_message_with_time(
+            group,
             message,
             #[cfg(feature = "by_ref_proposal")]
             true,
             Some(time),
         )
-        .await
+        .await?;
+
+        if let ReceivedMessage::Commit(description) = &received {
+            self.on_epoch_change(next_epoch, description).await?;
+        }
+
+        Ok(received)
+    }
+
+    /// Validate a commit message and compute its effects without changing
+    /// the state of this group.
+    ///
+    /// No secrets are consumed and no ratchets are advanced by this function.
+    /// The returned [`CommitPreview`] can be inspected, e.g. to veto the removal
+    /// of certain members, and then either discarded or applied with
+    /// [`Group::apply_previewed_commit`].
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn preview_commit(&self, message: &MlsMessage) -> Result<CommitPreview, MlsError> {
+        let mut provisional_group = self.clone();
+
+        let received = provisional_group
+            .process_incoming_message_unobserved(message.clone())
+            .await?;
+
+        let ReceivedMessage::Commit(description) = received else {
+            return Err(MlsError::UnexpectedMessageType);
+        };
+
+        Ok(CommitPreview {
+            description,
+            message: message.clone(),
+        })
+    }
+
+    /// Apply a commit that was previously inspected using [`Group::preview_commit`].
+    ///
+    /// The commit is processed again against the current state of this group,
+    /// so a preview that has become stale, e.g. because another commit was
+    /// applied in the meantime, results in an error.
+    ///
+    /// # Warning
+    ///
+    /// Changes to the group's state as a result of applying `preview` will
+    /// not be persisted by the
+    /// [`GroupStateStorage`](crate::GroupStateStorage)
+    /// in use by this group until [`Group::write_to_storage`] is called,
+    /// unless a new epoch is reached and the client was built with
+    /// [`persist_on_epoch_change`](crate::client_builder::ClientBuilder::persist_on_epoch_change).
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn apply_previewed_commit(
+        &mut self,
+        preview: CommitPreview,
+    ) -> Result<CommitMessageDescription, MlsError> {
+        match self.process_incoming_message(preview.message).await? {
+            ReceivedMessage::Commit(description) => Ok(description),
+            _ => Err(MlsError::UnexpectedMessageType),
+        }
     }
 
     /// Find a group member by
@@ -1360,8 +1889,13 @@ where
     /// The returned `GroupInfo` is suitable for one external commit for the current epoch.
     /// If `with_tree_in_extension` is set to true, the returned `GroupInfo` contains the
     /// ratchet tree and therefore contains all information needed to join the group. Otherwise,
-    /// the ratchet tree must be obtained separately, e.g. via
-    /// (ExternalClient::export_tree)[crate::external_client::ExternalGroup::export_tree].
+    /// the ratchet tree must be obtained separately, e.g. via [`Group::export_tree`], and
+    /// passed to
+    /// [`ExternalCommitBuilder::with_tree_data`](external_commit::ExternalCommitBuilder::with_tree_data).
+    ///
+    /// As required by RFC 9420, the `GroupInfo` always carries the confirmation tag and the
+    /// signature of this member. The joiner verifies the signature, checks the tree against
+    /// the group context and uses the `external_pub` extension added here to create the commit.
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn group_info_message_allowing_ext_commit(
         &self,
@@ -1429,6 +1963,10 @@ where
     /// Get the
     /// [epoch_authenticator](https://messaginglayersecurity.rocks/mls-protocol/draft-ietf-mls-protocol.html#name-key-schedule)
     /// of the current epoch.
+    ///
+    /// The epoch authenticator is derived from the epoch secret and can only
+    /// be computed by members. External observers can compare the
+    /// [group context](Group::context) instead.
     pub fn epoch_authenticator(&self) -> Result<Secret, MlsError> {
         Ok(self.key_schedule.authentication_secret.clone().into())
     }
@@ -1469,6 +2007,45 @@ where
         self.group_state().public_tree.roster()
     }
 
+    /// Capabilities supported by every current member of the group.
+    ///
+    /// Each list is the intersection of the lists advertised by all members,
+    /// without GREASE values. Default extension and proposal types are supported
+    /// by all members without being advertised, and are therefore not listed.
+    pub fn common_capabilities(&self) -> Capabilities {
+        let mut leaves = self
+            .group_state()
+            .public_tree
+            .non_empty_leaves()
+            .map(|(_, leaf)| leaf.ungreased_capabilities());
+
+        let mut common = leaves.next().unwrap_or_default();
+
+        for capabilities in leaves {
+            common
+                .protocol_versions
+                .retain(|v| capabilities.protocol_versions.contains(v));
+
+            common
+                .cipher_suites
+                .retain(|cs| capabilities.cipher_suites.contains(cs));
+
+            common
+                .extensions
+                .retain(|ext| capabilities.extensions.contains(ext));
+
+            common
+                .proposals
+                .retain(|p| capabilities.proposals.contains(p));
+
+            common
+                .credentials
+                .retain(|c| capabilities.credentials.contains(c));
+        }
+
+        common
+    }
+
     /// Determines equality of two different groups internal states.
     /// Useful for testing.
     ///
@@ -1499,6 +2076,7 @@ where
                 current_epoch: Some(&self.epoch_secrets),
                 prior_epochs: Some(&self.state_repo),
                 psk_store: &self.config.secret_store(),
+                resumption_psk_resolver: &self.config.resumption_psk_resolver(),
             }
             .resolve_to_secret(&psks, self.cipher_suite_provider())
             .await?;
@@ -1520,10 +2098,37 @@ where
         PskSecret::new(self.cipher_suite_provider())
     }
 
-    #[cfg(feature = "secret_tree_access")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    #[inline(never)]
-    pub async fn next_encryption_key(&mut self) -> Result<MessageKey, MlsError> {
+    /// The joiner secret from which the current epoch was entered.
+    ///
+    /// This is intended for comparing against other MLS implementations when
+    /// a welcome message fails to decrypt.
+    ///
+    /// # Warning
+    ///
+    /// The joiner secret allows decrypting the welcome messages for this
+    /// epoch and deriving all of its secrets. It must never leave a debugging
+    /// environment.
+    #[cfg(feature = "test_exports")]
+    pub fn joiner_secret(&self) -> Option<&[u8]> {
+        self.join_secrets.as_ref().map(JoinSecrets::joiner_secret)
+    }
+
+    /// The welcome secret derived from [`Group::joiner_secret`] and the PSKs
+    /// used to enter the current epoch.
+    ///
+    /// # Warning
+    ///
+    /// The welcome secret allows decrypting the group info of the welcome
+    /// messages for this epoch. It must never leave a debugging environment.
+    #[cfg(feature = "test_exports")]
+    pub fn welcome_secret(&self) -> Option<&[u8]> {
+        self.join_secrets.as_ref().map(JoinSecrets::welcome_secret)
+    }
+
+    #[cfg(feature = "secret_tree_access")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[inline(never)]
+    pub async fn next_encryption_key(&mut self) -> Result<MessageKey, MlsError> {
         self.epoch_secrets
             .secret_tree
             .next_message_key(
@@ -1576,15 +2181,12 @@ impl<C: ClientConfig> Group<C> {
             psk.id.psk_nonce = psk_id.psk_nonce.clone();
             PskSecret::calculate(&[psk], cipher_suite_provider).await
         } else {
-            PskResolver::<
-                <C as ClientConfig>::GroupStateStorage,
-                <C as ClientConfig>::KeyPackageRepository,
-                <C as ClientConfig>::PskStore,
-            > {
+            PskResolver {
                 group_context: None,
                 current_epoch: None,
-                prior_epochs: None,
+                prior_epochs: Some(&config.group_state_storage()),
                 psk_store: &config.secret_store(),
+                resumption_psk_resolver: &config.resumption_psk_resolver(),
             }
             .resolve_to_secret(psks, cipher_suite_provider)
             .await
@@ -1611,6 +2213,7 @@ impl<C: ClientConfig> Group<C> {
             config,
             #[cfg(feature = "psk")]
             None,
+            None,
         )
         .await
         .map(|info| info.0)
@@ -1621,6 +2224,7 @@ impl<C: ClientConfig> Group<C> {
         welcome: &MlsMessage,
         config: &C,
         #[cfg(feature = "psk")] additional_psk: Option<PskSecretInput>,
+        welcome_aad: Option<&[u8]>,
     ) -> Result<(GroupInfo, KeyPackageGeneration, GroupSecrets, PskSecret), MlsError> {
         let protocol_version = welcome.version;
 
@@ -1648,12 +2252,13 @@ impl<C: ClientConfig> Group<C> {
         // cipher suite and the HPKE private key corresponding to the GroupSecrets. If a
         // PreSharedKeyID is part of the GroupSecrets and the client is not in possession of
         // the corresponding PSK, return an error
-        let group_secrets = GroupSecrets::decrypt(
+        let group_secrets = GroupSecrets::decrypt_with_aad(
             &cipher_suite_provider,
             &key_package_generation.init_secret_key,
             &key_package_generation.key_package.hpke_init_key,
             &welcome.encrypted_group_info,
             &encrypted_group_secrets.encrypted_group_secrets,
+            welcome_aad,
         )
         .await?;
 
@@ -1744,6 +2349,31 @@ where
         &self,
         message: PublicMessage,
     ) -> Result<EventOrContent<Self::OutputType>, MlsError> {
+        // The membership key of a past epoch is not retained, so only the
+        // signature can be verified
+        #[cfg(feature = "prior_epoch")]
+        if message.content.epoch != self.context().epoch {
+            let epoch = self
+                .state_repo
+                .get_epoch(message.content.epoch)
+                .await?
+                .ok_or(MlsError::EpochNotFound)?;
+
+            let auth_content = AuthenticatedContent::from(message);
+
+            verify_auth_content_signature(
+                &self.cipher_suite_provider,
+                SignaturePublicKeysContainer::List(&epoch.signature_public_keys),
+                &epoch.context,
+                &auth_content,
+                #[cfg(feature = "by_ref_proposal")]
+                &external_signers(&epoch.context),
+            )
+            .await?;
+
+            return Ok(EventOrContent::Content(auth_content));
+        }
+
         let auth_content = verify_plaintext_authentication(
             &self.cipher_suite_provider,
             message,
@@ -1918,6 +2548,24 @@ where
 
         self.pending_commit = None;
 
+        // Keep the welcome secrets only if the commit that was just applied is the one they
+        // were created for.
+        self.welcome_secrets = self.pending_welcome_secrets.take().filter(|secrets| {
+            secrets.confirmed_transcript_hash == self.state.context.confirmed_transcript_hash
+        });
+
+        #[cfg(feature = "test_exports")]
+        {
+            self.join_secrets = Some(
+                JoinSecrets::new(
+                    &self.cipher_suite_provider,
+                    &key_schedule_result.joiner_secret,
+                    &psk,
+                )
+                .await?,
+            );
+        }
+
         Ok(())
     }
 
@@ -1961,6 +2609,15 @@ where
         None
     }
 
+    #[cfg(feature = "prior_epoch")]
+    fn commit_lookback(&self) -> u64 {
+        self.config.commit_lookback()
+    }
+
+    fn max_group_size(&self) -> Option<u32> {
+        self.config.max_group_size()
+    }
+
     fn cipher_suite_provider(&self) -> &Self::CipherSuiteProvider {
         &self.cipher_suite_provider
     }
@@ -1975,10 +2632,10 @@ mod tests {
         client::test_utils::{
             test_client_with_key_pkg, TestClientBuilder, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION,
         },
-        client_builder::test_utils::TestClientConfig,
+        client_builder::{test_utils::TestClientConfig, ClientBuilder, MlsConfig, WithMlsRules},
         crypto::test_utils::TestCryptoProvider,
         group::proposal_filter::ProposalInfo,
-        identity::test_utils::get_test_signing_identity,
+        identity::test_utils::{get_test_signing_identity, BasicWithCustomProvider},
         key_package::test_utils::test_key_package_message,
         mls_rules::CommitOptions,
         tree_kem::{
@@ -1990,13 +2647,11 @@ mod tests {
     #[cfg(feature = "by_ref_proposal")]
     use crate::{
         client::test_utils::{test_client_with_key_pkg_custom, TEST_CUSTOM_PROPOSAL_TYPE},
-        client_builder::{ClientBuilder, MlsConfig},
         group::{
             mls_rules::{CommitDirection, CommitSource},
             proposal_filter::ProposalBundle,
         },
         identity::basic::BasicIdentityProvider,
-        identity::test_utils::BasicWithCustomProvider,
     };
 
     #[cfg(any(feature = "private_message", feature = "custom_proposal"))]
@@ -2007,12 +2662,17 @@ mod tests {
 
     use crate::{extension::RequiredCapabilitiesExt, key_package::test_utils::test_key_package};
 
-    #[cfg(all(feature = "by_ref_proposal", feature = "custom_proposal"))]
     use super::test_utils::test_group_custom_config;
 
     #[cfg(feature = "psk")]
     use crate::{client::Client, psk::PreSharedKey};
 
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    use crate::psk::{NoResumptionPskResolver, ResumptionPskResolver};
+
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    use core::convert::Infallible;
+
     #[cfg(any(feature = "by_ref_proposal", feature = "private_message"))]
     use crate::group::test_utils::random_bytes;
 
@@ -2047,6 +2707,18 @@ mod tests {
 
     use mls_rs_core::extension::MlsExtension;
 
+    #[cfg(target_has_atomic = "ptr")]
+    use alloc::sync::Arc;
+
+    #[cfg(not(target_has_atomic = "ptr"))]
+    use portable_atomic_util::Arc;
+
+    #[cfg(feature = "std")]
+    use std::sync::{Mutex, MutexGuard};
+
+    #[cfg(not(feature = "std"))]
+    use spin::{Mutex, MutexGuard};
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_create_group() {
         for (protocol_version, cipher_suite) in ProtocolVersion::all().flat_map(|p| {
@@ -2301,7 +2973,8 @@ mod tests {
             &commit_output.welcome_messages[0],
             None,
             bob_client.config,
-            bob_client.signer.unwrap(),
+            |_| Ok(bob_client.signer.unwrap()),
+            None,
         )
         .await
         .map(|_| ());
@@ -2309,6 +2982,85 @@ mod tests {
         assert_matches!(bob_group, Err(MlsError::RatchetTreeNotFound));
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_with_mismatched_ratchet_tree_is_rejected() {
+        let mut alice_group = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            Default::default(),
+            None,
+            Some(CommitOptions::new().with_ratchet_tree_extension(false)),
+        )
+        .await;
+
+        let (bob_client, bob_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        // The tree of the current epoch does not contain bob
+        let stale_tree = alice_group.export_tree().into_owned();
+
+        let commit_output = alice_group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        let welcome = &commit_output.welcome_messages[0];
+        let res = bob_client
+            .join_group(Some(stale_tree), welcome)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+
+        bob_client
+            .join_group(Some(alice_group.export_tree()), welcome)
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_bound_to_aad_requires_matching_aad() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (bob_client, bob_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit_output = alice_group
+            .commit_builder()
+            .add_member(bob_key_package)
+            .unwrap()
+            .welcome_aad(b"session".to_vec())
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.apply_pending_commit().await.unwrap();
+
+        let welcome = &commit_output.welcome_messages[0];
+
+        let res = bob_client.join_group(None, welcome).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
+
+        let res = bob_client
+            .join_group_with_welcome_aad(None, welcome, b"other session")
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::CryptoProviderError(_)));
+
+        let (bob_group, _) = bob_client
+            .join_group_with_welcome_aad(None, welcome, b"session")
+            .await
+            .unwrap();
+
+        assert_eq!(bob_group.epoch_secrets, alice_group.epoch_secrets);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_reused_key_package() -> Result<(), MlsError> {
         let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
@@ -2478,6 +3230,59 @@ mod tests {
         );
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_context_extensions_update_is_applied_by_all_members() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let mut extensions = ExtensionList::new();
+
+        extensions
+            .set_from(RequiredCapabilitiesExt {
+                credentials: vec![CredentialType::BASIC],
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_ne!(groups[0].context().extensions, extensions);
+
+        let commit = groups[0]
+            .commit_builder()
+            .set_group_context_ext(extensions.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        groups[0].apply_pending_commit().await.unwrap();
+
+        // Processing verifies the confirmation tag, which is computed over the
+        // transcript hash of the new context, so a mismatch would fail here.
+        process_commit(&mut groups, commit.commit_message, 0).await;
+
+        for group in &groups[1..] {
+            assert_eq!(group.context().extensions, extensions);
+            assert_eq!(group.context(), groups[0].context());
+            assert_eq!(
+                group.state.confirmation_tag,
+                groups[0].state.confirmation_tag
+            );
+
+            assert_eq!(
+                group.epoch_authenticator().unwrap(),
+                groups[0].epoch_authenticator().unwrap()
+            );
+        }
+
+        // The tree hash of the new context is used in the next epoch as well
+        let commit = groups[1].commit(vec![]).await.unwrap();
+        groups[1].apply_pending_commit().await.unwrap();
+        process_commit(&mut groups, commit.commit_message, 1).await;
+
+        assert!(groups
+            .iter()
+            .all(|group| group.context().extensions == extensions));
+    }
+
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn make_group_with_required_capabilities(
         required_caps: RequiredCapabilitiesExt,
@@ -2757,1617 +3562,4025 @@ mod tests {
         assert_matches!(res, Err(MlsError::UnencryptedApplicationMessage));
     }
 
+    #[cfg(all(feature = "by_ref_proposal", feature = "private_message"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_description_external_commit() {
-        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-
-        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
-
-        let bob = TestClientBuilder::new_for_test()
-            .signing_identity(bob_identity, secret_key, TEST_CIPHER_SUITE)
-            .build();
+    async fn received_messages_describe_their_content() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
 
-        let (bob_group, commit) = bob
-            .external_commit_builder()
-            .unwrap()
-            .build(
-                alice_group
-                    .group_info_message_allowing_ext_commit(true)
-                    .await
-                    .unwrap(),
-            )
+        let message = groups[1]
+            .encrypt_application_message(b"hello", b"aad".to_vec())
             .await
             .unwrap();
 
-        let event = alice_group.process_message(commit).await.unwrap();
+        let received = groups[0].process_incoming_message(message).await.unwrap();
 
-        let ReceivedMessage::Commit(commit_description) = event else {
-            panic!("expected commit");
-        };
+        assert_matches!(
+            received,
+            ReceivedMessage::ApplicationMessage(ref m)
+                if m.sender_index == 1 && m.data() == b"hello" && m.authenticated_data == b"aad"
+        );
 
-        assert!(commit_description.is_external);
-        assert_eq!(commit_description.committer, 1);
+        let proposal = groups[2].propose_remove(1, Vec::new()).await.unwrap();
+        let received = groups[0].process_incoming_message(proposal).await.unwrap();
 
-        let new_epoch = match commit_description.effect {
-            CommitEffect::NewEpoch(new_epoch) => new_epoch,
-            _ => panic!("unexpected commit effect"),
+        assert_matches!(
+            received,
+            ReceivedMessage::Proposal(ProposalMessageDescription {
+                sender: ProposalSender::Member(2),
+                proposal: Proposal::Remove(ref r),
+                ..
+            }) if *r.to_remove == 1
+        );
+
+        let commit = groups[0].commit(Vec::new()).await.unwrap().commit_message;
+        let received = groups[2].process_incoming_message(commit).await.unwrap();
+
+        let ReceivedMessage::Commit(description) = received else {
+            panic!("expected commit");
         };
 
-        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        assert!(!description.is_external);
+        assert_eq!(description.committer, 0);
 
-        itertools::assert_equal(
-            bob_group.roster().members_iter(),
-            alice_group.roster().members_iter(),
+        assert_matches!(
+            description.effect,
+            CommitEffect::NewEpoch(ref new_epoch)
+                if matches!(
+                    new_epoch.applied_proposals.as_slice(),
+                    [ProposalInfo { proposal: Proposal::Remove(r), .. }] if *r.to_remove == 1
+                )
         );
     }
 
+    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_join_new_group_externally() {
-        use crate::client::test_utils::TestClientBuilder;
+    async fn commit_effect_describes_roster_update() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
 
-        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let update = groups[1].propose_update(Vec::new()).await.unwrap();
+        groups[0].process_incoming_message(update).await.unwrap();
 
-        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+        let prior_updated = groups[0].member_at_index(1).unwrap();
+        let removed = groups[0].member_at_index(2).unwrap();
 
-        let bob = TestClientBuilder::new_for_test()
-            .signing_identity(bob_identity, secret_key, TEST_CIPHER_SUITE)
-            .build();
+        let (_, dave_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "dave").await;
 
-        let (_, commit) = bob
-            .external_commit_builder()
+        let commit = groups[0]
+            .commit_builder()
+            .add_member(dave_key_package)
             .unwrap()
-            .with_tree_data(alice_group.export_tree().into_owned())
-            .build(
-                alice_group
-                    .group_info_message_allowing_ext_commit(false)
-                    .await
-                    .unwrap(),
-            )
+            .remove_member(2)
+            .unwrap()
+            .build()
             .await
-            .unwrap();
+            .unwrap()
+            .commit_message;
 
-        alice_group.process_message(commit).await.unwrap();
-    }
+        let received = groups[1].process_incoming_message(commit).await.unwrap();
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn test_membership_tag_from_non_member() {
-        let (mut alice_group, mut bob_group) =
-            test_two_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, true).await;
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = received
+        else {
+            panic!("expected commit creating a new epoch");
+        };
 
-        let mut commit_output = alice_group.commit(vec![]).await.unwrap();
+        let roster_update = &new_epoch.roster_update;
 
-        let plaintext = match commit_output.commit_message.payload {
-            MlsMessagePayload::Plain(ref mut plain) => plain,
-            _ => panic!("Non plaintext message"),
-        };
+        assert_eq!(roster_update.added.len(), 1);
+        assert_eq!(
+            roster_update.added[0],
+            groups[1]
+                .member_at_index(roster_update.added[0].index)
+                .unwrap()
+        );
 
-        plaintext.content.sender = Sender::NewMemberCommit;
+        assert_eq!(
+            roster_update.added[0].signing_identity.credential,
+            get_test_basic_credential(b"dave".to_vec())
+        );
 
-        let res = bob_group
-            .process_message(commit_output.commit_message)
-            .await;
+        assert_eq!(roster_update.removed, vec![removed]);
+        assert_eq!(roster_update.updated.len(), 1);
+        assert_eq!(roster_update.updated[0].prior, prior_updated);
+        assert_eq!(
+            roster_update.updated[0].new,
+            groups[1].member_at_index(1).unwrap()
+        );
 
-        assert_matches!(res, Err(MlsError::MembershipTagForNonMember));
+        assert!(!new_epoch.group_context_extensions_changed);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn test_partial_commits() {
-        let protocol_version = TEST_PROTOCOL_VERSION;
-        let cipher_suite = TEST_CIPHER_SUITE;
-
-        let mut alice = test_group(protocol_version, cipher_suite).await;
-        let (mut bob, _) = alice.join("bob").await;
-        let (mut charlie, commit) = alice.join("charlie").await;
-        bob.process_message(commit).await.unwrap();
+    async fn commit_effect_reports_group_context_extensions_change() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        let (_, commit) = charlie.join("dave").await;
+        assert!(!groups[0].context().extensions.is_empty());
 
-        alice.process_message(commit.clone()).await.unwrap();
-        bob.process_message(commit.clone()).await.unwrap();
+        let commit = groups[0]
+            .commit_builder()
+            .set_group_context_ext(ExtensionList::new())
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
 
-        let Content::Commit(commit) = commit.into_plaintext().unwrap().content.content else {
-            panic!("Expected commit")
-        };
+        let received = groups[1].process_incoming_message(commit).await.unwrap();
 
-        assert!(commit.path.is_none());
+        assert_matches!(
+            received,
+            ReceivedMessage::Commit(CommitMessageDescription {
+                effect: CommitEffect::NewEpoch(ref new_epoch),
+                ..
+            }) if new_epoch.group_context_extensions_changed
+                && new_epoch.roster_update == Default::default()
+        );
     }
 
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn group_with_path_required() -> TestGroup {
-        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_effect_counts_path_encryptions() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 4).await;
 
-        alice.config.0.mls_rules.commit_options.path_required = true;
+        // Member 0 populates its direct path, nodes 1 and 3, leaving node 5 blank.
+        let commit = groups[0].commit(Vec::new()).await.unwrap().commit_message;
+        groups[0].apply_pending_commit().await.unwrap();
+        process_commit(&mut groups, commit, 0).await;
 
-        alice
+        // The copath of member 3 is leaf 2 and node 1, each resolving to a single node.
+        let commit = groups[3].commit(Vec::new()).await.unwrap().commit_message;
+        let received = groups[0].process_incoming_message(commit).await.unwrap();
+
+        assert_matches!(
+            received,
+            ReceivedMessage::Commit(CommitMessageDescription {
+                effect: CommitEffect::NewEpoch(ref new_epoch),
+                ..
+            }) if new_epoch.path_encryptions == 2
+        );
+
+        let applied = groups[3].apply_pending_commit().await.unwrap();
+
+        assert_matches!(
+            applied.effect,
+            CommitEffect::NewEpoch(ref new_epoch) if new_epoch.path_encryptions == 2
+        );
     }
 
+    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn old_hpke_secrets_are_removed() {
-        let mut alice = group_with_path_required().await;
-        alice.join("bob").await;
-        alice.join("charlie").await;
+    async fn previewing_commit_leaves_group_unchanged() {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.mls_rules(
+                DefaultMlsRules::default()
+                    .with_encryption_options(EncryptionOptions::new(true, PaddingMode::None)),
+            )
+        })
+        .await;
 
-        alice
-            .commit_builder()
-            .remove_member(1)
-            .unwrap()
-            .build()
-            .await
-            .unwrap();
+        let (mut bob, _) = alice.join("bob").await;
 
-        assert!(alice.private_tree.secret_keys[1].is_some());
-        alice.process_pending_commit().await.unwrap();
-        assert!(alice.private_tree.secret_keys[1].is_none());
+        let commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+        assert_eq!(commit.wire_format(), WireFormat::PrivateMessage);
+
+        let epoch_authenticator = bob.epoch_authenticator().unwrap();
+        let preview = bob.preview_commit(&commit).await.unwrap();
+
+        assert_matches!(
+            preview.description.effect,
+            CommitEffect::NewEpoch(ref new_epoch) if new_epoch.epoch == 2
+        );
+
+        // Discarding the preview keeps the group in its current epoch with all
+        // secrets needed to process the same commit again.
+        drop(preview);
+        assert_eq!(bob.current_epoch(), 1);
+        assert_eq!(bob.epoch_authenticator().unwrap(), epoch_authenticator);
+
+        bob.process_message(commit).await.unwrap();
+        assert_eq!(bob.current_epoch(), 2);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn old_hpke_secrets_of_removed_are_removed() {
-        let mut alice = group_with_path_required().await;
-        alice.join("bob").await;
-        let (mut charlie, _) = alice.join("charlie").await;
+    async fn applied_preview_matches_direct_apply() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
 
-        let commit = charlie
+        let commit = groups[0]
             .commit_builder()
-            .remove_member(1)
+            .remove_member(2)
             .unwrap()
             .build()
             .await
-            .unwrap();
+            .unwrap()
+            .commit_message;
 
-        assert!(alice.private_tree.secret_keys[1].is_some());
-        alice.process_message(commit.commit_message).await.unwrap();
-        assert!(alice.private_tree.secret_keys[1].is_none());
-    }
+        let mut direct = groups[1].group.clone();
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn old_hpke_secrets_of_updated_are_removed() {
-        let mut alice = group_with_path_required().await;
-        let (mut bob, _) = alice.join("bob").await;
-        let (mut charlie, commit) = alice.join("charlie").await;
-        bob.process_message(commit).await.unwrap();
+        let Ok(ReceivedMessage::Commit(expected)) =
+            direct.process_incoming_message(commit.clone()).await
+        else {
+            panic!("expected a commit");
+        };
 
-        let update = bob.propose_update(vec![]).await.unwrap();
-        charlie.process_message(update.clone()).await.unwrap();
-        alice.process_message(update).await.unwrap();
+        let preview = groups[1].preview_commit(&commit).await.unwrap();
+        assert_eq!(preview.description, expected);
 
-        let commit = charlie.commit(vec![]).await.unwrap();
+        let applied = groups[1].apply_previewed_commit(preview).await.unwrap();
+        assert_eq!(applied, expected);
 
-        assert!(alice.private_tree.secret_keys[1].is_some());
-        alice.process_message(commit.commit_message).await.unwrap();
-        assert!(alice.private_tree.secret_keys[1].is_none());
+        assert_eq!(groups[1].state, direct.state);
+        assert_eq!(
+            groups[1].epoch_authenticator().unwrap(),
+            direct.epoch_authenticator().unwrap()
+        );
     }
 
-    #[cfg(feature = "psk")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn only_selected_members_of_the_original_group_can_join_subgroup() {
-        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-        let (mut bob, _) = alice.join("bob").await;
-        let (carol, commit) = alice.join("carol").await;
-
-        // Apply the commit that adds carol
-        bob.process_incoming_message(commit).await.unwrap();
-
-        let bob_identity = bob.current_member_signing_identity().unwrap().clone();
-        let signer = bob.signer.clone();
+    async fn stale_preview_cannot_be_applied() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        let new_key_pkg = Client::new(
-            bob.config.clone(),
-            Some(signer),
-            Some((bob_identity, TEST_CIPHER_SUITE)),
-            TEST_PROTOCOL_VERSION,
-        )
-        .generate_key_package_message(Default::default(), Default::default())
-        .await
-        .unwrap();
+        let commit = groups[0].commit(Vec::new()).await.unwrap().commit_message;
+        let preview = groups[1].preview_commit(&commit).await.unwrap();
 
-        let (mut alice_sub_group, welcome) = alice
-            .branch(b"subgroup".to_vec(), vec![new_key_pkg])
-            .await
-            .unwrap();
+        groups[1].process_message(commit).await.unwrap();
 
-        let welcome = &welcome[0];
+        let res = groups[1].apply_previewed_commit(preview).await;
+        assert_matches!(res, Err(MlsError::InvalidEpoch));
+    }
 
-        let (mut bob_sub_group, _) = bob.join_subgroup(welcome, None).await.unwrap();
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_description_external_commit() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        // Carol can't join
-        let res = carol.join_subgroup(welcome, None).await.map(|_| ());
-        assert_matches!(res, Err(_));
+        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
 
-        // Alice and Bob can still talk
-        let commit_output = alice_sub_group.commit(vec![]).await.unwrap();
+        let bob = TestClientBuilder::new_for_test()
+            .signing_identity(bob_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
 
-        bob_sub_group
-            .process_incoming_message(commit_output.commit_message)
+        let (bob_group, commit) = bob
+            .external_commit_builder()
+            .unwrap()
+            .build(
+                alice_group
+                    .group_info_message_allowing_ext_commit(true)
+                    .await
+                    .unwrap(),
+            )
             .await
             .unwrap();
-    }
 
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn joining_group_fails_if_unsupported<F>(
-        f: F,
-    ) -> Result<(TestGroup, MlsMessage), MlsError>
-    where
-        F: FnMut(&mut TestClientConfig),
-    {
-        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-        alice_group.join_with_custom_config("alice", false, f).await
-    }
+        let event = alice_group.process_message(commit).await.unwrap();
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn joining_group_fails_if_protocol_version_is_not_supported() {
-        let res = joining_group_fails_if_unsupported(|config| {
-            config.0.settings.protocol_versions.clear();
-        })
-        .await
-        .map(|_| ());
+        let ReceivedMessage::Commit(commit_description) = event else {
+            panic!("expected commit");
+        };
 
-        assert_matches!(
-            res,
-            Err(MlsError::UnsupportedProtocolVersion(v)) if v ==
-                TEST_PROTOCOL_VERSION
-        );
-    }
+        assert!(commit_description.is_external);
+        assert_eq!(commit_description.committer, 1);
 
-    // WebCrypto does not support disabling ciphersuites
-    #[cfg(not(target_arch = "wasm32"))]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn joining_group_fails_if_cipher_suite_is_not_supported() {
-        let res = joining_group_fails_if_unsupported(|config| {
-            config
-                .0
-                .crypto_provider
-                .enabled_cipher_suites
-                .retain(|&x| x != TEST_CIPHER_SUITE);
-        })
-        .await
-        .map(|_| ());
+        let new_epoch = match commit_description.effect {
+            CommitEffect::NewEpoch(new_epoch) => new_epoch,
+            _ => panic!("unexpected commit effect"),
+        };
 
-        assert_matches!(
-            res,
-            Err(MlsError::UnsupportedCipherSuite(TEST_CIPHER_SUITE))
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+
+        itertools::assert_equal(
+            bob_group.roster().members_iter(),
+            alice_group.roster().members_iter(),
         );
     }
 
-    #[cfg(feature = "private_message")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn member_can_see_sender_creds() {
+    async fn can_join_new_group_externally() {
+        use crate::client::test_utils::TestClientBuilder;
+
         let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-        let (mut bob_group, _) = alice_group.join("bob").await;
 
-        let bob_msg = b"I'm Bob";
+        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
 
-        let msg = bob_group
-            .encrypt_application_message(bob_msg, vec![])
+        let bob = TestClientBuilder::new_for_test()
+            .signing_identity(bob_identity, secret_key, TEST_CIPHER_SUITE)
+            .build();
+
+        let (_, commit) = bob
+            .external_commit_builder()
+            .unwrap()
+            .with_tree_data(alice_group.export_tree().into_owned())
+            .build(
+                alice_group
+                    .group_info_message_allowing_ext_commit(false)
+                    .await
+                    .unwrap(),
+            )
             .await
             .unwrap();
 
-        let received_by_alice = alice_group.process_incoming_message(msg).await.unwrap();
-
-        assert_matches!(
-            received_by_alice,
-            ReceivedMessage::ApplicationMessage(ApplicationMessageDescription { sender_index, .. })
-                if sender_index == bob_group.current_member_index()
-        );
+        alice_group.process_message(commit).await.unwrap();
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn members_of_a_group_have_identical_authentication_secrets() {
+    async fn external_commit_group_info_without_tree_round_trips() {
         let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-        let (bob_group, _) = alice_group.join("bob").await;
 
-        assert_eq!(
-            alice_group.epoch_authenticator().unwrap(),
-            bob_group.epoch_authenticator().unwrap()
-        );
-    }
+        let group_info = alice_group
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
 
-    #[cfg(feature = "private_message")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn member_cannot_decrypt_same_message_twice() {
-        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
-        let (mut bob_group, _) = alice_group.join("bob").await;
+        let group_info = MlsMessage::from_bytes(&group_info.to_bytes().unwrap()).unwrap();
+        let info = group_info.as_group_info().unwrap();
 
-        let message = alice_group
-            .encrypt_application_message(b"foobar", Vec::new())
+        assert!(info
+            .extensions
+            .has_extension(ExternalPubExt::extension_type()));
+        assert!(!info
+            .extensions
+            .has_extension(RatchetTreeExt::extension_type()));
+        assert_eq!(info.confirmation_tag, alice_group.state.confirmation_tag);
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
             .await
-            .unwrap();
+            .build();
 
-        let received_message = bob_group
-            .process_incoming_message(message.clone())
+        let res = bob
+            .external_commit_builder()
+            .unwrap()
+            .build(group_info.clone())
             .await
-            .unwrap();
+            .map(|_| ());
 
-        assert_matches!(
-            received_message,
-            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
-        );
+        assert_matches!(res, Err(MlsError::RatchetTreeNotFound));
 
-        let res = bob_group.process_incoming_message(message).await;
+        let (bob_group, commit) = bob
+            .external_commit_builder()
+            .unwrap()
+            .with_tree_data(alice_group.export_tree().into_owned())
+            .build(group_info)
+            .await
+            .unwrap();
 
-        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+        alice_group.process_message(commit).await.unwrap();
+
+        assert_eq!(alice_group.context(), bob_group.context());
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn removing_requirements_allows_to_add() {
-        let mut alice_group = test_group_custom(
-            TEST_PROTOCOL_VERSION,
-            TEST_CIPHER_SUITE,
-            vec![17.into()],
-            None,
+    async fn external_commit_group_info_with_bad_signature_is_rejected() {
+        let alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let mut group_info = alice_group
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let MlsMessagePayload::GroupInfo(info) = &mut group_info.payload else {
+            panic!("expected group info")
+        };
+
+        info.signature[0] ^= 1;
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let res = bob
+            .external_commit_builder()
+            .unwrap()
+            .build(group_info)
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn resigned_group_info_with_added_tree_allows_external_commit() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let mut group_info = alice_group
+            .group_info_message_allowing_ext_commit(false)
+            .await
+            .unwrap();
+
+        let MlsMessagePayload::GroupInfo(info) = &mut group_info.payload else {
+            panic!("expected group info")
+        };
+
+        // Publish the tree along with the group info
+        let mut extensions = info.extensions().clone();
+
+        extensions
+            .set_from(RatchetTreeExt {
+                tree_data: alice_group.export_tree().into_owned(),
+            })
+            .unwrap();
+
+        info.set_extensions(extensions);
+
+        let alice_public_key = &alice_group
+            .current_member_signing_identity()
+            .unwrap()
+            .signature_key;
+
+        let res = info
+            .verify(&alice_group.cipher_suite_provider, alice_public_key, &())
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+
+        info.resign(
+            &alice_group.cipher_suite_provider,
+            &alice_group.signer,
             None,
         )
-        .await;
+        .await
+        .unwrap();
+
+        info.verify(&alice_group.cipher_suite_provider, alice_public_key, &())
+            .await
+            .unwrap();
+
+        let bob = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .build();
+
+        let (bob_group, commit) = bob
+            .external_commit_builder()
+            .unwrap()
+            .build(group_info)
+            .await
+            .unwrap();
+
+        alice_group.process_message(commit).await.unwrap();
+
+        assert_eq!(alice_group.context(), bob_group.context());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_info_cannot_be_resigned_by_other_member() {
+        let (alice_group, bob_group) =
+            test_two_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, true).await;
+
+        let group_info = alice_group.group_info_message(false).await.unwrap();
+        let mut info = group_info.into_group_info().unwrap();
+
+        let res = info
+            .resign(
+                &alice_group.cipher_suite_provider,
+                &bob_group.signer,
+                Some(alice_group.export_tree()),
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::GroupInfoSignerMismatch));
+
+        let res = info
+            .resign(
+                &alice_group.cipher_suite_provider,
+                &alice_group.signer,
+                None,
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::RatchetTreeNotFound));
+
+        let other_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let res = info
+            .resign(
+                &alice_group.cipher_suite_provider,
+                &alice_group.signer,
+                Some(other_group.export_tree()),
+            )
+            .await;
+
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_membership_tag_from_non_member() {
+        let (mut alice_group, mut bob_group) =
+            test_two_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, true).await;
+
+        let mut commit_output = alice_group.commit(vec![]).await.unwrap();
+
+        let plaintext = match commit_output.commit_message.payload {
+            MlsMessagePayload::Plain(ref mut plain) => plain,
+            _ => panic!("Non plaintext message"),
+        };
+
+        plaintext.content.sender = Sender::NewMemberCommit;
+
+        let res = bob_group
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::MembershipTagForNonMember));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_partial_commits() {
+        let protocol_version = TEST_PROTOCOL_VERSION;
+        let cipher_suite = TEST_CIPHER_SUITE;
+
+        let mut alice = test_group(protocol_version, cipher_suite).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let (mut charlie, commit) = alice.join("charlie").await;
+        bob.process_message(commit).await.unwrap();
+
+        let (_, commit) = charlie.join("dave").await;
+
+        alice.process_message(commit.clone()).await.unwrap();
+        bob.process_message(commit.clone()).await.unwrap();
+
+        let Content::Commit(commit) = commit.into_plaintext().unwrap().content.content else {
+            panic!("Expected commit")
+        };
+
+        assert!(commit.path.is_none());
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn group_with_path_required() -> TestGroup {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        alice.config.0.mls_rules.commit_options.path_required = true;
+
+        alice
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn old_hpke_secrets_are_removed() {
+        let mut alice = group_with_path_required().await;
+        alice.join("bob").await;
+        alice.join("charlie").await;
+
+        alice
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert!(alice.private_tree.secret_keys[1].is_some());
+        alice.process_pending_commit().await.unwrap();
+        assert!(alice.private_tree.secret_keys[1].is_none());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn old_hpke_secrets_of_removed_are_removed() {
+        let mut alice = group_with_path_required().await;
+        alice.join("bob").await;
+        let (mut charlie, _) = alice.join("charlie").await;
+
+        let commit = charlie
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert!(alice.private_tree.secret_keys[1].is_some());
+        alice.process_message(commit.commit_message).await.unwrap();
+        assert!(alice.private_tree.secret_keys[1].is_none());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn old_hpke_secrets_of_updated_are_removed() {
+        let mut alice = group_with_path_required().await;
+        let (mut bob, _) = alice.join("bob").await;
+        let (mut charlie, commit) = alice.join("charlie").await;
+        bob.process_message(commit).await.unwrap();
+
+        let update = bob.propose_update(vec![]).await.unwrap();
+        charlie.process_message(update.clone()).await.unwrap();
+        alice.process_message(update).await.unwrap();
+
+        let commit = charlie.commit(vec![]).await.unwrap();
+
+        assert!(alice.private_tree.secret_keys[1].is_some());
+        alice.process_message(commit.commit_message).await.unwrap();
+        assert!(alice.private_tree.secret_keys[1].is_none());
+    }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn only_selected_members_of_the_original_group_can_join_subgroup() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+        let (carol, commit) = alice.join("carol").await;
+
+        // Apply the commit that adds carol
+        bob.process_incoming_message(commit).await.unwrap();
+
+        let bob_identity = bob.current_member_signing_identity().unwrap().clone();
+        let signer = bob.signer.clone();
+
+        let new_key_pkg = Client::new(
+            bob.config.clone(),
+            Some(signer),
+            Some((bob_identity, TEST_CIPHER_SUITE)),
+            Default::default(),
+            TEST_PROTOCOL_VERSION,
+        )
+        .generate_key_package_message(Default::default(), Default::default())
+        .await
+        .unwrap();
+
+        let (mut alice_sub_group, welcome) = alice
+            .branch(b"subgroup".to_vec(), vec![new_key_pkg])
+            .await
+            .unwrap();
+
+        let welcome = &welcome[0];
+
+        let (mut bob_sub_group, _) = bob.join_subgroup(welcome, None).await.unwrap();
+
+        // Carol can't join
+        let res = carol.join_subgroup(welcome, None).await.map(|_| ());
+        assert_matches!(res, Err(_));
+
+        // Alice and Bob can still talk
+        let commit_output = alice_sub_group.commit(vec![]).await.unwrap();
+
+        bob_sub_group
+            .process_incoming_message(commit_output.commit_message)
+            .await
+            .unwrap();
+    }
+
+    #[cfg(all(feature = "psk", feature = "by_ref_proposal"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn reinit_successor_requires_resumption_psk_to_join() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        let proposal = alice
+            .propose_reinit(
+                None,
+                TEST_PROTOCOL_VERSION,
+                TEST_CIPHER_SUITE,
+                ExtensionList::new(),
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        bob.process_message(proposal).await.unwrap();
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+        bob.apply_pending_commit().await.unwrap();
+        alice.process_message(commit).await.unwrap();
+
+        // A client sharing Bob's key packages, but without access to the old group
+        let bob_client = Client::new(
+            bob.config.clone(),
+            Some(bob.signer.clone()),
+            Some((
+                bob.current_member_signing_identity().unwrap().clone(),
+                TEST_CIPHER_SUITE,
+            )),
+            Default::default(),
+            TEST_PROTOCOL_VERSION,
+        );
+
+        let reinit_epoch = bob.current_epoch();
+        let alice = alice.group.get_reinit_client(None, None).unwrap();
+        let bob = bob.group.get_reinit_client(None, None).unwrap();
+
+        let key_package = bob.generate_key_package().await.unwrap();
+        let (alice, welcome) = alice
+            .commit(vec![key_package], Default::default())
+            .await
+            .unwrap();
+
+        let res = bob_client.join_group(None, &welcome[0]).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::ResumptionEpochNotFound(e)) if e == reinit_epoch);
+
+        let (bob, _) = bob.join(&welcome[0], None).await.unwrap();
+
+        assert_eq!(
+            alice.epoch_authenticator().unwrap(),
+            bob.epoch_authenticator().unwrap()
+        );
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn joining_group_fails_if_unsupported<F>(
+        f: F,
+    ) -> Result<(TestGroup, MlsMessage), MlsError>
+    where
+        F: FnMut(&mut TestClientConfig),
+    {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice_group.join_with_custom_config("alice", false, f).await
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn joining_group_fails_if_protocol_version_is_not_supported() {
+        let res = joining_group_fails_if_unsupported(|config| {
+            config.0.settings.protocol_versions.clear();
+        })
+        .await
+        .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::UnsupportedProtocolVersion(v)) if v ==
+                TEST_PROTOCOL_VERSION
+        );
+    }
+
+    // WebCrypto does not support disabling ciphersuites
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn joining_group_fails_if_cipher_suite_is_not_supported() {
+        let res = joining_group_fails_if_unsupported(|config| {
+            config
+                .0
+                .crypto_provider
+                .enabled_cipher_suites
+                .retain(|&x| x != TEST_CIPHER_SUITE);
+        })
+        .await
+        .map(|_| ());
+
+        assert_matches!(
+            res,
+            Err(MlsError::UnsupportedCipherSuite(TEST_CIPHER_SUITE))
+        );
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_can_see_sender_creds() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let bob_msg = b"I'm Bob";
+
+        let msg = bob_group
+            .encrypt_application_message(bob_msg, vec![])
+            .await
+            .unwrap();
+
+        let received_by_alice = alice_group.process_incoming_message(msg).await.unwrap();
+
+        assert_matches!(
+            received_by_alice,
+            ReceivedMessage::ApplicationMessage(ApplicationMessageDescription { sender_index, .. })
+                if sender_index == bob_group.current_member_index()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn members_of_a_group_have_identical_authentication_secrets() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (bob_group, _) = alice_group.join("bob").await;
+
+        assert_eq!(
+            alice_group.epoch_authenticator().unwrap(),
+            bob_group.epoch_authenticator().unwrap()
+        );
+    }
+
+    #[cfg(feature = "private_message")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn member_cannot_decrypt_same_message_twice() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let message = alice_group
+            .encrypt_application_message(b"foobar", Vec::new())
+            .await
+            .unwrap();
+
+        let received_message = bob_group
+            .process_incoming_message(message.clone())
+            .await
+            .unwrap();
+
+        assert_matches!(
+            received_message,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"foobar"
+        );
+
+        let res = bob_group.process_incoming_message(message).await;
+
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+    }
+
+    #[cfg(feature = "out_of_order")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn out_of_order_keys_are_deleted_once_used() {
+        let mut alice_group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob_group, _) = alice_group.join("bob").await;
+
+        let mut messages = Vec::new();
+
+        for data in [b"zero", b"one_", b"two_"] {
+            let message = alice_group
+                .encrypt_application_message(data, Vec::new())
+                .await
+                .unwrap();
+
+            messages.push(message);
+        }
+
+        // Receiving generation 2 first retains the keys for generations 0 and 1.
+        bob_group
+            .process_incoming_message(messages[2].clone())
+            .await
+            .unwrap();
+
+        bob_group
+            .process_incoming_message(messages[0].clone())
+            .await
+            .unwrap();
+
+        let res = bob_group
+            .process_incoming_message(messages[0].clone())
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::KeyMissing(0)));
+
+        let res = bob_group
+            .process_incoming_message(messages[2].clone())
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::KeyMissing(2)));
+
+        // The key for generation 1 has not been used and is still available.
+        let received = bob_group
+            .process_incoming_message(messages[1].clone())
+            .await
+            .unwrap();
+
+        assert_matches!(
+            received,
+            ReceivedMessage::ApplicationMessage(m) if m.data() == b"one_"
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn removing_requirements_allows_to_add() {
+        let mut alice_group = test_group_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            vec![17.into()],
+            None,
+            None,
+        )
+        .await;
+
+        alice_group
+            .commit_builder()
+            .set_group_context_ext(
+                vec![RequiredCapabilitiesExt {
+                    extensions: vec![17.into()],
+                    ..Default::default()
+                }
+                .into_extension()
+                .unwrap()]
+                .try_into()
+                .unwrap(),
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice_group.process_pending_commit().await.unwrap();
+
+        let test_key_package =
+            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let test_key_package = MlsMessage::new(
+            TEST_PROTOCOL_VERSION,
+            MlsMessagePayload::KeyPackage(test_key_package),
+        );
+
+        alice_group
+            .commit_builder()
+            .add_member(test_key_package.clone())
+            .unwrap()
+            .set_group_context_ext(Default::default())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let CommitEffect::NewEpoch(new_epoch) =
+            alice_group.process_pending_commit().await.unwrap().effect
+        else {
+            panic!("unexpected commit effect")
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 2);
+
+        assert_matches!(
+            new_epoch.applied_proposals[0],
+            ProposalInfo { proposal: Proposal::Add(ref add), .. }
+                if add.key_package == test_key_package.into_key_package().unwrap()
+        );
+
+        assert_eq!(alice_group.roster().members_iter().count(), 2);
+    }
+
+    /// Three member group where the member at leaf 1 was removed, as seen by
+    /// the members at leaves 0 and 2.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn group_with_blank_leaf() -> Vec<TestGroup> {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        let commit = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
+
+        groups[0].apply_pending_commit().await.unwrap();
+        groups[2].process_message(commit).await.unwrap();
+
+        groups
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn leaf_node_lookup() {
+        let groups = group_with_blank_leaf().await;
+
+        let bob_leaf = groups[0].leaf_node(2).unwrap();
+        assert_eq!(bob_leaf, groups[2].current_user_leaf_node().unwrap());
+
+        let member = groups[0].member_at_index(2).unwrap();
+        assert_eq!(bob_leaf.signing_identity, member.signing_identity);
+        // Members are reported without GREASE values, leaf nodes are not
+        assert_eq!(bob_leaf.ungreased_capabilities(), member.capabilities);
+        assert_eq!(bob_leaf.ungreased_extensions(), member.extensions);
+
+        // Blank leaf
+        assert_eq!(groups[0].leaf_node(1), None);
+
+        // Out of range
+        assert_eq!(groups[0].leaf_node(4), None);
+        assert_eq!(groups[0].leaf_node(u32::MAX), None);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn leaf_encryption_key_lookup() {
+        let groups = group_with_blank_leaf().await;
+
+        let bob_leaf = groups[2].current_user_leaf_node().unwrap();
+        let key = groups[0].leaf_encryption_key(2).unwrap();
+        assert_eq!(key, bob_leaf.public_key);
+        assert_ne!(
+            key.as_ref(),
+            bob_leaf.signing_identity.signature_key.as_ref()
+        );
+
+        // Blank leaf
+        assert_eq!(groups[0].leaf_encryption_key(1), None);
+
+        // Out of range
+        assert_eq!(groups[0].leaf_encryption_key(4), None);
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn four_member_group_with_left_subtree() -> Vec<TestGroup> {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 4).await;
+        groups[1].config.0.mls_rules.commit_options.path_required = true;
+
+        // Leaf 1 refreshes its path, which gives node 1 (the parent of leaves 0 and 1)
+        // a key that only leaves 0 and 1 know.
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+        groups[1].apply_pending_commit().await.unwrap();
+        process_commit(&mut groups, commit, 1).await;
+
+        groups
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn subtree_members_can_open_sealed_data() {
+        let groups = four_member_group_with_left_subtree().await;
+
+        let ciphertext = groups[2].seal_to_subtree(1, b"secret").await.unwrap();
+
+        for group in &groups[..2] {
+            let plaintext = group.open_from_subtree(1, &ciphertext).await.unwrap();
+            assert_eq!(plaintext, b"secret");
+        }
+
+        for group in &groups[2..] {
+            let res = group.open_from_subtree(1, &ciphertext).await;
+            assert_matches!(res, Err(MlsError::NotInSubtree(1)));
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn only_the_leaf_owner_can_open_data_sealed_to_a_leaf() {
+        let groups = four_member_group_with_left_subtree().await;
+
+        let ciphertext = groups[0].seal_to_subtree(4, b"secret").await.unwrap();
+
+        let plaintext = groups[2].open_from_subtree(4, &ciphertext).await.unwrap();
+        assert_eq!(plaintext, b"secret");
+
+        for i in [0, 1, 3] {
+            let res = groups[i].open_from_subtree(4, &ciphertext).await;
+            assert_matches!(res, Err(MlsError::NotInSubtree(4)));
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn data_sealed_to_blank_node_reaches_its_resolution() {
+        let groups = four_member_group_with_left_subtree().await;
+
+        // Node 5, the parent of leaves 2 and 3, was never populated by a path update
+        assert!(groups[0].state.public_tree.nodes.is_blank(5).unwrap());
+
+        let ciphertext = groups[0].seal_to_subtree(5, b"secret").await.unwrap();
+
+        for group in &groups[2..] {
+            let plaintext = group.open_from_subtree(5, &ciphertext).await.unwrap();
+            assert_eq!(plaintext, b"secret");
+        }
+
+        for group in &groups[..2] {
+            let res = group.open_from_subtree(5, &ciphertext).await;
+            assert_matches!(res, Err(MlsError::NotInSubtree(5)));
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn data_sealed_to_blank_root_reaches_all_members() {
+        // Members were added without path updates, so all parent nodes are blank
+        let groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 4).await;
+        assert!(groups[0].state.public_tree.nodes.is_blank(3).unwrap());
+
+        let ciphertext = groups[1].seal_to_subtree(3, b"secret").await.unwrap();
+
+        for group in &groups {
+            let plaintext = group.open_from_subtree(3, &ciphertext).await.unwrap();
+            assert_eq!(plaintext, b"secret");
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn unmerged_leaves_can_open_sealed_data() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        groups[0].config.0.mls_rules.commit_options.path_required = true;
+
+        // Leaf 0 refreshes its path, which gives the root (node 3) a key
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        groups[0].apply_pending_commit().await.unwrap();
+        process_commit(&mut groups, commit, 0).await;
+
+        // A new member takes leaf 3 without a path update, so it is unmerged at the root
+        groups[0].config.0.mls_rules.commit_options.path_required = false;
+        let (erin, commit) = groups[0].join("erin").await;
+        process_commit(&mut groups, commit, 0).await;
+        groups.push(erin);
+
+        let parent = groups[0]
+            .state
+            .public_tree
+            .nodes
+            .borrow_as_parent(3)
+            .unwrap();
+        assert_eq!(parent.unmerged_leaves, vec![LeafIndex(3)]);
+
+        let ciphertext = groups[1].seal_to_subtree(3, b"secret").await.unwrap();
+
+        for group in &groups {
+            let plaintext = group.open_from_subtree(3, &ciphertext).await.unwrap();
+            assert_eq!(plaintext, b"secret");
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn sealing_to_empty_subtree_fails() {
+        let groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        // Node 6 is the blank leaf 3
+        let res = groups[0].seal_to_subtree(6, b"secret").await;
+        assert_matches!(res, Err(MlsError::ExpectedNode));
+
+        let res = groups[0].seal_to_subtree(100, b"secret").await;
+        assert_matches!(res, Err(MlsError::InvalidNodeIndex(100)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_wrong_source() {
+        // RFC, 13.4.2. "The leaf_node_source field MUST be set to commit."
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.leaf_node_source = LeafNodeSource::Update;
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidLeafNodeSource));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_wrong_parent_hash() {
+        // RFC 7.9.2. "Verify that the parent_hash field of the leaf node matches the parent hash of its parent"
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.leaf_node_source =
+                LeafNodeSource::Commit(crate::tree_kem::parent_hash::ParentHash::from(vec![0; 32]));
+
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let state_before = groups[2].state.clone();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::ParentHashMismatch));
+        assert_eq!(groups[2].state, state_before);
+
+        groups[0].clear_pending_commit();
+        groups[0].commit_modifiers = Default::default();
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        groups[2]
+            .process_message(commit_output.commit_message)
+            .await
+            .unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_same_hpke_key() {
+        // RFC 13.4.2. "Verify that the encryption_key value in the LeafNode is different from the committer's current leaf node"
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        // Group 0 starts using fixed key
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        groups[0].process_pending_commit().await.unwrap();
+        groups[2]
+            .process_message(commit_output.commit_message)
+            .await
+            .unwrap();
+
+        // Group 0 tries to use the fixed key againd
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::SameHpkeKey(0)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_duplicate_hpke_key() {
+        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `encryption_key`"
+
+        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
+            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
+        {
+            return;
+        }
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        // Group 1 uses the fixed key
+        groups[1].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+
+        process_commit(&mut groups, commit_output.commit_message, 1).await;
+
+        // Group 0 tries to use the fixed key too
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_duplicate_signature_key() {
+        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `signature_key`"
+
+        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
+            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
+        {
+            return;
+        }
+
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        // Group 1 uses the fixed key
+        groups[1].commit_modifiers.modify_leaf = |leaf, _| {
+            let sk = hex!(
+                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
+            )
+            .into();
+
+            leaf.signing_identity.signature_key =
+                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+
+            Some(sk)
+        };
+
+        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+
+        process_commit(&mut groups, commit_output.commit_message, 1).await;
+
+        // Group 0 tries to use the fixed key too
+        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
+            let sk = hex!(
+                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
+            )
+            .into();
+
+            leaf.signing_identity.signature_key =
+                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+
+            Some(sk)
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_incorrect_signature() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
+            leaf.signature[0] ^= 1;
+            None
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_used_context_extension() {
+        const EXT_TYPE: ExtensionType = ExtensionType::new(999);
+
+        // The new leaf of the committer doesn't support an extension set in group context
+        let extension = Extension::new(EXT_TYPE, vec![]);
+
+        let mut groups =
+            get_test_groups_with_features(3, vec![extension].into(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities = get_test_capabilities();
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[1]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::UnsupportedGroupExtension(EXT_TYPE)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_required_extension() {
+        // The new leaf of the committer doesn't support an extension required by group context
+
+        let extension = RequiredCapabilitiesExt {
+            extensions: vec![999.into()],
+            proposals: vec![],
+            credentials: vec![],
+        };
+
+        let extensions = vec![extension.into_extension().unwrap()];
+        let mut groups =
+            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities = Capabilities::default();
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert!(res.is_err());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_has_unsupported_credential() {
+        // The new leaf of the committer has a credential unsupported by another leaf
+        let mut groups =
+            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+
+        for group in groups.iter_mut() {
+            group.config.0.identity_provider.allow_any_custom = true;
+        }
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.signing_identity.credential = Credential::Custom(CustomCredential::new(
+                CredentialType::new(43),
+                leaf.signing_identity
+                    .credential
+                    .as_basic()
+                    .unwrap()
+                    .identifier
+                    .to_vec(),
+            ));
+
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::CredentialTypeOfNewLeafIsUnsupported));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_credential_used_in_another_leaf() {
+        // The new leaf of the committer doesn't support another leaf's credential
+
+        let mut groups =
+            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities.credentials = vec![2.into()];
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InUseCredentialTypeUnsupportedByNewLeaf));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_required_credential() {
+        // The new leaf of the committer doesn't support a credential required by group context
+
+        let extension = RequiredCapabilitiesExt {
+            extensions: vec![],
+            proposals: vec![],
+            credentials: vec![1.into()],
+        };
+
+        let extensions = vec![extension.into_extension().unwrap()];
+        let mut groups =
+            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities.credentials = vec![2.into()];
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[2]
+            .process_incoming_message(commit_output.commit_message)
+            .await;
+
+        assert_matches!(res, Err(MlsError::RequiredCredentialNotFound(_)));
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn make_x509_external_senders_ext() -> ExternalSendersExt {
+        let (_, ext_sender_pk) = test_cipher_suite_provider(TEST_CIPHER_SUITE)
+            .signature_key_generate()
+            .await
+            .unwrap();
+
+        let ext_sender_id = SigningIdentity {
+            signature_key: ext_sender_pk,
+            credential: Credential::X509(CertificateChain::from(vec![random_bytes(32)])),
+        };
+
+        ExternalSendersExt::new(vec![ext_sender_id])
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_leaf_not_supporting_external_sender_credential_leads_to_rejected_commit() {
+        let ext_senders = make_x509_external_senders_ext()
+            .await
+            .into_extension()
+            .unwrap();
+
+        let mut alice = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build()
+            .create_group(core::iter::once(ext_senders).collect(), Default::default())
+            .await
+            .unwrap();
+
+        // New leaf supports only basic credentials (used by the group) but not X509 used by external sender
+        alice.commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.capabilities.credentials = vec![CredentialType::BASIC];
+            Some(sk.clone())
+        };
+
+        alice.commit(vec![]).await.unwrap();
+        let res = alice.apply_pending_commit().await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn node_not_supporting_external_sender_credential_cannot_join_group() {
+        let ext_senders = make_x509_external_senders_ext()
+            .await
+            .into_extension()
+            .unwrap();
+
+        let mut alice = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build()
+            .create_group(core::iter::once(ext_senders).collect(), Default::default())
+            .await
+            .unwrap();
+
+        let (_, bob_key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(bob_key_pkg)
+            .unwrap()
+            .build()
+            .await;
+
+        assert_matches!(
+            commit,
+            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_senders_extension_is_rejected_if_member_does_not_support_credential_type() {
+        let mut alice = ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(
+                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
+            )
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let (_, bob_key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        alice
+            .commit_builder()
+            .add_member(bob_key_pkg)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+        assert_eq!(alice.roster().members_iter().count(), 2);
+
+        let ext_senders = make_x509_external_senders_ext()
+            .await
+            .into_extension()
+            .unwrap();
+
+        let res = alice
+            .commit_builder()
+            .set_group_context_ext(core::iter::once(ext_senders).collect())
+            .unwrap()
+            .build()
+            .await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
+        );
+    }
+
+    /*
+     * Edge case paths
+     */
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn committing_degenerate_path_succeeds() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
+            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
+            tree.update_node(get_test_25519_key(1u8), 3).unwrap();
+        };
+
+        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
+            leaf.public_key = get_test_25519_key(1u8);
+            Some(sk.clone())
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert!(res.is_ok());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn inserting_key_in_filtered_node_fails() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        let commit_output = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+
+        for group in groups.iter_mut().skip(2) {
+            group
+                .process_message(commit_output.commit_message.clone())
+                .await
+                .unwrap();
+        }
+
+        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
+            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
+        };
+
+        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
+            let mut path = path;
+            let mut node = path[0].clone();
+            node.public_key = get_test_25519_key(1u8);
+            path.insert(0, node);
+            path
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        // We should get a path validation error, since the path is too long
+        assert_matches!(res, Err(MlsError::WrongPathLen));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_with_too_short_path_fails() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+
+        let commit_output = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        groups[0].process_pending_commit().await.unwrap();
+
+        for group in groups.iter_mut().skip(2) {
+            group
+                .process_message(commit_output.commit_message.clone())
+                .await
+                .unwrap();
+        }
+
+        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
+            let mut path = path;
+            path.pop();
+            path
+        };
+
+        let commit_output = groups[0].commit(vec![]).await.unwrap();
+
+        let res = groups[7]
+            .process_message(commit_output.commit_message)
+            .await;
+
+        assert!(res.is_err());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn update_proposal_can_change_credential() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
+
+        let update = groups[0]
+            .propose_update_with_identity(secret_key, identity.clone(), vec![])
+            .await
+            .unwrap();
+
+        groups[1].process_message(update).await.unwrap();
+        let commit_output = groups[1].commit(vec![]).await.unwrap();
+
+        // Check that the credential was updated by in the committer's state.
+        groups[1].process_pending_commit().await.unwrap();
+        let new_member = groups[1].roster().member_with_index(0).unwrap();
+
+        assert_eq!(
+            new_member.signing_identity.credential,
+            get_test_basic_credential(b"member".to_vec())
+        );
+
+        assert_eq!(
+            new_member.signing_identity.signature_key,
+            identity.signature_key
+        );
+
+        // Check that the credential was updated in the updater's state.
+        groups[0]
+            .process_message(commit_output.commit_message)
+            .await
+            .unwrap();
+        let new_member = groups[0].roster().member_with_index(0).unwrap();
+
+        assert_eq!(
+            new_member.signing_identity.credential,
+            get_test_basic_credential(b"member".to_vec())
+        );
+
+        assert_eq!(
+            new_member.signing_identity.signature_key,
+            identity.signature_key
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receiving_commit_with_old_adds_fails() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "foobar").await;
+
+        let proposal = groups[0].propose_add(key_package, vec![]).await.unwrap();
+
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+
+        // 10 years from now
+        let future_time = MlsTime::now().seconds_since_epoch() + 10 * 365 * 24 * 3600;
+
+        let future_time =
+            MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(future_time));
+
+        groups[1].process_incoming_message(proposal).await.unwrap();
+        let res = groups[1]
+            .process_incoming_message_with_time(commit, future_time)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn custom_proposal_setup() -> (TestGroup, TestGroup) {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
+        })
+        .await;
+
+        let (bob, _) = alice
+            .join_with_custom_config("bob", true, |c| {
+                c.0.settings
+                    .custom_proposal_types
+                    .push(TEST_CUSTOM_PROPOSAL_TYPE)
+            })
+            .await
+            .unwrap();
+
+        (alice, bob)
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_value() {
+        let (mut alice, mut bob) = custom_proposal_setup().await;
+
+        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+
+        let commit = alice
+            .commit_builder()
+            .custom_proposal(custom_proposal.clone())
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
+
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = bob.process_incoming_message(commit).await.unwrap()
+        else {
+            panic!("unexpected commit effect");
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+
+        assert_eq!(
+            new_epoch.applied_proposals[0].proposal,
+            Proposal::Custom(custom_proposal)
+        );
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_reference() {
+        let (mut alice, mut bob) = custom_proposal_setup().await;
+
+        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+
+        let proposal = alice
+            .propose_custom(custom_proposal.clone(), vec![])
+            .await
+            .unwrap();
+
+        let recv_prop = bob.process_incoming_message(proposal).await.unwrap();
+
+        assert_matches!(recv_prop, ReceivedMessage::Proposal(ProposalMessageDescription { proposal: Proposal::Custom(c), ..})
+            if c == custom_proposal);
+
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = bob.process_incoming_message(commit).await.unwrap()
+        else {
+            panic!("unexpected commit effect");
+        };
+
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+
+        assert_eq!(
+            new_epoch.applied_proposals[0].proposal,
+            Proposal::Custom(custom_proposal)
+        );
+    }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_join_with_psk() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (bob, key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let psk_id = ExternalPskId::new(vec![0]);
+        let psk = PreSharedKey::from(vec![0]);
+
+        alice
+            .config
+            .secret_store()
+            .insert(psk_id.clone(), psk.clone());
+
+        bob.config.secret_store().insert(psk_id.clone(), psk);
+
+        let commit = alice
+            .commit_builder()
+            .add_member(key_pkg)
+            .unwrap()
+            .add_external_psk(psk_id)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        bob.join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "psk")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn cannot_join_without_psk() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let (bob, key_pkg) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let psk_id = ExternalPskId::new(vec![0]);
+
+        alice
+            .config
+            .secret_store()
+            .insert(psk_id.clone(), PreSharedKey::from(vec![0]));
+
+        let commit = alice
+            .commit_builder()
+            .add_member(key_pkg)
+            .unwrap()
+            .add_external_psk(psk_id.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        // Bob holds a valid key package but not the PSK, so he can not derive the joiner secret
+        let res = bob
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .map(|_| ());
+
+        assert_matches!(res, Err(MlsError::MissingRequiredPsk));
+
+        // A wrong PSK results in a welcome that can not be decrypted
+        bob.config
+            .secret_store()
+            .insert(psk_id, PreSharedKey::from(vec![1]));
+
+        let res = bob.join_group(None, &commit.welcome_messages[0]).await;
+        assert!(res.is_err());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn invalid_update_does_not_prevent_other_updates() {
+        const EXTENSION_TYPE: ExtensionType = ExtensionType::new(33);
+
+        let group_extensions = ExtensionList::from(vec![RequiredCapabilitiesExt {
+            extensions: vec![EXTENSION_TYPE],
+            ..Default::default()
+        }
+        .into_extension()
+        .unwrap()]);
+
+        // Alice creates a group requiring support for an extension
+        let mut alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .extension_type(EXTENSION_TYPE)
+            .build()
+            .create_group(group_extensions.clone(), Default::default())
+            .await
+            .unwrap();
+
+        let (bob_signing_identity, bob_secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let bob_client = TestClientBuilder::new_for_test()
+            .signing_identity(
+                bob_signing_identity.clone(),
+                bob_secret_key.clone(),
+                TEST_CIPHER_SUITE,
+            )
+            .extension_type(EXTENSION_TYPE)
+            .build();
+
+        let carol_client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("carol", TEST_CIPHER_SUITE)
+            .await
+            .extension_type(EXTENSION_TYPE)
+            .build();
+
+        let dave_client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("dave", TEST_CIPHER_SUITE)
+            .await
+            .extension_type(EXTENSION_TYPE)
+            .build();
+
+        // Alice adds Bob, Carol and Dave to the group. They all support the mandatory extension.
+        let commit = alice
+            .commit_builder()
+            .add_member(
+                bob_client
+                    .generate_key_package_message(Default::default(), Default::default())
+                    .await
+                    .unwrap(),
+            )
+            .unwrap()
+            .add_member(
+                carol_client
+                    .generate_key_package_message(Default::default(), Default::default())
+                    .await
+                    .unwrap(),
+            )
+            .unwrap()
+            .add_member(
+                dave_client
+                    .generate_key_package_message(Default::default(), Default::default())
+                    .await
+                    .unwrap(),
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let mut bob = bob_client
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap()
+            .0;
+
+        bob.write_to_storage().await.unwrap();
+
+        // Bob reloads his group data, but with parameters that will cause his generated leaves to
+        // not support the mandatory extension.
+        let mut bob = TestClientBuilder::new_for_test()
+            .signing_identity(bob_signing_identity, bob_secret_key, TEST_CIPHER_SUITE)
+            .key_package_repo(bob.config.key_package_repo())
+            .group_state_storage(bob.config.group_state_storage())
+            .build()
+            .load_group(alice.group_id())
+            .await
+            .unwrap();
+
+        let mut carol = carol_client
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap()
+            .0;
+
+        let mut dave = dave_client
+            .join_group(None, &commit.welcome_messages[0])
+            .await
+            .unwrap()
+            .0;
+
+        // Bob's updated leaf does not support the mandatory extension.
+        let bob_update = bob.propose_update(Vec::new()).await.unwrap();
+        let carol_update = carol.propose_update(Vec::new()).await.unwrap();
+        let dave_update = dave.propose_update(Vec::new()).await.unwrap();
+
+        // Alice receives the update proposals to be committed.
+        alice.process_incoming_message(bob_update).await.unwrap();
+        alice.process_incoming_message(carol_update).await.unwrap();
+        alice.process_incoming_message(dave_update).await.unwrap();
+
+        // Alice commits the update proposals.
+        alice.commit(Vec::new()).await.unwrap();
+
+        let CommitEffect::NewEpoch(new_epoch) = alice.apply_pending_commit().await.unwrap().effect
+        else {
+            panic!("unexpected commit effect");
+        };
+
+        let find_update_for = |id: &str| {
+            new_epoch
+                .applied_proposals
+                .iter()
+                .filter_map(|p| match p.proposal {
+                    Proposal::Update(ref u) => u.signing_identity().credential.as_basic(),
+                    _ => None,
+                })
+                .any(|c| c.identifier == id.as_bytes())
+        };
+
+        // Carol's and Dave's updates should be part of the commit.
+        assert!(find_update_for("carol"));
+        assert!(find_update_for("dave"));
+
+        // Bob's update should be rejected.
+        assert!(!find_update_for("bob"));
+
+        // Check that all members are still in the group.
+        let all_members_are_in = alice
+            .roster()
+            .members_iter()
+            .zip(["alice", "bob", "carol", "dave"])
+            .all(|(member, id)| {
+                member
+                    .signing_identity
+                    .credential
+                    .as_basic()
+                    .unwrap()
+                    .identifier
+                    == id.as_bytes()
+            });
+
+        assert!(all_members_are_in);
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_may_enforce_path() {
+        test_custom_proposal_mls_rules(true).await;
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_need_not_enforce_path() {
+        test_custom_proposal_mls_rules(false).await;
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn test_custom_proposal_mls_rules(path_required_for_custom: bool) {
+        let mls_rules = CustomMlsRules {
+            path_required_for_custom,
+            external_joiner_can_send_custom: true,
+        };
+
+        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+            .await
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let alice_pub_before = alice.current_user_leaf_node().unwrap().public_key.clone();
+
+        let kp = client_with_custom_rules(b"bob", mls_rules)
+            .await
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        alice
+            .commit_builder()
+            .custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
+            .add_member(kp)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        let alice_pub_after = &alice.current_user_leaf_node().unwrap().public_key;
+
+        if path_required_for_custom {
+            assert_ne!(alice_pub_after, &alice_pub_before);
+        } else {
+            assert_eq!(alice_pub_after, &alice_pub_before);
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_value_in_external_join_may_be_allowed() {
+        test_custom_proposal_by_value_in_external_join(true).await
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_value_in_external_join_may_not_be_allowed() {
+        test_custom_proposal_by_value_in_external_join(false).await
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn test_custom_proposal_by_value_in_external_join(external_joiner_can_send_custom: bool) {
+        let mls_rules = CustomMlsRules {
+            path_required_for_custom: true,
+            external_joiner_can_send_custom,
+        };
+
+        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+            .await
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let commit = client_with_custom_rules(b"bob", mls_rules)
+            .await
+            .external_commit_builder()
+            .unwrap()
+            .with_custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
+            .build(group_info)
+            .await;
+
+        if external_joiner_can_send_custom {
+            let commit = commit.unwrap().1;
+            alice.process_incoming_message(commit).await.unwrap();
+        } else {
+            assert_matches!(commit.map(|_| ()), Err(MlsError::MlsRulesError(_)));
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn custom_proposal_by_ref_in_external_join() {
+        let mls_rules = CustomMlsRules {
+            path_required_for_custom: true,
+            external_joiner_can_send_custom: true,
+        };
+
+        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+            .await
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let by_ref = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]);
+        let by_ref = alice.propose_custom(by_ref, vec![]).await.unwrap();
+
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
+
+        let (_, commit) = client_with_custom_rules(b"bob", mls_rules)
+            .await
+            .external_commit_builder()
+            .unwrap()
+            .with_received_custom_proposal(by_ref)
+            .build(group_info)
+            .await
+            .unwrap();
+
+        alice.process_incoming_message(commit).await.unwrap();
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn client_with_custom_rules(
+        name: &[u8],
+        mls_rules: CustomMlsRules,
+    ) -> Client<impl MlsConfig> {
+        let (signing_identity, signer) = get_test_signing_identity(TEST_CIPHER_SUITE, name).await;
+
+        ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(BasicWithCustomProvider::new(BasicIdentityProvider::new()))
+            .signing_identity(signing_identity, signer, TEST_CIPHER_SUITE)
+            .custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
+            .mls_rules(mls_rules)
+            .build()
+    }
+
+    #[derive(Debug, Clone)]
+    struct CustomMlsRules {
+        path_required_for_custom: bool,
+        external_joiner_can_send_custom: bool,
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    impl ProposalBundle {
+        fn has_test_custom_proposal(&self) -> bool {
+            self.custom_proposal_types()
+                .any(|t| t == TEST_CUSTOM_PROPOSAL_TYPE)
+        }
+    }
+
+    #[cfg(feature = "custom_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl crate::MlsRules for CustomMlsRules {
+        type Error = MlsError;
+
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &ExtensionList,
+            proposals: &ProposalBundle,
+        ) -> Result<CommitOptions, MlsError> {
+            Ok(CommitOptions::default().with_path_required(
+                !proposals.has_test_custom_proposal() || self.path_required_for_custom,
+            ))
+        }
+
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &ExtensionList,
+        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        async fn filter_proposals(
+            &self,
+            _: CommitDirection,
+            sender: CommitSource,
+            _: &Roster,
+            _: &ExtensionList,
+            proposals: ProposalBundle,
+        ) -> Result<ProposalBundle, MlsError> {
+            let is_external = matches!(sender, CommitSource::NewMember(_));
+            let has_custom = proposals.has_test_custom_proposal();
+            let allowed = !has_custom || !is_external || self.external_joiner_can_send_custom;
+
+            allowed.then_some(proposals).ok_or(MlsError::InvalidSender)
+        }
+    }
 
-        alice_group
+    #[derive(Debug, Clone)]
+    struct AdminOnlyMlsRules {
+        admins: Vec<u32>,
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl crate::MlsRules for AdminOnlyMlsRules {
+        type Error = MlsError;
+
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &ExtensionList,
+            _: &crate::group::proposal_filter::ProposalBundle,
+        ) -> Result<CommitOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &ExtensionList,
+        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        async fn filter_proposals(
+            &self,
+            _: crate::mls_rules::CommitDirection,
+            _: crate::mls_rules::CommitSource,
+            _: &Roster,
+            _: &ExtensionList,
+            proposals: crate::group::proposal_filter::ProposalBundle,
+        ) -> Result<crate::group::proposal_filter::ProposalBundle, MlsError> {
+            Ok(proposals)
+        }
+
+        fn authorize_committer(
+            &self,
+            _: crate::mls_rules::CommitDirection,
+            committer: &crate::mls_rules::CommitSource,
+            _: &Roster,
+            _: &ExtensionList,
+        ) -> Result<bool, MlsError> {
+            Ok(matches!(
+                committer,
+                crate::mls_rules::CommitSource::ExistingMember(member)
+                    if self.admins.contains(&member.index)
+            ))
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_from_unauthorized_committer_is_rejected() {
+        let mut alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .mls_rules(AdminOnlyMlsRules { admins: vec![0] })
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        let (bob, bob_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let commit_output = alice
             .commit_builder()
-            .set_group_context_ext(
-                vec![RequiredCapabilitiesExt {
-                    extensions: vec![17.into()],
-                    ..Default::default()
-                }
-                .into_extension()
-                .unwrap()]
-                .try_into()
-                .unwrap(),
-            )
+            .add_member(bob_key_package)
             .unwrap()
             .build()
             .await
             .unwrap();
 
-        alice_group.process_pending_commit().await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        let test_key_package =
-            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        let (mut bob, _) = bob
+            .join_group(None, &commit_output.welcome_messages[0])
+            .await
+            .unwrap();
 
-        let test_key_package = MlsMessage::new(
-            TEST_PROTOCOL_VERSION,
-            MlsMessagePayload::KeyPackage(test_key_package),
-        );
+        // Bob is not an admin, so Alice rejects his commit and stays in the same epoch
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+        let res = alice.process_incoming_message(commit).await.map(|_| ());
 
-        alice_group
-            .commit_builder()
-            .add_member(test_key_package.clone())
-            .unwrap()
-            .set_group_context_ext(Default::default())
-            .unwrap()
+        assert_matches!(res, Err(MlsError::UnauthorizedCommitter));
+        assert_eq!(alice.current_epoch(), 1);
+
+        // Alice is an admin, so she can still commit and Bob accepts it
+        bob.clear_pending_commit();
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_incoming_message(commit).await.unwrap();
+
+        assert_eq!(alice.current_epoch(), 2);
+        assert_eq!(bob.current_epoch(), 2);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn unauthorized_member_cannot_create_commit() {
+        let mut alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .mls_rules(AdminOnlyMlsRules { admins: vec![] })
             .build()
+            .create_group(Default::default(), Default::default())
             .await
             .unwrap();
 
-        let CommitEffect::NewEpoch(new_epoch) =
-            alice_group.process_pending_commit().await.unwrap().effect
+        let res = alice.commit(vec![]).await.map(|_| ());
+
+        assert_matches!(res, Err(MlsError::UnauthorizedCommitter));
+        assert_eq!(alice.current_epoch(), 0);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_can_receive_commit_from_self() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let commit = group.commit(vec![]).await.unwrap();
+
+        let update = group
+            .process_incoming_message(commit.commit_message)
+            .await
+            .unwrap();
+
+        let ReceivedMessage::Commit(update) = update else {
+            panic!("expected commit message")
+        };
+
+        assert_eq!(update.committer, *group.private_tree.self_index);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_process_commit_when_pending_commit() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        groups[1].commit(vec![]).await.unwrap();
+
+        groups[1].process_incoming_message(commit).await.unwrap();
+
+        let res = groups[1].apply_pending_commit().await;
+        assert_matches!(res, Err(MlsError::PendingCommitNotFound));
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_process_own_plaintext_proposal() {
+        can_process_own_proposal(false).await;
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn can_process_own_ciphertext_proposal() {
+        can_process_own_proposal(true).await;
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn can_process_own_proposal(encrypt_proposal: bool) {
+        let (alice, _) = test_client_with_key_pkg_custom(
+            TEST_PROTOCOL_VERSION,
+            TEST_CIPHER_SUITE,
+            "alice",
+            Default::default(),
+            Default::default(),
+            |c| c.0.mls_rules.encryption_options.encrypt_control_messages = encrypt_proposal,
+        )
+        .await;
+
+        let mut alice = TestGroup {
+            group: alice
+                .create_group(Default::default(), Default::default())
+                .await
+                .unwrap(),
+        };
+
+        let mut bob = alice.join("bob").await.0;
+        let mut alice = alice;
+
+        let upd = alice.propose_update(vec![]).await.unwrap();
+        alice.process_incoming_message(upd.clone()).await.unwrap();
+
+        bob.process_incoming_message(upd).await.unwrap();
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+        let update = alice.process_incoming_message(commit).await.unwrap();
+
+        let ReceivedMessage::Commit(CommitMessageDescription {
+            effect: CommitEffect::NewEpoch(new_epoch),
+            ..
+        }) = update
         else {
             panic!("unexpected commit effect")
         };
 
-        assert_eq!(new_epoch.applied_proposals.len(), 2);
+        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        assert_eq!(new_epoch.applied_proposals[0].sender, Sender::Member(0));
+    }
 
-        assert_matches!(
-            new_epoch.applied_proposals[0],
-            ProposalInfo { proposal: Proposal::Add(ref add), .. }
-                if add.key_package == test_key_package.into_key_package().unwrap()
-        );
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_clears_proposals() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        assert_eq!(alice_group.roster().members_iter().count(), 2);
+        groups[0].propose_update(vec![]).await.unwrap();
+
+        assert_eq!(groups[0].state.proposals.proposals.len(), 1);
+        assert_eq!(groups[0].state.proposals.own_proposals.len(), 1);
+
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+        groups[0].process_message(commit).await.unwrap();
+
+        assert!(groups[0].state.proposals.proposals.is_empty());
+        assert!(groups[0].state.proposals.own_proposals.is_empty());
     }
 
+    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_wrong_source() {
-        // RFC, 13.4.2. "The leaf_node_source field MUST be set to commit."
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+    async fn received_proposal_is_pending_until_committed() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.leaf_node_source = LeafNodeSource::Update;
-            Some(sk.clone())
+        assert!(groups[1].pending_proposals().is_empty());
+
+        let proposal = groups[0].propose_update(vec![]).await.unwrap();
+        let received = groups[1].process_message(proposal).await.unwrap();
+
+        let ReceivedMessage::Proposal(received) = received else {
+            panic!("expected proposal message");
         };
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let pending = groups[1].pending_proposals();
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, received.proposal_ref);
+        assert_eq!(pending[0].1, received.proposal);
+        assert_eq!(pending[0].2, Sender::Member(0));
+
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+        groups[1].apply_pending_commit().await.unwrap();
+        groups[0].process_message(commit).await.unwrap();
+
+        assert!(groups[0].pending_proposals().is_empty());
+        assert!(groups[1].pending_proposals().is_empty());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn uncommitted_proposal_is_dropped_on_new_epoch() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+
+        // Member 2 receives a proposal that member 1 never sees, so member 1's
+        // commit does not reference it.
+        let proposal = groups[0].propose_update(vec![]).await.unwrap();
+        groups[2].process_message(proposal).await.unwrap();
+
+        assert_eq!(groups[2].pending_proposals().len(), 1);
+
+        let epoch = groups[2].current_epoch();
+
+        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
+        groups[2].process_message(commit).await.unwrap();
+
+        assert_eq!(groups[2].current_epoch(), epoch + 1);
+        assert!(groups[2].pending_proposals().is_empty());
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_required_is_true_when_proposals_pending() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        assert!(!group.commit_required());
+
+        group
+            .propose_group_context_extensions(ExtensionList::new(), vec![])
+            .await
+            .unwrap();
+
+        assert!(group.commit_required());
+
+        let res = group.encrypt_application_message(&[0u8; 32], vec![]).await;
+
+        assert_matches!(res, Err(MlsError::CommitRequired));
+
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+
+        assert!(!group.commit_required());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_exceeding_max_group_size_is_not_created() {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.max_group_size(2)
+        })
+        .await;
+
+        let (_, bob_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let (_, carol_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        let res = groups[2]
-            .process_message(commit_output.commit_message)
+        let res = alice
+            .commit_builder()
+            .add_member(bob_kp)
+            .unwrap()
+            .add_member(carol_kp)
+            .unwrap()
+            .build()
             .await;
 
-        assert_matches!(res, Err(MlsError::InvalidLeafNodeSource));
+        assert_matches!(res, Err(MlsError::GroupSizeLimitExceeded(2)));
+        assert!(alice.pending_commit.is_none());
+        assert_eq!(alice.current_epoch(), 0);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_same_hpke_key() {
-        // RFC 13.4.2. "Verify that the encryption_key value in the LeafNode is different from the committer's current leaf node"
-
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
-
-        // Group 0 starts using fixed key
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
+    async fn commit_exceeding_max_group_size_is_rejected_atomically() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
-        groups[0].process_pending_commit().await.unwrap();
-        groups[2]
-            .process_message(commit_output.commit_message)
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", false, |c| c.0.settings.max_group_size = Some(3))
             .await
             .unwrap();
 
-        // Group 0 tries to use the fixed key againd
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let (_, carol_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        let res = groups[2]
-            .process_message(commit_output.commit_message)
-            .await;
+        let (_, dave_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "dave").await;
 
-        assert_matches!(res, Err(MlsError::SameHpkeKey(0)));
-    }
+        let commit = alice
+            .commit_builder()
+            .add_member(carol_kp)
+            .unwrap()
+            .add_member(dave_kp)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_duplicate_hpke_key() {
-        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `encryption_key`"
+        let epoch = bob.current_epoch();
+        let res = bob.process_incoming_message(commit).await;
 
-        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
-            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
-        {
-            return;
-        }
+        assert_matches!(res, Err(MlsError::GroupSizeLimitExceeded(3)));
+        assert_eq!(bob.current_epoch(), epoch);
+        assert_eq!(bob.roster().members().len(), 2);
+    }
 
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn added_member_fills_leaf_freed_by_removal() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 4).await;
 
-        // Group 1 uses the fixed key
-        groups[1].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
+        let commit = groups[0]
+            .commit_builder()
+            .remove_member(1)
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
 
-        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+        groups[0].apply_pending_commit().await.unwrap();
+        groups.remove(1);
 
-        process_commit(&mut groups, commit_output.commit_message, 1).await;
+        for group in groups.iter_mut().skip(1) {
+            group.process_message(commit.clone()).await.unwrap();
+        }
 
-        // Group 0 tries to use the fixed key too
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
+        let leaf_count = groups[0].state.public_tree.total_leaf_count();
+        let (erin, commit) = groups[0].join("erin").await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        for group in groups.iter_mut().skip(1) {
+            group.process_message(commit.clone()).await.unwrap();
+        }
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        assert_eq!(erin.current_member_index(), 1);
+        assert_eq!(erin.state.public_tree.total_leaf_count(), leaf_count);
 
-        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+        for group in &groups {
+            assert_eq!(group.state.public_tree.total_leaf_count(), leaf_count);
+            assert_eq!(group.context().tree_hash, erin.context().tree_hash);
+        }
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_duplicate_signature_key() {
-        // RFC 8.3 "Verify that the following fields are unique among the members of the group: `signature_key`"
+    async fn current_epoch_ignores_pending_commit() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
 
-        if TEST_CIPHER_SUITE != CipherSuite::CURVE25519_AES128
-            && TEST_CIPHER_SUITE != CipherSuite::CURVE25519_CHACHA
-        {
-            return;
-        }
+        assert_eq!(alice.group_id(), TEST_GROUP);
+        assert_eq!(bob.group_id(), TEST_GROUP);
 
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+        for epoch in 2..4 {
+            let commit = alice.commit(Vec::new()).await.unwrap().commit_message;
+            assert_eq!(alice.current_epoch(), epoch - 1);
 
-        // Group 1 uses the fixed key
-        groups[1].commit_modifiers.modify_leaf = |leaf, _| {
-            let sk = hex!(
-                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
-            )
-            .into();
+            alice.apply_pending_commit().await.unwrap();
+            bob.process_message(commit).await.unwrap();
 
-            leaf.signing_identity.signature_key =
-                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+            assert_eq!(alice.current_epoch(), epoch);
+            assert_eq!(bob.current_epoch(), epoch);
+        }
+    }
 
-            Some(sk)
-        };
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn welcome_can_be_recreated_for_member_added_in_current_epoch() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        groups[0].config.0.mls_rules.commit_options.path_required = true;
 
-        let commit_output = groups.get_mut(1).unwrap().commit(vec![]).await.unwrap();
+        let (dave, dave_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "dave").await;
 
-        process_commit(&mut groups, commit_output.commit_message, 1).await;
+        // The welcome message produced by the commit is never delivered to dave
+        let commit = groups[0]
+            .commit_builder()
+            .add_member(dave_kp.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
 
-        // Group 0 tries to use the fixed key too
-        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
-            let sk = hex!(
-                "3468b4c890255c983e3d5cbf5cb64c1ef7f6433a518f2f3151d6672f839a06ebcad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b"
-            )
-            .into();
+        groups[0].apply_pending_commit().await.unwrap();
+        process_commit(&mut groups, commit, 0).await;
 
-            leaf.signing_identity.signature_key =
-                hex!("cad4fc381fe61822af45135c82921a348e6f46643d66ddefc70483565433714b").into();
+        let welcome = groups[0].encrypt_group_secrets_for(dave_kp).await.unwrap();
 
-            Some(sk)
-        };
+        let (mut dave_group, _) = dave
+            .join_group(Some(groups[0].export_tree()), &welcome)
+            .await
+            .unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        assert_eq!(dave_group.context(), groups[0].context());
+        assert_eq!(dave_group.epoch_secrets, groups[0].epoch_secrets);
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        let commit = dave_group.commit(Vec::new()).await.unwrap().commit_message;
+        dave_group.apply_pending_commit().await.unwrap();
+        process_commit(&mut groups, commit, 3).await;
 
-        assert_matches!(res, Err(MlsError::DuplicateLeafData(_)));
+        assert_eq!(dave_group.epoch_secrets, groups[0].epoch_secrets);
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_incorrect_signature() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
-
-        groups[0].commit_modifiers.modify_leaf = |leaf, _| {
-            leaf.signature[0] ^= 1;
-            None
-        };
+    async fn welcome_cannot_be_recreated_for_non_member() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice.join("bob").await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let (_, carol_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        let res = groups[2]
-            .process_message(commit_output.commit_message)
-            .await;
+        let res = alice.encrypt_group_secrets_for(carol_kp).await;
 
-        assert_matches!(res, Err(MlsError::InvalidSignature));
+        assert_matches!(res, Err(MlsError::MemberNotFound));
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_used_context_extension() {
-        const EXT_TYPE: ExtensionType = ExtensionType::new(999);
+    async fn welcome_cannot_be_recreated_without_own_commit() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
 
-        // The new leaf of the committer doesn't support an extension set in group context
-        let extension = Extension::new(EXT_TYPE, vec![]);
+        let (_, carol_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        let mut groups =
-            get_test_groups_with_features(3, vec![extension].into(), Default::default()).await;
+        let commit = alice
+            .commit_builder()
+            .add_member(carol_kp.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+            .commit_message;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities = get_test_capabilities();
-            Some(sk.clone())
-        };
+        // The secrets are not available while the commit is pending
+        let res = alice.encrypt_group_secrets_for(carol_kp.clone()).await;
+        assert_matches!(res, Err(MlsError::MemberNotFound));
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_message(commit).await.unwrap();
 
-        let res = groups[1]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        // Only the committer knows the secrets
+        let res = bob.encrypt_group_secrets_for(carol_kp.clone()).await;
+        assert_matches!(res, Err(MlsError::WelcomeSecretsNotFound));
 
-        assert_matches!(res, Err(MlsError::UnsupportedGroupExtension(EXT_TYPE)));
+        // The secrets are dropped once the next epoch starts
+        let commit = bob.commit(Vec::new()).await.unwrap().commit_message;
+        bob.apply_pending_commit().await.unwrap();
+        alice.process_message(commit).await.unwrap();
+
+        let res = alice.encrypt_group_secrets_for(carol_kp).await;
+        assert_matches!(res, Err(MlsError::WelcomeSecretsNotFound));
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_required_extension() {
-        // The new leaf of the committer doesn't support an extension required by group context
+    async fn welcome_can_be_recreated_once_for_members_added_by_commit() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        let extension = RequiredCapabilitiesExt {
-            extensions: vec![999.into()],
-            proposals: vec![],
-            credentials: vec![],
-        };
+        let (_, carol_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        let extensions = vec![extension.into_extension().unwrap()];
-        let mut groups =
-            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
+        let (_, dave_kp) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "dave").await;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities = Capabilities::default();
-            Some(sk.clone())
-        };
+        alice
+            .commit_builder()
+            .add_member(carol_kp.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        // A commit without adds does not keep any secrets
+        alice.commit(Vec::new()).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        assert!(res.is_err());
-    }
+        let res = alice.encrypt_group_secrets_for(carol_kp.clone()).await;
+        assert_matches!(res, Err(MlsError::WelcomeSecretsNotFound));
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_has_unsupported_credential() {
-        // The new leaf of the committer has a credential unsupported by another leaf
-        let mut groups =
-            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+        alice
+            .commit_builder()
+            .add_member(dave_kp.clone())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
 
-        for group in groups.iter_mut() {
-            group.config.0.identity_provider.allow_any_custom = true;
-        }
+        alice.apply_pending_commit().await.unwrap();
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.signing_identity.credential = Credential::Custom(CustomCredential::new(
-                CredentialType::new(43),
-                leaf.signing_identity
-                    .credential
-                    .as_basic()
-                    .unwrap()
-                    .identifier
-                    .to_vec(),
-            ));
+        // Carol is a member, but was not added by the last commit
+        let res = alice.encrypt_group_secrets_for(carol_kp).await;
+        assert_matches!(res, Err(MlsError::MemberNotAddedByCommit(1)));
 
-            Some(sk.clone())
-        };
+        alice
+            .encrypt_group_secrets_for(dave_kp.clone())
+            .await
+            .unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        // The secrets are dropped once every added member was welcomed again
+        assert!(alice.welcome_secrets.is_none());
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        let res = alice.encrypt_group_secrets_for(dave_kp).await;
+        assert_matches!(res, Err(MlsError::WelcomeSecretsNotFound));
+    }
 
-        assert_matches!(res, Err(MlsError::CredentialTypeOfNewLeafIsUnsupported));
+    type ObservedTransition = (u64, Vec<u8>, u32);
+
+    #[derive(Clone, Default)]
+    struct TestGroupObserver(Arc<Mutex<Vec<ObservedTransition>>>);
+
+    impl TestGroupObserver {
+        fn lock(&self) -> MutexGuard<'_, Vec<ObservedTransition>> {
+            #[cfg(feature = "std")]
+            return self.0.lock().unwrap();
+
+            #[cfg(not(feature = "std"))]
+            return self.0.lock();
+        }
+    }
+
+    impl GroupObserver for TestGroupObserver {
+        fn epoch_transition(&self, transition: &EpochTransition<'_>) {
+            self.lock().push((
+                transition.epoch,
+                transition.tree_hash.to_vec(),
+                transition.member_count,
+            ));
+        }
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_credential_used_in_another_leaf() {
-        // The new leaf of the committer doesn't support another leaf's credential
+    async fn group_observer_sees_each_applied_commit() {
+        let observer = TestGroupObserver::default();
 
-        let mut groups =
-            get_test_groups_with_features(3, Default::default(), Default::default()).await;
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.group_observer(observer.clone())
+        })
+        .await;
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities.credentials = vec![2.into()];
-            Some(sk.clone())
-        };
+        let (mut bob, _) = alice.join("bob").await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let expected = (1, alice.context().tree_hash.clone(), 2);
+        assert_eq!(*observer.lock(), vec![expected.clone()]);
+
+        let commit = bob.commit(Vec::new()).await.unwrap().commit_message;
+        bob.apply_pending_commit().await.unwrap();
+        alice.process_message(commit.clone()).await.unwrap();
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        // A rejected commit is not reported
+        let res = alice.process_message(commit).await;
+        assert!(res.is_err());
 
-        assert_matches!(res, Err(MlsError::InUseCredentialTypeUnsupportedByNewLeaf));
+        let events = observer.lock().clone();
+        assert_eq!(
+            events,
+            vec![expected, (2, bob.context().tree_hash.clone(), 2)]
+        );
     }
 
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_required_credential() {
-        // The new leaf of the committer doesn't support a credential required by group context
-
-        let extension = RequiredCapabilitiesExt {
-            extensions: vec![],
-            proposals: vec![],
-            credentials: vec![1.into()],
-        };
+    async fn group_observer_ignores_previewed_commit() {
+        let observer = TestGroupObserver::default();
 
-        let extensions = vec![extension.into_extension().unwrap()];
-        let mut groups =
-            get_test_groups_with_features(3, extensions.into(), Default::default()).await;
-
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities.credentials = vec![2.into()];
-            Some(sk.clone())
-        };
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.group_observer(observer.clone())
+        })
+        .await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let (mut bob, _) = alice.join("bob").await;
+        let commit = bob.commit(Vec::new()).await.unwrap().commit_message;
 
-        let res = groups[2]
-            .process_incoming_message(commit_output.commit_message)
-            .await;
+        let preview = alice.preview_commit(&commit).await.unwrap();
+        assert_eq!(observer.lock().len(), 1);
 
-        assert_matches!(res, Err(MlsError::RequiredCredentialNotFound(_)));
+        alice.apply_previewed_commit(preview).await.unwrap();
+        assert_eq!(observer.lock().len(), 2);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn make_x509_external_senders_ext() -> ExternalSendersExt {
-        let (_, ext_sender_pk) = test_cipher_suite_provider(TEST_CIPHER_SUITE)
-            .signature_key_generate()
-            .await
-            .unwrap();
+    #[cfg(feature = "test_exports")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn joiner_computes_same_join_secrets_as_committer() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        assert!(alice.joiner_secret().is_none());
 
-        let ext_sender_id = SigningIdentity {
-            signature_key: ext_sender_pk,
-            credential: Credential::X509(CertificateChain::from(vec![random_bytes(32)])),
-        };
+        let (bob, _) = alice.join("bob").await;
 
-        ExternalSendersExt::new(vec![ext_sender_id])
+        assert!(alice.joiner_secret().is_some());
+        assert_eq!(alice.joiner_secret(), bob.joiner_secret());
+        assert_eq!(alice.welcome_secret(), bob.welcome_secret());
+        assert_ne!(alice.joiner_secret(), alice.welcome_secret());
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_leaf_not_supporting_external_sender_credential_leads_to_rejected_commit() {
-        let ext_senders = make_x509_external_senders_ext()
-            .await
-            .into_extension()
-            .unwrap();
+    async fn exported_tree_hash_matches_group_context() {
+        let groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
 
-        let mut alice = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
-            )
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .build()
-            .create_group(core::iter::once(ext_senders).collect(), Default::default())
+        let tree_hash = groups[1]
+            .export_tree()
+            .tree_hash(&groups[1].cipher_suite_provider)
             .await
             .unwrap();
 
-        // New leaf supports only basic credentials (used by the group) but not X509 used by external sender
-        alice.commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.capabilities.credentials = vec![CredentialType::BASIC];
-            Some(sk.clone())
-        };
+        assert_eq!(tree_hash, groups[0].context().tree_hash());
+    }
 
-        alice.commit(vec![]).await.unwrap();
-        let res = alice.apply_pending_commit().await;
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn resolve_stored_resumption_psk<RR: ResumptionPskResolver>(
+        group: &TestGroup,
+        psk_id: &PreSharedKeyID,
+        resumption_psk_resolver: &RR,
+    ) -> Result<PskSecret, MlsError> {
+        // Resolve as a client outside of the group would, using only stored state
+        PskResolver {
+            group_context: None,
+            current_epoch: None,
+            prior_epochs: Some(&group.config.group_state_storage()),
+            psk_store: &group.config.secret_store(),
+            resumption_psk_resolver,
+        }
+        .resolve_to_secret(core::slice::from_ref(psk_id), &group.cipher_suite_provider)
+        .await
+    }
 
-        assert_matches!(
-            res,
-            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
-        );
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    fn branch_psk_id(group: &TestGroup, psk_epoch: u64) -> PreSharedKeyID {
+        PreSharedKeyID {
+            key_id: JustPreSharedKeyID::Resumption(ResumptionPsk {
+                usage: ResumptionPSKUsage::Branch,
+                psk_group_id: PskGroupId(group.group_id().to_vec()),
+                psk_epoch,
+            }),
+            psk_nonce: crate::psk::PskNonce::random(&group.cipher_suite_provider).unwrap(),
+        }
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn node_not_supporting_external_sender_credential_cannot_join_group() {
-        let ext_senders = make_x509_external_senders_ext()
-            .await
-            .into_extension()
-            .unwrap();
+    async fn resumption_psk_is_resolved_from_stored_epoch() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let psk_id = branch_psk_id(&group, group.current_epoch());
 
-        let mut alice = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
-            )
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .build()
-            .create_group(core::iter::once(ext_senders).collect(), Default::default())
+        let input = PskSecretInput {
+            id: psk_id.clone(),
+            psk: group.epoch_secrets.resumption_secret.clone(),
+        };
+
+        let expected = PskSecret::calculate(&[input], &group.cipher_suite_provider)
             .await
             .unwrap();
 
-        let (_, bob_key_pkg) =
-            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+        group.write_to_storage().await.unwrap();
 
-        let commit = alice
-            .commit_builder()
-            .add_member(bob_key_pkg)
-            .unwrap()
-            .build()
-            .await;
+        let secret = resolve_stored_resumption_psk(&group, &psk_id, &NoResumptionPskResolver)
+            .await
+            .unwrap();
 
-        assert_matches!(
-            commit,
-            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
-        );
+        assert_eq!(secret, expected);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn external_senders_extension_is_rejected_if_member_does_not_support_credential_type() {
-        let mut alice = ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(
-                BasicWithCustomProvider::default().with_credential_type(CredentialType::X509),
-            )
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .build()
-            .create_group(Default::default(), Default::default())
-            .await
-            .unwrap();
-
-        let (_, bob_key_pkg) =
-            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+    async fn resumption_psk_of_evicted_epoch_is_not_found() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let evicted = group.current_epoch();
 
-        alice
-            .commit_builder()
-            .add_member(bob_key_pkg)
-            .unwrap()
-            .build()
-            .await
-            .unwrap();
+        // The default storage retains 3 prior epochs
+        for _ in 0..4 {
+            group.commit(vec![]).await.unwrap();
+            group.apply_pending_commit().await.unwrap();
+        }
 
-        alice.apply_pending_commit().await.unwrap();
-        assert_eq!(alice.roster().members_iter().count(), 2);
+        group.write_to_storage().await.unwrap();
 
-        let ext_senders = make_x509_external_senders_ext()
-            .await
-            .into_extension()
-            .unwrap();
+        let psk_id = branch_psk_id(&group, evicted);
+        let res = resolve_stored_resumption_psk(&group, &psk_id, &NoResumptionPskResolver).await;
+        assert_matches!(res, Err(MlsError::ResumptionEpochNotFound(e)) if e == evicted);
 
-        let res = alice
+        let res = group
             .commit_builder()
-            .set_group_context_ext(core::iter::once(ext_senders).collect())
+            .add_resumption_psk(evicted)
             .unwrap()
             .build()
             .await;
 
-        assert_matches!(
-            res,
-            Err(MlsError::RequiredCredentialNotFound(CredentialType::X509))
-        );
+        assert_matches!(res, Err(MlsError::ResumptionEpochNotFound(e)) if e == evicted);
     }
 
-    /*
-     * Edge case paths
-     */
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    #[derive(Clone, Debug)]
+    struct ArchivedEpochResolver;
+
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl ResumptionPskResolver for ArchivedEpochResolver {
+        type Error = Infallible;
+
+        async fn resumption_secret(
+            &self,
+            group_id: &[u8],
+            epoch: u64,
+            usage: ResumptionPSKUsage,
+        ) -> Result<Option<PreSharedKey>, Self::Error> {
+            // Every branch PSK of the test group is archived, except for epoch 41
+            let found =
+                group_id == TEST_GROUP && epoch != 41 && usage == ResumptionPSKUsage::Branch;
+            Ok(found.then(|| PreSharedKey::new(vec![42; 32])))
+        }
+    }
 
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn committing_degenerate_path_succeeds() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+    async fn resumption_psk_resolver_is_asked_after_stored_epochs() {
+        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
 
-        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
-            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
-            tree.update_node(get_test_25519_key(1u8), 3).unwrap();
-        };
+        // Epochs in the group state storage are not looked up in the resolver
+        let stored_id = branch_psk_id(&group, group.current_epoch());
+        let stored_psk = group.epoch_secrets.resumption_secret.clone();
 
-        groups[0].commit_modifiers.modify_leaf = |leaf, sk| {
-            leaf.public_key = get_test_25519_key(1u8);
-            Some(sk.clone())
-        };
+        group.commit(vec![]).await.unwrap();
+        group.apply_pending_commit().await.unwrap();
+        group.write_to_storage().await.unwrap();
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let archived_id = branch_psk_id(&group, 42);
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        for (psk_id, psk) in [
+            (archived_id, PreSharedKey::new(vec![42; 32])),
+            (stored_id, stored_psk),
+        ] {
+            let input = PskSecretInput {
+                id: psk_id.clone(),
+                psk,
+            };
 
-        assert!(res.is_ok());
+            let expected = PskSecret::calculate(&[input], &group.cipher_suite_provider)
+                .await
+                .unwrap();
+
+            let secret = resolve_stored_resumption_psk(&group, &psk_id, &ArchivedEpochResolver)
+                .await
+                .unwrap();
+
+            assert_eq!(secret, expected);
+        }
+
+        let psk_id = branch_psk_id(&group, 41);
+        let res = resolve_stored_resumption_psk(&group, &psk_id, &ArchivedEpochResolver).await;
+        assert_matches!(res, Err(MlsError::ResumptionEpochNotFound(41)));
     }
 
+    #[cfg(all(feature = "psk", feature = "prior_epoch"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn inserting_key_in_filtered_node_fails() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+    async fn resumption_psk_resolver_is_set_by_client_builder() {
+        let client = TestClientBuilder::new_for_test()
+            .resumption_psk_resolver(ArchivedEpochResolver)
+            .build();
 
-        let commit_output = groups[0]
-            .commit_builder()
-            .remove_member(1)
-            .unwrap()
-            .build()
+        let psk = client
+            .config
+            .resumption_psk_resolver()
+            .resumption_secret(TEST_GROUP, 42, ResumptionPSKUsage::Branch)
             .await
             .unwrap();
 
-        groups[0].process_pending_commit().await.unwrap();
+        assert_eq!(psk, Some(PreSharedKey::new(vec![42; 32])));
+    }
 
-        for group in groups.iter_mut().skip(2) {
-            group
-                .process_message(commit_output.commit_message.clone())
-                .await
-                .unwrap();
-        }
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn remaining_members_agree_after_removing_many_in_one_commit() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 12).await;
 
-        groups[0].commit_modifiers.modify_tree = |tree: &mut TreeKemPublic| {
-            tree.update_node(get_test_25519_key(1u8), 1).unwrap();
-        };
+        let mut builder = groups[0].commit_builder();
 
-        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
-            let mut path = path;
-            let mut node = path[0].clone();
-            node.public_key = get_test_25519_key(1u8);
-            path.insert(0, node);
-            path
-        };
+        for index in 1..9 {
+            builder = builder.remove_member(index).unwrap();
+        }
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        let commit = builder.build().await.unwrap().commit_message;
+        groups[0].apply_pending_commit().await.unwrap();
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        for group in &mut groups[9..] {
+            group.process_message(commit.clone()).await.unwrap();
+        }
 
-        // We should get a path validation error, since the path is too long
-        assert_matches!(res, Err(MlsError::WrongPathLen));
-    }
+        let remaining = [&groups[0], &groups[9], &groups[10], &groups[11]];
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_with_too_short_path_fails() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 10).await;
+        let expected_indexes = [0, 9, 10, 11];
+        let indexes = groups[0]
+            .roster()
+            .members_iter()
+            .map(|member| member.index)
+            .collect::<Vec<_>>();
 
-        let commit_output = groups[0]
-            .commit_builder()
-            .remove_member(1)
-            .unwrap()
-            .build()
-            .await
-            .unwrap();
+        assert_eq!(indexes, expected_indexes);
 
-        groups[0].process_pending_commit().await.unwrap();
+        for group in remaining {
+            assert_eq!(group.state, groups[0].state);
 
-        for group in groups.iter_mut().skip(2) {
-            group
-                .process_message(commit_output.commit_message.clone())
-                .await
-                .unwrap();
+            assert_eq!(
+                group.epoch_authenticator().unwrap(),
+                groups[0].epoch_authenticator().unwrap()
+            );
         }
+    }
 
-        groups[0].commit_modifiers.modify_path = |path: Vec<UpdatePathNode>| {
-            let mut path = path;
-            path.pop();
-            path
-        };
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn commit_with_diverging_transcript_is_rejected() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        let commit_output = groups[0].commit(vec![]).await.unwrap();
+        // The commit is signed correctly, but its confirmation tag is computed over a
+        // confirmed transcript hash that does not chain from the receiver's transcript.
+        groups[0].state.interim_transcript_hash = vec![0xab; 32].into();
 
-        let res = groups[7]
-            .process_message(commit_output.commit_message)
-            .await;
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        let res = groups[1].process_message(commit).await.map(|_| ());
 
-        assert!(res.is_err());
+        assert_matches!(res, Err(MlsError::InvalidConfirmationTag));
+        assert_eq!(groups[1].current_epoch(), 1);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn update_proposal_can_change_credential() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
-        let (identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"member").await;
-
-        let update = groups[0]
-            .propose_update_with_identity(secret_key, identity.clone(), vec![])
-            .await
-            .unwrap();
-
-        groups[1].process_message(update).await.unwrap();
-        let commit_output = groups[1].commit(vec![]).await.unwrap();
+    async fn group_context_bytes_match_signed_group_info() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
 
-        // Check that the credential was updated by in the committer's state.
-        groups[1].process_pending_commit().await.unwrap();
-        let new_member = groups[1].roster().member_with_index(0).unwrap();
+        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        groups[0].apply_pending_commit().await.unwrap();
+        groups[1].process_message(commit).await.unwrap();
 
-        assert_eq!(
-            new_member.signing_identity.credential,
-            get_test_basic_credential(b"member".to_vec())
-        );
+        let bytes = groups[1].context().to_bytes().unwrap();
 
+        assert_eq!(bytes, groups[0].context().to_bytes().unwrap());
         assert_eq!(
-            new_member.signing_identity.signature_key,
-            identity.signature_key
+            &GroupContext::from_bytes(&bytes).unwrap(),
+            groups[1].context()
         );
 
-        // Check that the credential was updated in the updater's state.
-        groups[0]
-            .process_message(commit_output.commit_message)
+        let group_info = groups[0]
+            .group_info_message(true)
             .await
+            .unwrap()
+            .into_group_info()
             .unwrap();
-        let new_member = groups[0].roster().member_with_index(0).unwrap();
 
-        assert_eq!(
-            new_member.signing_identity.credential,
-            get_test_basic_credential(b"member".to_vec())
-        );
+        let signer = groups[0].current_member_signing_identity().unwrap().clone();
+
+        group_info
+            .verify(&groups[0].cipher_suite_provider, &signer.signature_key, &())
+            .await
+            .unwrap();
 
+        assert!(group_info
+            .signable_content(&())
+            .unwrap()
+            .starts_with(&bytes));
         assert_eq!(
-            new_member.signing_identity.signature_key,
-            identity.signature_key
+            group_info.confirmation_tag,
+            groups[1].state.confirmation_tag
         );
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn receiving_commit_with_old_adds_fails() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+    async fn common_capabilities_intersect_members() {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.extension_types([100.into(), 101.into(), 102.into()])
+        })
+        .await;
 
-        let key_package =
-            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "foobar").await;
+        let (bob, _) = alice
+            .join_with_custom_config("bob", true, |c| {
+                c.0.settings.extension_types = vec![101.into(), 102.into(), 103.into()]
+            })
+            .await
+            .unwrap();
 
-        let proposal = groups[0].propose_add(key_package, vec![]).await.unwrap();
+        let capabilities = alice.common_capabilities();
 
-        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
+        assert_eq!(capabilities.extensions, vec![101.into(), 102.into()]);
+        assert_eq!(capabilities, bob.common_capabilities());
 
-        // 10 years from now
-        let future_time = MlsTime::now().seconds_since_epoch() + 10 * 365 * 24 * 3600;
+        let own = alice
+            .current_user_leaf_node()
+            .unwrap()
+            .ungreased_capabilities();
 
-        let future_time =
-            MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(future_time));
+        assert_eq!(capabilities.protocol_versions, own.protocol_versions);
+        assert_eq!(capabilities.cipher_suites, own.cipher_suites);
+        assert_eq!(capabilities.credentials, own.credentials);
 
-        groups[1].process_incoming_message(proposal).await.unwrap();
-        let res = groups[1]
-            .process_incoming_message_with_time(commit, future_time)
-            .await;
+        let (carol, _) = alice
+            .join_with_custom_config("carol", true, |c| {
+                c.0.settings.extension_types = vec![102.into()]
+            })
+            .await
+            .unwrap();
 
-        assert_matches!(res, Err(MlsError::InvalidLifetime));
+        assert_eq!(alice.common_capabilities().extensions, vec![102.into()]);
+
+        // Removing Carol restores the intersection of Alice and Bob
+        alice
+            .commit_builder()
+            .remove_member(carol.current_member_index())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+
+        assert_eq!(alice.common_capabilities(), capabilities);
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn custom_proposal_setup() -> (TestGroup, TestGroup) {
+    async fn restart(group: &TestGroup) -> TestGroup {
+        let client = crate::client::Client::new(
+            group.config.clone(),
+            None,
+            None,
+            Default::default(),
+            TEST_PROTOCOL_VERSION,
+        );
+        let group = client.load_group(group.group_id()).await.unwrap();
+
+        TestGroup { group }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn group_is_persisted_on_epoch_change() {
         let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
-            b.custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
+            b.persist_on_epoch_change(true)
         })
         .await;
 
-        let (bob, _) = alice
-            .join_with_custom_config("bob", true, |c| {
-                c.0.settings
-                    .custom_proposal_types
-                    .push(TEST_CUSTOM_PROPOSAL_TYPE)
+        let restarted = restart(&alice).await;
+        assert_eq!(restarted.current_epoch(), 0);
+
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", false, |c| {
+                c.0.settings.persist_on_epoch_change = true
             })
             .await
             .unwrap();
 
-        (alice, bob)
+        for _ in 0..3 {
+            let restarted = restart(&alice).await;
+            assert_eq!(restarted.current_epoch(), alice.current_epoch());
+
+            // Bob restarts at the latest epoch before processing each commit
+            bob = restart(&bob).await;
+            assert_eq!(bob.current_epoch(), alice.current_epoch());
+
+            let commit = alice.commit(vec![]).await.unwrap().commit_message;
+            alice.apply_pending_commit().await.unwrap();
+            bob.process_message(commit).await.unwrap();
+        }
+
+        let restarted = restart(&bob).await;
+        assert_eq!(restarted.current_epoch(), 4);
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_value() {
-        let (mut alice, mut bob) = custom_proposal_setup().await;
+    async fn commit_is_reprocessed_after_restart_before_persisting() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
 
-        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+        bob.write_to_storage().await.unwrap();
 
-        let commit = alice
-            .commit_builder()
-            .custom_proposal(custom_proposal.clone())
-            .build()
-            .await
-            .unwrap()
-            .commit_message;
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
 
-        let ReceivedMessage::Commit(CommitMessageDescription {
-            effect: CommitEffect::NewEpoch(new_epoch),
-            ..
-        }) = bob.process_incoming_message(commit).await.unwrap()
-        else {
-            panic!("unexpected commit effect");
-        };
+        // Bob stops after applying the commit but before persisting it
+        bob.process_message(commit.clone()).await.unwrap();
 
-        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        let mut bob = restart(&bob).await;
+        assert_eq!(bob.current_epoch(), 1);
 
-        assert_eq!(
-            new_epoch.applied_proposals[0].proposal,
-            Proposal::Custom(custom_proposal)
-        );
+        bob.process_message(commit).await.unwrap();
+        assert_eq!(bob.current_epoch(), alice.current_epoch());
     }
 
-    #[cfg(feature = "custom_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_reference() {
-        let (mut alice, mut bob) = custom_proposal_setup().await;
+    #[derive(Debug)]
+    struct StorageUnavailable;
 
-        let custom_proposal = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![0, 1, 2]);
+    impl mls_rs_core::error::IntoAnyError for StorageUnavailable {}
 
-        let proposal = alice
-            .propose_custom(custom_proposal.clone(), vec![])
-            .await
-            .unwrap();
+    #[derive(Clone, Default)]
+    struct UnreliableGroupStateStorage {
+        inner: crate::storage_provider::in_memory::InMemoryGroupStateStorage,
+        fail_writes: Arc<Mutex<bool>>,
+    }
 
-        let recv_prop = bob.process_incoming_message(proposal).await.unwrap();
+    impl UnreliableGroupStateStorage {
+        fn fail_writes(&self) -> MutexGuard<'_, bool> {
+            #[cfg(feature = "std")]
+            return self.fail_writes.lock().unwrap();
 
-        assert_matches!(recv_prop, ReceivedMessage::Proposal(ProposalMessageDescription { proposal: Proposal::Custom(c), ..})
-            if c == custom_proposal);
+            #[cfg(not(feature = "std"))]
+            return self.fail_writes.lock();
+        }
+    }
 
-        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl mls_rs_core::group::GroupStateStorage for UnreliableGroupStateStorage {
+        type Error = StorageUnavailable;
 
-        let ReceivedMessage::Commit(CommitMessageDescription {
-            effect: CommitEffect::NewEpoch(new_epoch),
-            ..
-        }) = bob.process_incoming_message(commit).await.unwrap()
-        else {
-            panic!("unexpected commit effect");
-        };
+        async fn state(&self, group_id: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+            self.inner.state(group_id).await.map_err(|e| match e {})
+        }
 
-        assert_eq!(new_epoch.applied_proposals.len(), 1);
+        async fn epoch(
+            &self,
+            group_id: &[u8],
+            epoch_id: u64,
+        ) -> Result<Option<Vec<u8>>, Self::Error> {
+            self.inner
+                .epoch(group_id, epoch_id)
+                .await
+                .map_err(|e| match e {})
+        }
 
-        assert_eq!(
-            new_epoch.applied_proposals[0].proposal,
-            Proposal::Custom(custom_proposal)
-        );
+        async fn write(
+            &mut self,
+            state: mls_rs_core::group::GroupState,
+            epoch_inserts: Vec<mls_rs_core::group::EpochRecord>,
+            epoch_updates: Vec<mls_rs_core::group::EpochRecord>,
+        ) -> Result<(), Self::Error> {
+            if *self.fail_writes() {
+                return Err(StorageUnavailable);
+            }
+
+            self.inner
+                .write(state, epoch_inserts, epoch_updates)
+                .await
+                .map_err(|e| match e {})
+        }
+
+        async fn max_epoch_id(&self, group_id: &[u8]) -> Result<Option<u64>, Self::Error> {
+            self.inner
+                .max_epoch_id(group_id)
+                .await
+                .map_err(|e| match e {})
+        }
     }
 
-    #[cfg(feature = "psk")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_join_with_psk() {
-        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    async fn group_stays_at_prior_epoch_if_persisting_fails() {
+        let alice_storage = UnreliableGroupStateStorage::default();
 
-        let (bob, key_pkg) =
-            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+        let mut alice = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
+            .await
+            .group_state_storage(alice_storage.clone())
+            .persist_on_epoch_change(true)
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
 
-        let psk_id = ExternalPskId::new(vec![0]);
-        let psk = PreSharedKey::from(vec![0]);
+        let bob_storage = UnreliableGroupStateStorage::default();
 
-        alice
-            .config
-            .secret_store()
-            .insert(psk_id.clone(), psk.clone());
+        let bob_client = TestClientBuilder::new_for_test()
+            .with_random_signing_identity("bob", TEST_CIPHER_SUITE)
+            .await
+            .group_state_storage(bob_storage.clone())
+            .persist_on_epoch_change(true)
+            .build();
 
-        bob.config.secret_store().insert(psk_id.clone(), psk);
+        let key_package = bob_client
+            .generate_key_package_message(Default::default(), Default::default())
+            .await
+            .unwrap();
 
         let commit = alice
             .commit_builder()
-            .add_member(key_pkg)
-            .unwrap()
-            .add_external_psk(psk_id)
+            .add_member(key_package)
             .unwrap()
             .build()
             .await
             .unwrap();
 
-        bob.join_group(None, &commit.welcome_messages[0])
+        alice.apply_pending_commit().await.unwrap();
+
+        let mut bob = bob_client
+            .join_group(None, &commit.welcome_messages[0])
             .await
-            .unwrap();
+            .unwrap()
+            .0;
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+
+        *alice_storage.fail_writes() = true;
+        let res = alice.apply_pending_commit().await;
+        assert_matches!(res, Err(MlsError::GroupStorageError(_)));
+        assert_eq!(alice.current_epoch(), 1);
+        assert!(alice.has_pending_commit());
+
+        *bob_storage.fail_writes() = true;
+        let res = bob.process_incoming_message(commit.clone()).await;
+        assert_matches!(res, Err(MlsError::GroupStorageError(_)));
+        assert_eq!(bob.current_epoch(), 1);
+
+        // Once storage is available again, the same commit moves the groups to the next epoch
+        *alice_storage.fail_writes() = false;
+        alice.apply_pending_commit().await.unwrap();
+        assert_eq!(alice.current_epoch(), 2);
+
+        *bob_storage.fail_writes() = false;
+        bob.process_incoming_message(commit).await.unwrap();
+        assert_eq!(bob.current_epoch(), 2);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn invalid_update_does_not_prevent_other_updates() {
-        const EXTENSION_TYPE: ExtensionType = ExtensionType::new(33);
+    #[derive(Debug, Clone)]
+    struct NoBasicCredentialMlsRules;
 
-        let group_extensions = ExtensionList::from(vec![RequiredCapabilitiesExt {
-            extensions: vec![EXTENSION_TYPE],
-            ..Default::default()
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+    impl crate::MlsRules for NoBasicCredentialMlsRules {
+        type Error = MlsError;
+
+        fn commit_options(
+            &self,
+            _: &Roster,
+            _: &ExtensionList,
+            _: &crate::group::proposal_filter::ProposalBundle,
+        ) -> Result<CommitOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        fn encryption_options(
+            &self,
+            _: &Roster,
+            _: &ExtensionList,
+        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
+            Ok(Default::default())
+        }
+
+        async fn filter_proposals(
+            &self,
+            _: crate::mls_rules::CommitDirection,
+            _: crate::mls_rules::CommitSource,
+            _: &Roster,
+            _: &ExtensionList,
+            proposals: crate::group::proposal_filter::ProposalBundle,
+        ) -> Result<crate::group::proposal_filter::ProposalBundle, MlsError> {
+            Ok(proposals)
+        }
+
+        fn authorize_leaf_node(
+            &self,
+            _: crate::mls_rules::CommitDirection,
+            leaf_node: &LeafNode,
+            _: &Roster,
+            _: &ExtensionList,
+        ) -> Result<bool, MlsError> {
+            Ok(leaf_node.signing_identity.credential.credential_type() != CredentialType::BASIC)
         }
-        .into_extension()
-        .unwrap()]);
+    }
 
-        // Alice creates a group requiring support for an extension
-        let mut alice = TestClientBuilder::new_for_test()
-            .with_random_signing_identity("alice", TEST_CIPHER_SUITE)
-            .await
-            .extension_type(EXTENSION_TYPE)
-            .build()
-            .create_group(group_extensions.clone(), Default::default())
-            .await
-            .unwrap();
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn custom_credential_client_builder(name: &str) -> TestClientBuilder {
+        let (mut identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, name.as_bytes()).await;
 
-        let (bob_signing_identity, bob_secret_key) =
-            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+        identity.credential = Credential::Custom(CustomCredential::new(
+            CredentialType::new(
+                crate::identity::test_utils::BasicWithCustomProvider::CUSTOM_CREDENTIAL_TYPE,
+            ),
+            name.as_bytes().to_vec(),
+        ));
 
-        let bob_client = TestClientBuilder::new_for_test()
-            .signing_identity(
-                bob_signing_identity.clone(),
-                bob_secret_key.clone(),
-                TEST_CIPHER_SUITE,
-            )
-            .extension_type(EXTENSION_TYPE)
-            .build();
+        TestClientBuilder::new_for_test().signing_identity(identity, secret_key, TEST_CIPHER_SUITE)
+    }
 
-        let carol_client = TestClientBuilder::new_for_test()
-            .with_random_signing_identity("carol", TEST_CIPHER_SUITE)
+    // Alice uses a basic credential and the default rules. Bob uses a custom credential and
+    // rejects new leaf nodes with basic credentials.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn group_with_no_basic_credential_member() -> (
+        TestGroup,
+        Group<WithMlsRules<NoBasicCredentialMlsRules, TestClientConfig>>,
+    ) {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+        let bob = custom_credential_client_builder("bob")
             .await
-            .extension_type(EXTENSION_TYPE)
+            .mls_rules(NoBasicCredentialMlsRules)
             .build();
 
-        let dave_client = TestClientBuilder::new_for_test()
-            .with_random_signing_identity("dave", TEST_CIPHER_SUITE)
+        let bob_key_package = bob
+            .generate_key_package_message(Default::default(), Default::default())
             .await
-            .extension_type(EXTENSION_TYPE)
-            .build();
+            .unwrap();
 
-        // Alice adds Bob, Carol and Dave to the group. They all support the mandatory extension.
-        let commit = alice
+        let welcome = alice
             .commit_builder()
-            .add_member(
-                bob_client
-                    .generate_key_package_message(Default::default(), Default::default())
-                    .await
-                    .unwrap(),
-            )
-            .unwrap()
-            .add_member(
-                carol_client
-                    .generate_key_package_message(Default::default(), Default::default())
-                    .await
-                    .unwrap(),
-            )
-            .unwrap()
-            .add_member(
-                dave_client
-                    .generate_key_package_message(Default::default(), Default::default())
-                    .await
-                    .unwrap(),
-            )
+            .add_member(bob_key_package)
             .unwrap()
             .build()
             .await
-            .unwrap();
+            .unwrap()
+            .welcome_messages;
 
         alice.apply_pending_commit().await.unwrap();
 
-        let mut bob = bob_client
-            .join_group(None, &commit.welcome_messages[0])
+        let (bob, _) = bob.join_group(None, &welcome[0]).await.unwrap();
+
+        (alice, bob)
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn unauthorized_leaf_node_cannot_be_added() {
+        let mut alice = custom_credential_client_builder("alice")
+            .await
+            .mls_rules(NoBasicCredentialMlsRules)
+            .build()
+            .create_group(Default::default(), Default::default())
             .await
+            .unwrap();
+
+        let (_, bob_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let res = alice
+            .commit_builder()
+            .add_member(bob_key_package)
             .unwrap()
-            .0;
+            .build()
+            .await
+            .map(|_| ());
 
-        bob.write_to_storage().await.unwrap();
+        assert_matches!(res, Err(MlsError::UnauthorizedLeafNode));
 
-        // Bob reloads his group data, but with parameters that will cause his generated leaves to
-        // not support the mandatory extension.
-        let mut bob = TestClientBuilder::new_for_test()
-            .signing_identity(bob_signing_identity, bob_secret_key, TEST_CIPHER_SUITE)
-            .key_package_repo(bob.config.key_package_repo())
-            .group_state_storage(bob.config.group_state_storage())
+        let carol_key_package = custom_credential_client_builder("carol")
+            .await
             .build()
-            .load_group(alice.group_id())
+            .generate_key_package_message(Default::default(), Default::default())
             .await
             .unwrap();
 
-        let mut carol = carol_client
-            .join_group(None, &commit.welcome_messages[0])
-            .await
+        alice
+            .commit_builder()
+            .add_member(carol_key_package)
             .unwrap()
-            .0;
+            .build()
+            .await
+            .unwrap();
+    }
 
-        let mut dave = dave_client
-            .join_group(None, &commit.welcome_messages[0])
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn received_commit_adding_unauthorized_leaf_node_is_rejected() {
+        let (mut alice, mut bob) = group_with_no_basic_credential_member().await;
+
+        let (_, carol_key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
+
+        let commit = alice
+            .commit_builder()
+            .add_member(carol_key_package)
+            .unwrap()
+            .build()
             .await
             .unwrap()
-            .0;
-
-        // Bob's updated leaf does not support the mandatory extension.
-        let bob_update = bob.propose_update(Vec::new()).await.unwrap();
-        let carol_update = carol.propose_update(Vec::new()).await.unwrap();
-        let dave_update = dave.propose_update(Vec::new()).await.unwrap();
+            .commit_message;
 
-        // Alice receives the update proposals to be committed.
-        alice.process_incoming_message(bob_update).await.unwrap();
-        alice.process_incoming_message(carol_update).await.unwrap();
-        alice.process_incoming_message(dave_update).await.unwrap();
+        let res = bob.process_incoming_message(commit).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::UnauthorizedLeafNode));
+        assert_eq!(bob.current_epoch(), 1);
+    }
 
-        // Alice commits the update proposals.
-        alice.commit(Vec::new()).await.unwrap();
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn received_update_path_with_unauthorized_leaf_node_is_rejected() {
+        let (mut alice, mut bob) = group_with_no_basic_credential_member().await;
 
-        let CommitEffect::NewEpoch(new_epoch) = alice.apply_pending_commit().await.unwrap().effect
-        else {
-            panic!("unexpected commit effect");
-        };
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
 
-        let find_update_for = |id: &str| {
-            new_epoch
-                .applied_proposals
-                .iter()
-                .filter_map(|p| match p.proposal {
-                    Proposal::Update(ref u) => u.signing_identity().credential.as_basic(),
-                    _ => None,
-                })
-                .any(|c| c.identifier == id.as_bytes())
-        };
+        let res = bob.process_incoming_message(commit).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::UnauthorizedLeafNode));
+        assert_eq!(bob.current_epoch(), 1);
+    }
 
-        // Carol's and Dave's updates should be part of the commit.
-        assert!(find_update_for("carol"));
-        assert!(find_update_for("dave"));
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn external_commit_with_unauthorized_leaf_node_is_rejected() {
+        let mut alice = custom_credential_client_builder("alice")
+            .await
+            .mls_rules(NoBasicCredentialMlsRules)
+            .build()
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
 
-        // Bob's update should be rejected.
-        assert!(!find_update_for("bob"));
+        let group_info = alice
+            .group_info_message_allowing_ext_commit(true)
+            .await
+            .unwrap();
 
-        // Check that all members are still in the group.
-        let all_members_are_in = alice
-            .roster()
-            .members_iter()
-            .zip(["alice", "bob", "carol", "dave"])
-            .all(|(member, id)| {
-                member
-                    .signing_identity
-                    .credential
-                    .as_basic()
-                    .unwrap()
-                    .identifier
-                    == id.as_bytes()
-            });
+        let (bob_identity, secret_key) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
 
-        assert!(all_members_are_in);
-    }
+        let (_, commit) = TestClientBuilder::new_for_test()
+            .signing_identity(bob_identity, secret_key, TEST_CIPHER_SUITE)
+            .build()
+            .external_commit_builder()
+            .unwrap()
+            .build(group_info)
+            .await
+            .unwrap();
 
-    #[cfg(feature = "custom_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_may_enforce_path() {
-        test_custom_proposal_mls_rules(true).await;
+        let res = alice.process_incoming_message(commit).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::UnauthorizedLeafNode));
+        assert_eq!(alice.current_epoch(), 0);
     }
 
-    #[cfg(feature = "custom_proposal")]
+    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_need_not_enforce_path() {
-        test_custom_proposal_mls_rules(false).await;
-    }
-
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn test_custom_proposal_mls_rules(path_required_for_custom: bool) {
-        let mls_rules = CustomMlsRules {
-            path_required_for_custom,
-            external_joiner_can_send_custom: true,
-        };
-
-        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
+    async fn update_proposal_with_unauthorized_leaf_node_is_filtered() {
+        let mut alice = custom_credential_client_builder("alice")
             .await
+            .mls_rules(NoBasicCredentialMlsRules)
+            .build()
             .create_group(Default::default(), Default::default())
             .await
             .unwrap();
 
-        let alice_pub_before = alice.current_user_leaf_node().unwrap().public_key.clone();
+        let bob = custom_credential_client_builder("bob").await.build();
 
-        let kp = client_with_custom_rules(b"bob", mls_rules)
-            .await
+        let bob_key_package = bob
             .generate_key_package_message(Default::default(), Default::default())
             .await
             .unwrap();
 
-        alice
+        let welcome = alice
             .commit_builder()
-            .custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
-            .add_member(kp)
+            .add_member(bob_key_package)
             .unwrap()
             .build()
             .await
-            .unwrap();
+            .unwrap()
+            .welcome_messages;
 
         alice.apply_pending_commit().await.unwrap();
 
-        let alice_pub_after = &alice.current_user_leaf_node().unwrap().public_key;
+        let (mut bob, _) = bob.join_group(None, &welcome[0]).await.unwrap();
 
-        if path_required_for_custom {
-            assert_ne!(alice_pub_after, &alice_pub_before);
-        } else {
-            assert_eq!(alice_pub_after, &alice_pub_before);
-        }
-    }
+        // Bob replaces his custom credential with a basic one for the same identity
+        let (basic_identity, secret_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
 
-    #[cfg(feature = "custom_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_value_in_external_join_may_be_allowed() {
-        test_custom_proposal_by_value_in_external_join(true).await
+        let proposal = bob
+            .propose_update_with_identity(secret_key, basic_identity, vec![])
+            .await
+            .unwrap();
+
+        alice.process_incoming_message(proposal).await.unwrap();
+
+        let commit = alice.commit(vec![]).await.unwrap();
+
+        assert_eq!(commit.unused_proposals.len(), 1);
     }
 
-    #[cfg(feature = "custom_proposal")]
+    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_value_in_external_join_may_not_be_allowed() {
-        test_custom_proposal_by_value_in_external_join(false).await
-    }
+    async fn commit_order_does_not_depend_on_proposal_arrival() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn test_custom_proposal_by_value_in_external_join(external_joiner_can_send_custom: bool) {
-        let mls_rules = CustomMlsRules {
-            path_required_for_custom: true,
-            external_joiner_can_send_custom,
-        };
+        let mut proposals = vec![];
 
-        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
-            .await
-            .create_group(Default::default(), Default::default())
-            .await
-            .unwrap();
+        for name in ["carol", "dave", "erin"] {
+            let key_package =
+                test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, name).await;
 
-        let group_info = alice
-            .group_info_message_allowing_ext_commit(true)
-            .await
-            .unwrap();
+            proposals.push(bob.propose_add(key_package, vec![]).await.unwrap());
+        }
+
+        let mut outputs = vec![];
+
+        for order in [[0, 1, 2], [2, 0, 1], [1, 2, 0]] {
+            let mut committer = alice.clone();
+
+            for i in order {
+                committer
+                    .process_incoming_message(proposals[i].clone())
+                    .await
+                    .unwrap();
+            }
 
-        let commit = client_with_custom_rules(b"bob", mls_rules)
-            .await
-            .external_commit_builder()
-            .unwrap()
-            .with_custom_proposal(CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]))
-            .build(group_info)
-            .await;
+            let output = committer.commit(vec![]).await.unwrap();
+            committer.apply_pending_commit().await.unwrap();
 
-        if external_joiner_can_send_custom {
-            let commit = commit.unwrap().1;
-            alice.process_incoming_message(commit).await.unwrap();
-        } else {
-            assert_matches!(commit.map(|_| ()), Err(MlsError::MlsRulesError(_)));
+            let welcome = output.welcome_messages[0].clone().into_welcome().unwrap();
+
+            let new_members = welcome
+                .secrets
+                .into_iter()
+                .map(|secrets| secrets.new_member)
+                .collect::<Vec<_>>();
+
+            outputs.push((committer.roster().members(), new_members));
         }
+
+        assert_eq!(outputs[0], outputs[1]);
+        assert_eq!(outputs[0], outputs[2]);
     }
 
-    #[cfg(feature = "custom_proposal")]
+    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn custom_proposal_by_ref_in_external_join() {
-        let mls_rules = CustomMlsRules {
-            path_required_for_custom: true,
-            external_joiner_can_send_custom: true,
-        };
+    async fn cleared_proposals_cannot_be_committed_by_reference() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
 
-        let mut alice = client_with_custom_rules(b"alice", mls_rules.clone())
-            .await
-            .create_group(Default::default(), Default::default())
-            .await
-            .unwrap();
+        let key_package =
+            test_key_package_message(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "carol").await;
 
-        let by_ref = CustomProposal::new(TEST_CUSTOM_PROPOSAL_TYPE, vec![]);
-        let by_ref = alice.propose_custom(by_ref, vec![]).await.unwrap();
+        let proposal = bob.propose_add(key_package, vec![]).await.unwrap();
+        alice.process_incoming_message(proposal).await.unwrap();
 
-        let group_info = alice
-            .group_info_message_allowing_ext_commit(true)
-            .await
-            .unwrap();
+        alice.clear_proposal_cache();
 
-        let (_, commit) = client_with_custom_rules(b"bob", mls_rules)
-            .await
-            .external_commit_builder()
-            .unwrap()
-            .with_received_custom_proposal(by_ref)
-            .build(group_info)
-            .await
-            .unwrap();
+        // The cleared proposal is not committed
+        let mut committer = alice.clone();
+        committer.commit(vec![]).await.unwrap();
+        committer.apply_pending_commit().await.unwrap();
+        assert_eq!(committer.roster().members().len(), 2);
 
-        alice.process_incoming_message(commit).await.unwrap();
+        // A commit referencing the cleared proposal is rejected
+        let commit = bob.commit(vec![]).await.unwrap().commit_message;
+        let res = alice.process_incoming_message(commit).await.map(|_| ());
+
+        assert_matches!(res, Err(MlsError::ProposalNotFound));
     }
 
-    #[cfg(feature = "custom_proposal")]
-    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn client_with_custom_rules(
-        name: &[u8],
-        mls_rules: CustomMlsRules,
-    ) -> Client<impl MlsConfig> {
-        let (signing_identity, signer) = get_test_signing_identity(TEST_CIPHER_SUITE, name).await;
+    #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "std", derive(thiserror::Error))]
+    #[cfg_attr(feature = "std", error("credential expired"))]
+    struct ExpiredCredentialError;
 
-        ClientBuilder::new()
-            .crypto_provider(TestCryptoProvider::new())
-            .identity_provider(BasicWithCustomProvider::new(BasicIdentityProvider::new()))
-            .signing_identity(signing_identity, signer, TEST_CIPHER_SUITE)
-            .custom_proposal_type(TEST_CUSTOM_PROPOSAL_TYPE)
-            .mls_rules(mls_rules)
-            .build()
+    impl IntoAnyError for ExpiredCredentialError {
+        #[cfg(feature = "std")]
+        fn into_dyn_error(self) -> Result<Box<dyn std::error::Error + Send + Sync>, Self> {
+            Ok(self.into())
+        }
     }
 
-    #[derive(Debug, Clone)]
-    struct CustomMlsRules {
-        path_required_for_custom: bool,
-        external_joiner_can_send_custom: bool,
-    }
+    /// Accepts basic and custom credentials. The data of a custom credential
+    /// starts with its expiration time in seconds since the epoch.
+    #[derive(Clone, Debug, Default)]
+    struct ExpiringCredentialProvider(BasicWithCustomProvider);
 
-    #[cfg(feature = "custom_proposal")]
-    impl ProposalBundle {
-        fn has_test_custom_proposal(&self) -> bool {
-            self.custom_proposal_types()
-                .any(|t| t == TEST_CUSTOM_PROPOSAL_TYPE)
-        }
+    fn expiration_seconds(signing_identity: &SigningIdentity) -> Option<u64> {
+        signing_identity
+            .credential
+            .as_custom()
+            .map(|c| u64::from_be_bytes(c.data[..8].try_into().unwrap()))
     }
 
-    #[cfg(feature = "custom_proposal")]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     #[cfg_attr(mls_build_async, maybe_async::must_be_async)]
-    impl crate::MlsRules for CustomMlsRules {
-        type Error = MlsError;
+    impl IdentityProvider for ExpiringCredentialProvider {
+        type Error = ExpiredCredentialError;
 
-        fn commit_options(
+        async fn validate_member(
             &self,
-            _: &Roster,
-            _: &ExtensionList,
-            proposals: &ProposalBundle,
-        ) -> Result<CommitOptions, MlsError> {
-            Ok(CommitOptions::default().with_path_required(
-                !proposals.has_test_custom_proposal() || self.path_required_for_custom,
-            ))
+            signing_identity: &SigningIdentity,
+            timestamp: Option<MlsTime>,
+            _extensions: Option<&ExtensionList>,
+        ) -> Result<(), Self::Error> {
+            match (expiration_seconds(signing_identity), timestamp) {
+                (Some(expiration), Some(time)) if time.seconds_since_epoch() > expiration => {
+                    Err(ExpiredCredentialError)
+                }
+                _ => Ok(()),
+            }
         }
 
-        fn encryption_options(
+        async fn expiration(
             &self,
-            _: &Roster,
-            _: &ExtensionList,
-        ) -> Result<crate::mls_rules::EncryptionOptions, MlsError> {
-            Ok(Default::default())
+            signing_identity: &SigningIdentity,
+        ) -> Result<Option<MlsTime>, Self::Error> {
+            Ok(expiration_seconds(signing_identity).map(|seconds| {
+                MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(seconds))
+            }))
         }
 
-        async fn filter_proposals(
+        async fn validate_external_sender(
             &self,
-            _: CommitDirection,
-            sender: CommitSource,
-            _: &Roster,
-            _: &ExtensionList,
-            proposals: ProposalBundle,
-        ) -> Result<ProposalBundle, MlsError> {
-            let is_external = matches!(sender, CommitSource::NewMember(_));
-            let has_custom = proposals.has_test_custom_proposal();
-            let allowed = !has_custom || !is_external || self.external_joiner_can_send_custom;
+            signing_identity: &SigningIdentity,
+            timestamp: Option<MlsTime>,
+            extensions: Option<&ExtensionList>,
+        ) -> Result<(), Self::Error> {
+            self.validate_member(signing_identity, timestamp, extensions)
+                .await
+        }
 
-            allowed.then_some(proposals).ok_or(MlsError::InvalidSender)
+        async fn identity(
+            &self,
+            signing_identity: &SigningIdentity,
+            extensions: &ExtensionList,
+        ) -> Result<Vec<u8>, Self::Error> {
+            self.0
+                .identity(signing_identity, extensions)
+                .await
+                .map_err(|_| ExpiredCredentialError)
+        }
+
+        async fn valid_successor(
+            &self,
+            _predecessor: &SigningIdentity,
+            _successor: &SigningIdentity,
+            _extensions: &ExtensionList,
+        ) -> Result<bool, Self::Error> {
+            Ok(true)
+        }
+
+        fn supported_types(&self) -> Vec<CredentialType> {
+            self.0.supported_types()
         }
     }
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn group_can_receive_commit_from_self() {
-        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn client_with_expiration(
+        name: &[u8],
+        expiration: Option<u64>,
+    ) -> crate::client::Client<impl MlsConfig> {
+        let (mut signing_identity, signer) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, name).await;
+
+        if let Some(expiration) = expiration {
+            signing_identity.credential = Credential::Custom(CustomCredential::new(
+                CredentialType::new(BasicWithCustomProvider::CUSTOM_CREDENTIAL_TYPE),
+                [&expiration.to_be_bytes(), name].concat(),
+            ));
+        }
 
-        let commit = group.commit(vec![]).await.unwrap();
+        ClientBuilder::new()
+            .crypto_provider(TestCryptoProvider::new())
+            .identity_provider(ExpiringCredentialProvider::default())
+            .signing_identity(signing_identity, signer, TEST_CIPHER_SUITE)
+            .build()
+    }
 
-        let update = group
-            .process_incoming_message(commit.commit_message)
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn propose_remove_expired_removes_only_expired_members() {
+        const NOW: u64 = 4_500_000_000;
+
+        let mut alice = client_with_expiration(b"alice", Some(NOW - 1))
+            .await
+            .create_group(Default::default(), Default::default())
             .await
             .unwrap();
 
-        let ReceivedMessage::Commit(update) = update else {
-            panic!("expected commit message")
-        };
+        let members = [
+            (b"bob".as_slice(), Some(NOW - 1)),
+            (b"carol", Some(NOW + 1)),
+            (b"dave", None),
+            (b"erin", Some(NOW - 100)),
+        ];
 
-        assert_eq!(update.committer, *group.private_tree.self_index);
-    }
+        let mut commit = alice.commit_builder();
 
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_process_commit_when_pending_commit() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
+        for (name, expiration) in members {
+            let key_package = client_with_expiration(name, expiration)
+                .await
+                .generate_key_package_message(Default::default(), Default::default())
+                .await
+                .unwrap();
 
-        let commit = groups[0].commit(vec![]).await.unwrap().commit_message;
-        groups[1].commit(vec![]).await.unwrap();
+            commit = commit.add_member(key_package).unwrap();
+        }
 
-        groups[1].process_incoming_message(commit).await.unwrap();
+        commit.build().await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        let res = groups[1].apply_pending_commit().await;
-        assert_matches!(res, Err(MlsError::PendingCommitNotFound));
-    }
+        let now = MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(NOW));
+        let proposals = alice.propose_remove_expired(now).await.unwrap();
 
-    #[cfg(feature = "by_ref_proposal")]
-    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_process_own_plaintext_proposal() {
-        can_process_own_proposal(false).await;
+        // Bob and Erin are expired. Alice is expired too, but is the current member.
+        let expected = [1, 4].map(|i| {
+            Proposal::Remove(RemoveProposal {
+                to_remove: LeafIndex(i),
+            })
+        });
+
+        assert_eq!(proposals, expected);
+
+        alice
+            .commit_builder()
+            .raw_proposals(proposals)
+            .build()
+            .await
+            .unwrap();
+
+        alice.apply_pending_commit().await.unwrap();
+        assert_eq!(alice.roster().members().len(), 3);
     }
 
-    #[cfg(feature = "by_ref_proposal")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn can_process_own_ciphertext_proposal() {
-        can_process_own_proposal(true).await;
+    async fn propose_remove_expired_skips_basic_credentials() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice.join("bob").await;
+
+        let now = MlsTime::from_duration_since_epoch(core::time::Duration::from_secs(u64::MAX));
+        let proposals = alice.propose_remove_expired(now).await.unwrap();
+
+        assert!(proposals.is_empty());
     }
 
-    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(all(feature = "prior_epoch", feature = "private_message"))]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
-    async fn can_process_own_proposal(encrypt_proposal: bool) {
-        let (alice, _) = test_client_with_key_pkg_custom(
-            TEST_PROTOCOL_VERSION,
-            TEST_CIPHER_SUITE,
-            "alice",
-            Default::default(),
-            Default::default(),
-            |c| c.0.mls_rules.encryption_options.encrypt_control_messages = encrypt_proposal,
-        )
+    async fn process_stale_commit(
+        lookback: u64,
+        epochs_behind: u64,
+        encrypt: bool,
+    ) -> Result<ReceivedMessage, MlsError> {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.commit_lookback(lookback)
+        })
         .await;
 
-        let mut alice = TestGroup {
-            group: alice
-                .create_group(Default::default(), Default::default())
-                .await
-                .unwrap(),
-        };
+        let (mut bob, _) = alice
+            .join_with_custom_config("bob", false, |c| {
+                c.0.mls_rules = DefaultMlsRules::default()
+                    .with_encryption_options(EncryptionOptions::new(encrypt, PaddingMode::None))
+            })
+            .await
+            .unwrap();
 
-        let mut bob = alice.join("bob").await.0;
-        let mut alice = alice;
+        let stale_commit = bob.commit(vec![]).await.unwrap().commit_message;
 
-        let upd = alice.propose_update(vec![]).await.unwrap();
-        alice.process_incoming_message(upd.clone()).await.unwrap();
+        for _ in 0..epochs_behind {
+            alice.commit(vec![]).await.unwrap();
+            alice.apply_pending_commit().await.unwrap();
+        }
 
-        bob.process_incoming_message(upd).await.unwrap();
-        let commit = bob.commit(vec![]).await.unwrap().commit_message;
-        let update = alice.process_incoming_message(commit).await.unwrap();
+        let epoch = alice.current_epoch();
+        let res = alice.process_incoming_message(stale_commit).await;
 
-        let ReceivedMessage::Commit(CommitMessageDescription {
-            effect: CommitEffect::NewEpoch(new_epoch),
-            ..
-        }) = update
-        else {
-            panic!("unexpected commit effect")
-        };
+        assert_eq!(alice.current_epoch(), epoch);
 
-        assert_eq!(new_epoch.applied_proposals.len(), 1);
-        assert_eq!(new_epoch.applied_proposals[0].sender, Sender::Member(0));
+        res
     }
 
-    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(all(feature = "prior_epoch", feature = "private_message"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_clears_proposals() {
-        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 2).await;
-
-        groups[0].propose_update(vec![]).await.unwrap();
-
-        assert_eq!(groups[0].state.proposals.proposals.len(), 1);
-        assert_eq!(groups[0].state.proposals.own_proposals.len(), 1);
-
-        let commit = groups[1].commit(vec![]).await.unwrap().commit_message;
-        groups[0].process_message(commit).await.unwrap();
-
-        assert!(groups[0].state.proposals.proposals.is_empty());
-        assert!(groups[0].state.proposals.own_proposals.is_empty());
+    async fn stale_commit_within_lookback_is_validated_but_not_applied() {
+        for encrypt in [false, true] {
+            let res = process_stale_commit(2, 2, encrypt).await.map(|_| ());
+            assert_matches!(res, Err(MlsError::PastEpochMessage(_)));
+        }
     }
 
-    #[cfg(feature = "by_ref_proposal")]
+    #[cfg(all(feature = "prior_epoch", feature = "private_message"))]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
-    async fn commit_required_is_true_when_proposals_pending() {
-        let mut group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+    async fn stale_commit_outside_lookback_is_rejected() {
+        let res = process_stale_commit(0, 1, false).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::InvalidEpoch));
 
-        assert!(!group.commit_required());
+        let res = process_stale_commit(1, 2, false).await.map(|_| ());
+        assert_matches!(res, Err(MlsError::InvalidEpoch));
+    }
 
-        group
-            .propose_group_context_extensions(ExtensionList::new(), vec![])
-            .await
-            .unwrap();
+    #[cfg(feature = "prior_epoch")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn forged_stale_commit_within_lookback_fails_validation() {
+        let mut alice = test_group_custom_config(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, |b| {
+            b.commit_lookback(1)
+        })
+        .await;
 
-        assert!(group.commit_required());
+        let (mut bob, _) = alice.join("bob").await;
+        let mut stale_commit = bob.commit(vec![]).await.unwrap().commit_message;
 
-        let res = group.encrypt_application_message(&[0u8; 32], vec![]).await;
+        alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
 
-        assert_matches!(res, Err(MlsError::CommitRequired));
+        let MlsMessagePayload::Plain(ref mut plaintext) = stale_commit.payload else {
+            panic!("expected a public message");
+        };
 
-        group.commit(vec![]).await.unwrap();
-        group.apply_pending_commit().await.unwrap();
+        plaintext.content.authenticated_data = b"forged".to_vec();
 
-        assert!(!group.commit_required());
+        let res = alice
+            .process_incoming_message(stale_commit)
+            .await
+            .map(|_| ());
+        assert_matches!(res, Err(MlsError::InvalidSignature));
     }
 }
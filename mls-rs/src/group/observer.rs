@@ -0,0 +1,29 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+/// Summary of a group's state after it moved to a new epoch.
+///
+/// This contains only public information about the group and is safe to log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpochTransition<'a> {
+    /// Identifier of the group.
+    pub group_id: &'a [u8],
+    /// Epoch the group moved to.
+    pub epoch: u64,
+    /// Tree hash of the new epoch.
+    pub tree_hash: &'a [u8],
+    /// Number of members in the new epoch.
+    pub member_count: u32,
+}
+
+/// Observer of the state changes of all groups of a client.
+///
+/// An observer can be set with
+/// [`ClientBuilder::group_observer`](crate::client_builder::ClientBuilder::group_observer).
+/// When no observer is set, no summary is computed.
+pub trait GroupObserver: Send + Sync {
+    /// Called after a commit, sent or received, was applied to a group. This is not called
+    /// for commits that are rejected or that remove the local member.
+    fn epoch_transition(&self, transition: &EpochTransition<'_>);
+}
@@ -420,6 +420,12 @@ impl MlsDecode for Proposal {
 }
 
 impl Proposal {
+    /// Type of the proposal, as encoded on the wire.
+    ///
+    /// The [raw value](ProposalType::raw_value) is the code registered in
+    /// [RFC 9420 Section 17.4](https://www.rfc-editor.org/rfc/rfc9420.html#section-17.4),
+    /// for example `1` for add and `3` for remove, or the type chosen by the
+    /// application for custom proposals.
     pub fn proposal_type(&self) -> ProposalType {
         match self {
             Proposal::Add(_) => ProposalType::ADD,
@@ -582,3 +588,117 @@ impl From<ProposalRef> for ProposalOrRef {
         Self::Reference(r)
     }
 }
+
+#[cfg(all(test, feature = "custom_proposal"))]
+mod tests {
+    use alloc::{boxed::Box, vec};
+    use assert_matches::assert_matches;
+    use mls_rs_codec::{MlsDecode, MlsEncode};
+
+    use crate::{
+        client::test_utils::{test_client_with_key_pkg, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        tree_kem::node::LeafIndex,
+    };
+
+    #[cfg(feature = "psk")]
+    use crate::{
+        group::test_utils::test_group,
+        psk::{ExternalPskId, JustPreSharedKeyID},
+    };
+
+    #[cfg(feature = "by_ref_proposal")]
+    use crate::tree_kem::leaf_node::test_utils::get_basic_test_node;
+
+    #[cfg(feature = "by_ref_proposal")]
+    use super::UpdateProposal;
+
+    use super::{
+        AddProposal, CustomProposal, ExternalInit, Proposal, ProposalType, ReInitProposal,
+        RemoveProposal,
+    };
+
+    #[test]
+    fn custom_proposal_round_trips() {
+        let proposal = Proposal::Custom(CustomProposal::new(
+            ProposalType::new(0xf0f0),
+            b"custom data".to_vec(),
+        ));
+
+        let encoded = proposal.mls_encode_to_vec().unwrap();
+        assert_eq!(&encoded[..2], &[0xf0, 0xf0]);
+
+        let decoded = Proposal::mls_decode(&mut &*encoded).unwrap();
+        assert_eq!(decoded, proposal);
+        assert_eq!(decoded.mls_encode_to_vec().unwrap(), encoded);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn proposal_type_of_each_variant() {
+        let (_, key_package) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        let add = Proposal::Add(Box::new(AddProposal::from(
+            key_package.into_key_package().unwrap(),
+        )));
+
+        let remove = Proposal::Remove(RemoveProposal {
+            to_remove: LeafIndex(1),
+        });
+
+        let reinit = Proposal::ReInit(ReInitProposal {
+            group_id: vec![1],
+            version: TEST_PROTOCOL_VERSION,
+            cipher_suite: TEST_CIPHER_SUITE,
+            extensions: Default::default(),
+        });
+
+        let external_init = Proposal::ExternalInit(ExternalInit {
+            kem_output: vec![2],
+        });
+
+        let custom = Proposal::Custom(CustomProposal::new(ProposalType::new(0xf0f0), vec![]));
+
+        let mut cases = vec![
+            (add, 1),
+            (remove, 3),
+            (reinit, 5),
+            (external_init, 6),
+            (Proposal::GroupContextExtensions(Default::default()), 7),
+            (custom, 0xf0f0),
+        ];
+
+        #[cfg(feature = "by_ref_proposal")]
+        {
+            let leaf_node = get_basic_test_node(TEST_CIPHER_SUITE, "foo").await;
+            cases.push((Proposal::Update(UpdateProposal { leaf_node }), 2));
+        }
+
+        #[cfg(feature = "psk")]
+        {
+            let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+
+            let psk = group
+                .psk_proposal(JustPreSharedKeyID::External(ExternalPskId::new(vec![3])))
+                .unwrap();
+
+            cases.push((psk, 4));
+        }
+
+        for (proposal, code) in cases {
+            assert_eq!(proposal.proposal_type().raw_value(), code);
+
+            let encoded = proposal.mls_encode_to_vec().unwrap();
+            assert_eq!(encoded[..2], code.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn custom_proposal_cannot_use_defined_type() {
+        let proposal = Proposal::Custom(CustomProposal::new(ProposalType::ADD, vec![]));
+
+        assert_matches!(
+            proposal.mls_encode_to_vec(),
+            Err(mls_rs_codec::Error::Custom(_))
+        );
+    }
+}
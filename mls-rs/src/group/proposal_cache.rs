@@ -147,13 +147,34 @@ impl ProposalCache {
         Ok(())
     }
 
+    /// Cached proposals ordered by reference.
+    ///
+    /// Commits built from the cache must not depend on the iteration order
+    /// of the underlying map, which is randomized with `std`. Otherwise the
+    /// same set of proposals could add members at different leaf indices.
+    pub fn sorted_proposals(&self) -> Vec<(&ProposalRef, &CachedProposal)> {
+        #[cfg(feature = "std")]
+        let mut proposals = self.proposals.iter().collect::<Vec<_>>();
+
+        // Without `std` the map is a vector of pairs
+        #[cfg(not(feature = "std"))]
+        let mut proposals = self
+            .proposals
+            .iter()
+            .map(|(r, p)| (r, p))
+            .collect::<Vec<_>>();
+
+        proposals.sort_by_key(|(r, _)| *r);
+        proposals
+    }
+
     pub fn prepare_commit(
         &self,
         sender: Sender,
         additional_proposals: Vec<Proposal>,
     ) -> ProposalBundle {
-        self.proposals
-            .iter()
+        self.sorted_proposals()
+            .into_iter()
             .map(|(r, p)| {
                 (
                     p.proposal.clone(),
@@ -286,6 +307,14 @@ impl GroupState {
             )),
         }?;
 
+        let authorized = user_rules
+            .authorize_committer(direction, &origin, &roster, group_extensions)
+            .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
+
+        if !authorized {
+            return Err(MlsError::UnauthorizedCommitter);
+        }
+
         proposals = user_rules
             .filter_proposals(direction, origin, &roster, group_extensions, proposals)
             .await
@@ -299,6 +328,8 @@ impl GroupState {
             external_leaf,
             identity_provider,
             psk_storage,
+            user_rules,
+            direction,
             #[cfg(feature = "by_ref_proposal")]
             &self.context.group_id,
         );
@@ -326,7 +357,7 @@ impl GroupState {
         let unused_proposals = unused_proposals(
             match direction {
                 CommitDirection::Send => all_proposals,
-                CommitDirection::Receive => self.proposals.proposals.iter().collect(),
+                CommitDirection::Receive => self.proposals.sorted_proposals().into_iter().collect(),
             },
             &applier_output.applied_proposals,
         );
@@ -733,7 +764,7 @@ mod tests {
     use core::convert::Infallible;
     use itertools::Itertools;
     use mls_rs_core::crypto::{CipherSuite, CipherSuiteProvider};
-    use mls_rs_core::extension::ExtensionList;
+    use mls_rs_core::extension::{ExtensionList, ExtensionType};
     use mls_rs_core::group::{Capabilities, ProposalType};
     use mls_rs_core::identity::IdentityProvider;
     use mls_rs_core::protocol_version::ProtocolVersion;
@@ -790,13 +821,27 @@ mod tests {
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn update_leaf_node(name: &str, leaf_index: u32) -> LeafNode {
+        update_leaf_node_with_capabilities(name, leaf_index, get_test_capabilities()).await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn update_leaf_node_with_capabilities(
+        name: &str,
+        leaf_index: u32,
+        capabilities: Capabilities,
+    ) -> LeafNode {
         let (mut leaf, _, signer) = get_basic_test_node_sig_key(TEST_CIPHER_SUITE, name).await;
 
+        let properties = ConfigProperties {
+            capabilities,
+            ..default_properties()
+        };
+
         leaf.update(
             &test_cipher_suite_provider(TEST_CIPHER_SUITE),
             TEST_GROUP,
             leaf_index,
-            Some(default_properties()),
+            Some(properties),
             None,
             &signer,
         )
@@ -1344,7 +1389,7 @@ mod tests {
             )
             .await;
 
-        assert_matches!(res, Err(MlsError::DuplicatePskIds));
+        assert_matches!(res, Err(MlsError::DuplicatePskIds(ProposalSource::ByValue)));
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -2227,6 +2272,62 @@ mod tests {
         assert_eq!(processed_proposals.1.unused_proposals, vec![proposal_info]);
     }
 
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn receive_update_with_required_extension(
+        supported_extensions: Vec<ExtensionType>,
+    ) -> Result<ProvisionalState, MlsError> {
+        let (alice, mut tree) = new_tree("alice").await;
+        let bob = add_member(&mut tree, "bob").await;
+
+        let capabilities = Capabilities {
+            extensions: supported_extensions,
+            ..get_test_capabilities()
+        };
+
+        let proposal = Proposal::Update(UpdateProposal {
+            leaf_node: update_leaf_node_with_capabilities("bob", *bob, capabilities).await,
+        });
+
+        let proposal_ref = make_proposal_ref(&proposal, bob).await;
+
+        let required_capabilities = RequiredCapabilitiesExt {
+            extensions: vec![33.into()],
+            ..Default::default()
+        };
+
+        CommitReceiver::new(
+            &tree,
+            alice,
+            alice,
+            test_cipher_suite_provider(TEST_CIPHER_SUITE),
+        )
+        .with_extensions(vec![required_capabilities.into_extension().unwrap()].into())
+        .cache(proposal_ref.clone(), proposal, bob)
+        .receive([proposal_ref])
+        .await
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receiving_update_without_required_extension_fails() {
+        let res = receive_update_with_required_extension(vec![]).await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::RequiredExtensionNotFound(v)) if v == 33.into()
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn receiving_update_with_required_extension_succeeds() {
+        let res = receive_update_with_required_extension(vec![33.into()]).await;
+
+        let state = res.unwrap();
+        assert_eq!(
+            state.applied_proposals.update_proposal_senders(),
+            &[LeafIndex(1)]
+        );
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn receiving_remove_with_invalid_index_fails() {
         let (alice, tree) = new_tree("alice").await;
@@ -2908,7 +3009,7 @@ mod tests {
         .receive([psk_proposal.clone(), psk_proposal])
         .await;
 
-        assert_matches!(res, Err(MlsError::DuplicatePskIds));
+        assert_matches!(res, Err(MlsError::DuplicatePskIds(ProposalSource::ByValue)));
     }
 
     #[cfg(feature = "psk")]
@@ -2922,7 +3023,7 @@ mod tests {
             .send()
             .await;
 
-        assert_matches!(res, Err(MlsError::DuplicatePskIds));
+        assert_matches!(res, Err(MlsError::DuplicatePskIds(ProposalSource::ByValue)));
     }
 
     #[cfg(feature = "psk")]
@@ -2998,7 +3099,9 @@ mod tests {
 
         assert_matches!(
             res,
-            Err(MlsError::MoreThanOneGroupContextExtensionsProposal)
+            Err(MlsError::MoreThanOneGroupContextExtensionsProposal(
+                ProposalSource::ByValue
+            ))
         );
     }
 
@@ -3016,7 +3119,69 @@ mod tests {
 
         assert_matches!(
             res,
-            Err(MlsError::MoreThanOneGroupContextExtensionsProposal)
+            Err(MlsError::MoreThanOneGroupContextExtensionsProposal(
+                ProposalSource::ByValue
+            ))
+        );
+    }
+
+    #[cfg(feature = "by_ref_proposal")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn multiple_group_context_extensions_error_points_at_offending_reference() {
+        let (alice, mut tree) = new_tree("alice").await;
+        let bob = add_member(&mut tree, "bob").await;
+
+        let proposal = Proposal::GroupContextExtensions(ExtensionList::new());
+        let proposal_ref = make_proposal_ref(&proposal, bob).await;
+
+        let res = CommitReceiver::new(
+            &tree,
+            alice,
+            alice,
+            test_cipher_suite_provider(TEST_CIPHER_SUITE),
+        )
+        .cache(proposal_ref.clone(), proposal.clone(), bob)
+        .receive([
+            ProposalOrRef::from(Proposal::Remove(RemoveProposal { to_remove: bob })),
+            ProposalOrRef::from(proposal),
+            ProposalOrRef::from(proposal_ref.clone()),
+        ])
+        .await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::MoreThanOneGroupContextExtensionsProposal(
+                ProposalSource::ByReference(r)
+            )) if r == proposal_ref
+        );
+    }
+
+    #[cfg(all(feature = "by_ref_proposal", feature = "psk"))]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn duplicate_psk_error_points_at_offending_reference() {
+        let (alice, mut tree) = new_tree("alice").await;
+        let bob = add_member(&mut tree, "bob").await;
+
+        let proposal = Proposal::Psk(new_external_psk(b"foo"));
+        let proposal_ref = make_proposal_ref(&proposal, bob).await;
+
+        let res = CommitReceiver::new(
+            &tree,
+            alice,
+            alice,
+            test_cipher_suite_provider(TEST_CIPHER_SUITE),
+        )
+        .cache(proposal_ref.clone(), proposal.clone(), bob)
+        .receive([
+            ProposalOrRef::from(Proposal::Psk(new_external_psk(b"bar"))),
+            ProposalOrRef::from(proposal),
+            ProposalOrRef::from(proposal_ref.clone()),
+        ])
+        .await;
+
+        assert_matches!(
+            res,
+            Err(MlsError::DuplicatePskIds(ProposalSource::ByReference(r))) if r == proposal_ref
         );
     }
 
@@ -223,6 +223,9 @@ impl ProposalBundle {
     }
 
     /// Iterate over all proposals inside the bundle.
+    ///
+    /// Proposals are grouped by type and appear in the order they were added
+    /// within each type. The order does not change between iterations.
     pub fn iter_proposals(&self) -> impl Iterator<Item = ProposalInfo<BorrowedProposal<'_>>> {
         let res = self
             .additions
@@ -435,6 +438,16 @@ impl ProposalBundle {
     }
 }
 
+impl<'a> IntoIterator for &'a ProposalBundle {
+    type Item = ProposalInfo<BorrowedProposal<'a>>;
+    type IntoIter = Box<dyn Iterator<Item = Self::Item> + 'a>;
+
+    /// Same as [`ProposalBundle::iter_proposals`].
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter_proposals())
+    }
+}
+
 impl FromIterator<(Proposal, Sender, ProposalSource)> for ProposalBundle {
     fn from_iter<I>(iter: I) -> Self
     where
@@ -631,3 +644,86 @@ impl_proposable!(
     GROUP_CONTEXT_EXTENSIONS,
     group_context_extensions
 );
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::{
+        group::{
+            proposal::{Proposal, RemoveProposal},
+            Sender,
+        },
+        tree_kem::node::LeafIndex,
+        ExtensionList,
+    };
+
+    #[cfg(feature = "by_ref_proposal")]
+    use crate::group::proposal_ref::ProposalRef;
+
+    use super::{ProposalBundle, ProposalSource};
+
+    fn remove(index: u32) -> Proposal {
+        Proposal::Remove(RemoveProposal {
+            to_remove: LeafIndex(index),
+        })
+    }
+
+    #[test]
+    fn iterating_bundle_yields_source_proposal_and_sender() {
+        let mut bundle = ProposalBundle::default();
+
+        let extensions = Proposal::GroupContextExtensions(ExtensionList::new());
+        bundle.add(
+            extensions.clone(),
+            Sender::Member(0),
+            ProposalSource::ByValue,
+        );
+        bundle.add(remove(1), Sender::Member(0), ProposalSource::Local);
+
+        #[cfg(feature = "by_ref_proposal")]
+        let reference = ProposalRef::new_fake(vec![1, 2, 3]);
+
+        #[cfg(feature = "by_ref_proposal")]
+        bundle.add(
+            remove(2),
+            Sender::External(0),
+            ProposalSource::ByReference(reference.clone()),
+        );
+
+        let iterated = (&bundle)
+            .into_iter()
+            .map(|p| {
+                (
+                    p.source.clone(),
+                    Proposal::from(p.proposal.clone()),
+                    p.sender,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (ProposalSource::Local, remove(1), Sender::Member(0)),
+            #[cfg(feature = "by_ref_proposal")]
+            (
+                ProposalSource::ByReference(reference),
+                remove(2),
+                Sender::External(0),
+            ),
+            (ProposalSource::ByValue, extensions, Sender::Member(0)),
+        ];
+
+        assert_eq!(iterated, expected);
+
+        // The order is stable across iterations
+        let mut proposals = Vec::new();
+
+        for p in &bundle {
+            proposals.push(Proposal::from(p.proposal));
+        }
+
+        let expected_proposals = expected.into_iter().map(|(_, p, _)| p).collect::<Vec<_>>();
+        assert_eq!(proposals, expected_proposals);
+    }
+}
@@ -5,6 +5,7 @@
 use crate::{
     client::MlsError,
     group::{
+        mls_rules::MlsRules,
         proposal::ReInitProposal,
         proposal_filter::{ProposalBundle, ProposalInfo},
         AddProposal, ProposalType, RemoveProposal, Sender, UpdateProposal,
@@ -45,11 +46,12 @@ use {crate::iter::ParallelIteratorExt, rayon::prelude::*};
 #[cfg(mls_build_async)]
 use futures::{StreamExt, TryStreamExt};
 
-impl<'a, C, P, CSP> ProposalApplier<'a, C, P, CSP>
+impl<'a, C, P, CSP, R> ProposalApplier<'a, C, P, CSP, R>
 where
     C: IdentityProvider,
     P: PreSharedKeyStorage,
     CSP: CipherSuiteProvider,
+    R: MlsRules,
 {
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(super) async fn apply_proposals_from_member(
@@ -205,6 +207,9 @@ where
                         .map_err(|e| MlsError::IdentityProviderError(e.into_any_error()))
                         .and_then(|valid| valid.then_some(()).ok_or(MlsError::InvalidSuccessor));
 
+                    let valid_successor = valid_successor
+                        .and_then(|_| self.authorize_leaf_node(leaf, group_extensions_in_use));
+
                     res.and(valid_successor)
                 };
 
@@ -224,7 +229,12 @@ where
             .enumerate()
             .filter_map(|(i, p)| async move {
                 let res = self
-                    .validate_new_node(leaf_node_validator, &p.proposal.key_package, commit_time)
+                    .validate_new_node(
+                        leaf_node_validator,
+                        &p.proposal.key_package,
+                        group_extensions_in_use,
+                        commit_time,
+                    )
                     .await;
 
                 apply_strategy(strategy, p.is_by_reference(), res)
@@ -358,7 +368,9 @@ fn filter_out_extra_group_context_extensions(
             p.is_by_reference(),
             (!core::mem::replace(&mut found, true))
                 .then_some(())
-                .ok_or(MlsError::MoreThanOneGroupContextExtensionsProposal),
+                .ok_or_else(|| {
+                    MlsError::MoreThanOneGroupContextExtensionsProposal(p.source.clone())
+                }),
         )
     })?;
 
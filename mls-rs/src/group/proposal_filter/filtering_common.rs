@@ -4,7 +4,11 @@
 
 use crate::{
     client::MlsError,
-    group::{proposal_filter::ProposalBundle, Sender},
+    group::{
+        mls_rules::{CommitDirection, MlsRules},
+        proposal_filter::ProposalBundle,
+        Sender,
+    },
     key_package::{validate_key_package_properties, KeyPackage},
     protocol_version::ProtocolVersion,
     time::MlsTime,
@@ -48,7 +52,7 @@ use super::filtering::{apply_strategy, filter_out_invalid_proposers, FilterStrat
 use super::filtering::filter_out_unsupported_custom_proposals;
 
 #[derive(Debug)]
-pub(crate) struct ProposalApplier<'a, C, P, CSP> {
+pub(crate) struct ProposalApplier<'a, C, P, CSP, R> {
     pub original_tree: &'a TreeKemPublic,
     pub protocol_version: ProtocolVersion,
     pub cipher_suite_provider: &'a CSP,
@@ -56,6 +60,8 @@ pub(crate) struct ProposalApplier<'a, C, P, CSP> {
     pub external_leaf: Option<&'a LeafNode>,
     pub identity_provider: &'a C,
     pub psk_storage: &'a P,
+    pub mls_rules: &'a R,
+    pub direction: CommitDirection,
     #[cfg(feature = "by_ref_proposal")]
     pub group_id: &'a [u8],
 }
@@ -70,11 +76,12 @@ pub(crate) struct ApplyProposalsOutput {
     pub(crate) new_context_extensions: Option<ExtensionList>,
 }
 
-impl<'a, C, P, CSP> ProposalApplier<'a, C, P, CSP>
+impl<'a, C, P, CSP, R> ProposalApplier<'a, C, P, CSP, R>
 where
     C: IdentityProvider,
     P: PreSharedKeyStorage,
     CSP: CipherSuiteProvider,
+    R: MlsRules,
 {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
@@ -85,6 +92,8 @@ where
         external_leaf: Option<&'a LeafNode>,
         identity_provider: &'a C,
         psk_storage: &'a P,
+        mls_rules: &'a R,
+        direction: CommitDirection,
         #[cfg(feature = "by_ref_proposal")] group_id: &'a [u8],
     ) -> Self {
         Self {
@@ -95,6 +104,8 @@ where
             external_leaf,
             identity_provider,
             psk_storage,
+            mls_rules,
+            direction,
             #[cfg(feature = "by_ref_proposal")]
             group_id,
         }
@@ -320,6 +331,7 @@ where
         &self,
         leaf_node_validator: &LeafNodeValidator<'_, Ip, Cp>,
         key_package: &KeyPackage,
+        group_extensions: &ExtensionList,
         commit_time: Option<MlsTime>,
     ) -> Result<(), MlsError> {
         leaf_node_validator
@@ -331,7 +343,9 @@ where
             self.protocol_version,
             self.cipher_suite_provider,
         )
-        .await
+        .await?;
+
+        self.authorize_leaf_node(&key_package.leaf_node, group_extensions)
     }
 
     #[cfg(all(not(mls_build_async), feature = "rayon"))]
@@ -339,6 +353,7 @@ where
         &self,
         leaf_node_validator: &LeafNodeValidator<'_, Ip, Cp>,
         key_package: &KeyPackage,
+        group_extensions: &ExtensionList,
         commit_time: Option<MlsTime>,
     ) -> Result<(), MlsError> {
         let (a, b) = rayon::join(
@@ -355,7 +370,29 @@ where
             },
         );
         a?;
-        b
+        b?;
+
+        self.authorize_leaf_node(&key_package.leaf_node, group_extensions)
+    }
+
+    pub(super) fn authorize_leaf_node(
+        &self,
+        leaf_node: &LeafNode,
+        group_extensions: &ExtensionList,
+    ) -> Result<(), MlsError> {
+        let authorized = self
+            .mls_rules
+            .authorize_leaf_node(
+                self.direction,
+                leaf_node,
+                &self.original_tree.roster(),
+                group_extensions,
+            )
+            .map_err(|e| MlsError::MlsRulesError(e.into_any_error()))?;
+
+        authorized
+            .then_some(())
+            .ok_or(MlsError::UnauthorizedLeafNode)
     }
 }
 
@@ -425,7 +462,7 @@ where
         } else if !nonce_valid {
             return Err(MlsError::InvalidPskNonceLength);
         } else if !is_new_id {
-            return Err(MlsError::DuplicatePskIds);
+            return Err(MlsError::DuplicatePskIds(p.source.clone()));
         } else if external_id_is_valid.is_err() {
             return external_id_is_valid;
         }
@@ -437,7 +474,7 @@ where
             } else if !nonce_valid {
                 Err(MlsError::InvalidPskNonceLength)
             } else if !is_new_id {
-                Err(MlsError::DuplicatePskIds)
+                Err(MlsError::DuplicatePskIds(p.source.clone()))
             } else {
                 external_id_is_valid
             };
@@ -4,7 +4,7 @@
 
 use crate::{
     client::MlsError,
-    group::proposal_filter::ProposalBundle,
+    group::{mls_rules::MlsRules, proposal_filter::ProposalBundle},
     iter::wrap_iter,
     protocol_version::ProtocolVersion,
     time::MlsTime,
@@ -39,11 +39,12 @@ use crate::group::{
 #[cfg(all(feature = "std", feature = "psk"))]
 use std::collections::HashSet;
 
-impl<'a, C, P, CSP> ProposalApplier<'a, C, P, CSP>
+impl<'a, C, P, CSP, R> ProposalApplier<'a, C, P, CSP, R>
 where
     C: IdentityProvider,
     P: PreSharedKeyStorage,
     CSP: CipherSuiteProvider,
+    R: MlsRules,
 {
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub(super) async fn apply_proposals_from_member(
@@ -137,7 +138,12 @@ where
 
         { adds }
             .try_for_each(|p| {
-                self.validate_new_node(leaf_node_validator, &p.proposal.key_package, commit_time)
+                self.validate_new_node(
+                    leaf_node_validator,
+                    &p.proposal.key_package,
+                    group_extensions_in_use,
+                    commit_time,
+                )
             })
             .await
     }
@@ -178,9 +184,14 @@ where
 }
 
 fn filter_out_extra_group_context_extensions(proposals: &ProposalBundle) -> Result<(), MlsError> {
-    (proposals.group_context_extensions.len() < 2)
-        .then_some(())
-        .ok_or(MlsError::MoreThanOneGroupContextExtensionsProposal)
+    proposals
+        .group_context_extensions
+        .get(1)
+        .map_or(Ok(()), |p| {
+            Err(MlsError::MoreThanOneGroupContextExtensionsProposal(
+                p.source.clone(),
+            ))
+        })
 }
 
 fn filter_out_invalid_reinit(
@@ -6,6 +6,7 @@ use core::ops::Deref;
 
 use super::*;
 use crate::hash_reference::HashReference;
+use crate::label::mls_label;
 
 #[cfg_attr(
     all(feature = "ffi", not(test)),
@@ -32,11 +33,28 @@ impl ProposalRef {
         cipher_suite_provider: &CS,
         content: &AuthenticatedContent,
     ) -> Result<Self, MlsError> {
-        let bytes = &content.mls_encode_to_vec()?;
+        Self::from_value(cipher_suite_provider, &content.mls_encode_to_vec()?).await
+    }
 
+    /// Compute the reference of an MLS encoded `AuthenticatedContent` carrying a
+    /// proposal, as defined in
+    /// [RFC 9420 Section 5.2](https://www.rfc-editor.org/rfc/rfc9420.html#section-5.2).
+    ///
+    /// The content is not decoded, so `authenticated_content_bytes` must be exactly
+    /// the encoding the reference is expected to cover.
+    #[cfg_attr(all(feature = "ffi", not(test)), ::safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn from_value<CS: CipherSuiteProvider>(
+        cipher_suite_provider: &CS,
+        authenticated_content_bytes: &[u8],
+    ) -> Result<Self, MlsError> {
         Ok(ProposalRef(
-            HashReference::compute(bytes, b"MLS 1.0 Proposal Reference", cipher_suite_provider)
-                .await?,
+            HashReference::compute(
+                authenticated_content_bytes,
+                &mls_label(cipher_suite_provider, b"Proposal Reference"),
+                cipher_suite_provider,
+            )
+            .await?,
         ))
     }
 
@@ -223,4 +241,38 @@ mod test {
             assert_eq!(expected_out, proposal_ref);
         }
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn proposal_ref_from_value_matches_test_vectors() {
+        let test_cases = load_test_cases().await;
+
+        for one_case in test_cases {
+            let Some(cs_provider) = try_test_cipher_suite_provider(one_case.cipher_suite) else {
+                continue;
+            };
+
+            let proposal_ref = ProposalRef::from_value(&cs_provider, &one_case.input)
+                .await
+                .unwrap();
+
+            assert_eq!(proposal_ref.as_slice(), one_case.output);
+        }
+    }
+
+    // The value is the `ref_hash` value of the RFC 9420 crypto-basics vector for cipher suite 1.
+    // The expected reference was computed independently of this crate, by hashing the RefHashInput
+    // structure of RFC 9420 Section 5.2 with SHA-256.
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn proposal_ref_from_value_matches_known_answer() {
+        let Some(provider) = try_test_cipher_suite_provider(1) else {
+            return;
+        };
+
+        let value = hex!("40312db83f651883c05ab26fa12c6af61930015c81947cfd0f129e6d99210bb2");
+
+        let proposal_ref = ProposalRef::from_value(&provider, &value).await.unwrap();
+
+        let expected = hex!("39d9ead8508f304f460142c8b7f521e6301d65b2a141c66b641e6cce1f2be171");
+        assert_eq!(proposal_ref.as_slice(), expected);
+    }
 }
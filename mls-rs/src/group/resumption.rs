@@ -134,6 +134,7 @@ where
             self.config,
             Some(new_signer),
             Some((new_signing_identity, reinit.new_cipher_suite())),
+            Default::default(),
             reinit.new_version(),
         );
 
@@ -289,8 +290,15 @@ async fn resumption_join_group<C: ClientConfig + Clone>(
 ) -> Result<(Group<C>, NewMemberInfo), MlsError> {
     let psk_input = Some(psk_input);
 
-    let (group, new_member_info) =
-        Group::<C>::from_welcome_message(welcome, tree_data, config, signer, psk_input).await?;
+    let (group, new_member_info) = Group::<C>::from_welcome_message(
+        welcome,
+        tree_data,
+        config,
+        |_| Ok(signer),
+        psk_input,
+        None,
+    )
+    .await?;
 
     if group.protocol_version() != expected_new_group_params.version {
         Err(MlsError::ProtocolVersionMismatch)
@@ -15,6 +15,84 @@ pub(crate) fn member_from_leaf_node(leaf_node: &LeafNode, leaf_index: LeafIndex)
     )
 }
 
+/// Changes to the members of a group made by a commit.
+#[cfg_attr(
+    all(feature = "ffi", not(test)),
+    safer_ffi_gen::ffi_type(clone, opaque)
+)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct RosterUpdate {
+    /// Members added by the commit, including a member joining with an
+    /// external commit.
+    pub added: Vec<Member>,
+    /// Members removed by the commit, as they were before the commit.
+    pub removed: Vec<Member>,
+    /// Members whose leaf node was replaced by an update proposal.
+    pub updated: Vec<MemberUpdate>,
+}
+
+/// A member whose leaf node was replaced by an update proposal.
+#[cfg_attr(
+    all(feature = "ffi", not(test)),
+    safer_ffi_gen::ffi_type(clone, opaque)
+)]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct MemberUpdate {
+    /// The member before the update.
+    pub prior: Member,
+    /// The member after the update.
+    pub new: Member,
+}
+
+impl RosterUpdate {
+    pub(crate) fn new(prior_tree: &TreeKemPublic, provisional_state: &ProvisionalState) -> Self {
+        let new_tree = &provisional_state.public_tree;
+        let proposals = &provisional_state.applied_proposals;
+
+        let member = |tree: &TreeKemPublic, index: LeafIndex| {
+            tree.get_leaf_node(index)
+                .ok()
+                .map(|leaf| member_from_leaf_node(leaf, index))
+        };
+
+        let added = provisional_state
+            .indexes_of_added_kpkgs
+            .iter()
+            .chain(provisional_state.external_init_index.iter())
+            .filter_map(|&index| member(new_tree, index))
+            .collect();
+
+        let removed = proposals
+            .remove_proposals()
+            .iter()
+            .filter_map(|p| member(prior_tree, p.proposal.to_remove))
+            .collect();
+
+        #[cfg(feature = "by_ref_proposal")]
+        let updated = proposals
+            .update_proposal_senders()
+            .iter()
+            .filter_map(|&index| {
+                Some(MemberUpdate {
+                    prior: member(prior_tree, index)?,
+                    new: member(new_tree, index)?,
+                })
+            })
+            .collect();
+
+        #[cfg(not(feature = "by_ref_proposal"))]
+        let updated = Vec::new();
+
+        Self {
+            added,
+            removed,
+            updated,
+        }
+    }
+}
+
 #[cfg_attr(
     all(feature = "ffi", not(test)),
     safer_ffi_gen::ffi_type(clone, opaque)
@@ -84,3 +162,39 @@ impl TreeKemPublic {
         Roster { public_tree: self }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        group::test_utils::test_group,
+    };
+
+    use super::Member;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn roster_round_trips_through_json() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        alice.join("bob").await;
+
+        let members = alice.roster().members();
+        let json = serde_json::to_string(&members).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let bob = &value[1];
+
+        assert_eq!(bob["index"], 1);
+        assert_eq!(
+            bob["signing_identity"]["credential"]["Basic"]["identifier"],
+            hex::encode(b"bob")
+        );
+        assert!(bob["signing_identity"]["signature_key"].is_string());
+        assert!(bob["capabilities"]["cipher_suites"].is_array());
+        assert!(bob["extensions"].is_array());
+
+        let decoded: Vec<Member> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, members);
+    }
+}
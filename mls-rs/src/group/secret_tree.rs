@@ -47,7 +47,7 @@ struct TreeSecret(
 
 impl Debug for TreeSecret {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        mls_rs_core::debug::pretty_bytes(&self.0)
+        mls_rs_core::debug::pretty_secret(&self.0)
             .named("TreeSecret")
             .fmt(f)
     }
@@ -242,12 +242,12 @@ impl<T: TreeIndex> SecretTree<T> {
         key_type: KeyType,
     ) -> Result<MessageKeyData, MlsError> {
         let mut ratchet = self.take_leaf_ratchet(cipher_suite, &leaf_index).await?;
-        let res = ratchet.next_message_key(cipher_suite, key_type).await?;
+        let res = ratchet.next_message_key(cipher_suite, key_type).await;
 
         self.known_secrets
             .set_node(leaf_index, SecretTreeNode::Ratchet(ratchet));
 
-        Ok(res)
+        res
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -262,12 +262,14 @@ impl<T: TreeIndex> SecretTree<T> {
 
         let res = ratchet
             .message_key_generation(cipher_suite, generation, key_type)
-            .await?;
+            .await;
 
+        // The ratchet is put back even on error so that a replayed or too distant generation
+        // does not discard the sender's unused keys.
         self.known_secrets
             .set_node(leaf_index, SecretTreeNode::Ratchet(ratchet));
 
-        Ok(res)
+        res
     }
 }
 
@@ -297,8 +299,8 @@ pub struct MessageKeyData {
 impl Debug for MessageKeyData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("MessageKeyData")
-            .field("nonce", &mls_rs_core::debug::pretty_bytes(&self.nonce))
-            .field("key", &mls_rs_core::debug::pretty_bytes(&self.key))
+            .field("nonce", &mls_rs_core::debug::pretty_secret(&self.nonce))
+            .field("key", &mls_rs_core::debug::pretty_secret(&self.key))
             .field("generation", &self.generation)
             .finish()
     }
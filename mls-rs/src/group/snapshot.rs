@@ -10,7 +10,7 @@ use crate::{
         state_repo::GroupStateRepository, CommitGeneration, ConfirmationTag, Group, GroupContext,
         GroupState, InterimTranscriptHash, ReInitProposal, TreeKemPublic,
     },
-    tree_kem::TreeKemPrivate,
+    tree_kem::{tree_validator::TreeValidator, TreeKemPrivate},
 };
 
 #[cfg(feature = "by_ref_proposal")]
@@ -152,6 +152,46 @@ where
         self.state_repo.write_to_storage(self.snapshot()).await
     }
 
+    /// Check that the current state of the group is internally consistent.
+    ///
+    /// The tree hash is recomputed from scratch and compared to the group
+    /// context, parent hashes and leaf node signatures are verified, and the
+    /// confirmation tag of the current epoch is checked against the stored
+    /// transcript hashes. This can be used to detect corruption after loading
+    /// a group from storage, before the group is used.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate(&self) -> Result<(), MlsError> {
+        let context = &self.state.context;
+        let identity_provider = self.config.identity_provider();
+
+        // Importing the nodes into a new tree discards any cached hashes.
+        let mut tree = TreeKemPublic::import_node_data(
+            self.state.public_tree.nodes.clone(),
+            &identity_provider,
+            &context.extensions,
+        )
+        .await?;
+
+        TreeValidator::new(&self.cipher_suite_provider, context, &identity_provider)
+            .validate(&mut tree)
+            .await?;
+
+        // The confirmation key is not retained after the epoch starts, but the
+        // interim transcript hash commits to the confirmation tag.
+        let interim_transcript_hash = InterimTranscriptHash::create(
+            &self.cipher_suite_provider,
+            &context.confirmed_transcript_hash,
+            &self.state.confirmation_tag,
+        )
+        .await?;
+
+        if interim_transcript_hash != self.state.interim_transcript_hash {
+            return Err(MlsError::InvalidConfirmationTag);
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn snapshot(&self) -> Snapshot {
         Snapshot {
             state: RawGroupState::export(&self.state),
@@ -198,6 +238,8 @@ where
             #[cfg(feature = "by_ref_proposal")]
             pending_updates: snapshot.pending_updates,
             pending_commit: snapshot.pending_commit,
+            pending_welcome_secrets: None,
+            welcome_secrets: None,
             #[cfg(test)]
             commit_modifiers: Default::default(),
             epoch_secrets: snapshot.epoch_secrets,
@@ -205,6 +247,8 @@ where
             cipher_suite_provider,
             #[cfg(feature = "psk")]
             previous_psk: None,
+            #[cfg(feature = "test_exports")]
+            join_secrets: None,
             signer: snapshot.signer,
         })
     }
@@ -258,12 +302,19 @@ pub(crate) mod test_utils {
 mod tests {
     use alloc::vec;
 
+    use assert_matches::assert_matches;
+
     use crate::{
-        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        client::{
+            test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
         group::{
-            test_utils::{test_group, TestGroup},
+            confirmation_tag::ConfirmationTag,
+            test_utils::{test_group, test_n_member_group, TestGroup},
             Group,
         },
+        tree_kem::node::LeafIndex,
     };
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -312,6 +363,83 @@ mod tests {
         snapshot_restore(group).await
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn restored_snapshot_passes_validation() {
+        let mut groups = test_n_member_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, 3).await;
+        groups[0].config.0.mls_rules.commit_options.path_required = true;
+        groups[0].commit(vec![]).await.unwrap();
+        groups[0].apply_pending_commit().await.unwrap();
+
+        let restored = Group::from_snapshot(groups[0].config.clone(), groups[0].snapshot())
+            .await
+            .unwrap();
+
+        restored.validate().await.unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn snapshot_with_tampered_tree_fails_validation() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut snapshot = group.snapshot();
+
+        snapshot
+            .state
+            .public_tree
+            .nodes
+            .borrow_as_leaf_mut(LeafIndex(0))
+            .unwrap()
+            .signature[0] ^= 1;
+
+        let restored = Group::from_snapshot(group.config.clone(), snapshot)
+            .await
+            .unwrap();
+
+        let res = restored.validate().await;
+        assert_matches!(res, Err(MlsError::TreeHashMismatch));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn snapshot_with_tampered_leaf_signature_fails_validation() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut snapshot = group.snapshot();
+        let tree = &mut snapshot.state.public_tree;
+
+        tree.nodes
+            .borrow_as_leaf_mut(LeafIndex(0))
+            .unwrap()
+            .signature[0] ^= 1;
+
+        // Make the tree hash consistent so that only the signature check fails
+        snapshot.state.context.tree_hash = tree
+            .nodes
+            .tree_hash(&group.cipher_suite_provider)
+            .await
+            .unwrap();
+
+        let restored = Group::from_snapshot(group.config.clone(), snapshot)
+            .await
+            .unwrap();
+
+        let res = restored.validate().await;
+        assert_matches!(res, Err(MlsError::InvalidLeafNodeSignature(0)));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn snapshot_with_tampered_confirmation_tag_fails_validation() {
+        let group = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let mut snapshot = group.snapshot();
+
+        snapshot.state.confirmation_tag =
+            ConfirmationTag::empty(&group.cipher_suite_provider).await;
+
+        let restored = Group::from_snapshot(group.config.clone(), snapshot)
+            .await
+            .unwrap();
+
+        let res = restored.validate().await;
+        assert_matches!(res, Err(MlsError::InvalidConfirmationTag));
+    }
+
     #[cfg(feature = "serde")]
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn serde() {
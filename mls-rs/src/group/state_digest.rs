@@ -0,0 +1,160 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+use crate::client_config::ClientConfig;
+
+use super::Group;
+
+/// Component of a [`StateDigest`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StateDigestField {
+    Epoch,
+    TreeHash,
+    ConfirmedTranscriptHash,
+    EpochAuthenticator,
+}
+
+/// Summary of the state of a group member in the current epoch, obtained with
+/// [`Group::state_digest`].
+///
+/// Members that processed the same commits have equal digests. Comparing the
+/// digests of two members with [`StateDigest::diff`] shows where their states
+/// diverged: a different tree hash points at the ratchet tree, a different
+/// transcript hash at the commit messages, and a different epoch authenticator
+/// alone at the key schedule, for instance because of a different PSK.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StateDigest {
+    pub epoch: u64,
+    pub tree_hash: Vec<u8>,
+    pub confirmed_transcript_hash: Vec<u8>,
+    pub epoch_authenticator: Vec<u8>,
+}
+
+impl Debug for StateDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateDigest")
+            .field("epoch", &self.epoch)
+            .field(
+                "tree_hash",
+                &mls_rs_core::debug::pretty_bytes(&self.tree_hash),
+            )
+            .field(
+                "confirmed_transcript_hash",
+                &mls_rs_core::debug::pretty_bytes(&self.confirmed_transcript_hash),
+            )
+            .field(
+                "epoch_authenticator",
+                &mls_rs_core::debug::pretty_bytes(&self.epoch_authenticator),
+            )
+            .finish()
+    }
+}
+
+impl StateDigest {
+    /// Components that differ between `self` and `other`, in the order of the
+    /// fields of [`StateDigest`]. The result is empty if the digests are equal.
+    pub fn diff(&self, other: &StateDigest) -> Vec<StateDigestField> {
+        [
+            (self.epoch != other.epoch, StateDigestField::Epoch),
+            (
+                self.tree_hash != other.tree_hash,
+                StateDigestField::TreeHash,
+            ),
+            (
+                self.confirmed_transcript_hash != other.confirmed_transcript_hash,
+                StateDigestField::ConfirmedTranscriptHash,
+            ),
+            (
+                self.epoch_authenticator != other.epoch_authenticator,
+                StateDigestField::EpochAuthenticator,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(differs, field)| differs.then_some(field))
+        .collect()
+    }
+}
+
+impl<C> Group<C>
+where
+    C: ClientConfig + Clone,
+{
+    /// Digest of the state of this member in the current epoch, used to find
+    /// where the states of members diverged.
+    ///
+    /// # Warning
+    ///
+    /// The digest contains the epoch authenticator, which is only meant to be
+    /// compared between members of the group.
+    pub fn state_digest(&self) -> StateDigest {
+        let context = self.context();
+
+        StateDigest {
+            epoch: context.epoch,
+            tree_hash: context.tree_hash.clone(),
+            confirmed_transcript_hash: context.confirmed_transcript_hash.to_vec(),
+            epoch_authenticator: self.key_schedule.authentication_secret.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{
+        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        group::test_utils::test_group,
+    };
+
+    use super::StateDigestField;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn members_in_the_same_epoch_have_equal_digests() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        assert!(alice.state_digest().diff(&bob.state_digest()).is_empty());
+
+        let commit = alice.commit(vec![]).await.unwrap().commit_message;
+        alice.apply_pending_commit().await.unwrap();
+        bob.process_incoming_message(commit).await.unwrap();
+
+        assert_eq!(alice.state_digest(), bob.state_digest());
+        assert_eq!(alice.state_digest().epoch, 2);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn diverged_members_report_differing_fields() {
+        let mut alice = test_group(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE).await;
+        let (mut bob, _) = alice.join("bob").await;
+
+        // Both members commit concurrently and apply their own commit
+        alice.commit(vec![]).await.unwrap();
+        alice.apply_pending_commit().await.unwrap();
+
+        bob.commit(vec![]).await.unwrap();
+        bob.apply_pending_commit().await.unwrap();
+
+        assert_eq!(
+            alice.state_digest().diff(&bob.state_digest()),
+            [
+                StateDigestField::TreeHash,
+                StateDigestField::ConfirmedTranscriptHash,
+                StateDigestField::EpochAuthenticator
+            ]
+        );
+
+        let digest = alice.state_digest();
+
+        let mut other = digest.clone();
+        other.epoch_authenticator[0] ^= 1;
+
+        assert_eq!(digest.diff(&other), [StateDigestField::EpochAuthenticator]);
+    }
+}
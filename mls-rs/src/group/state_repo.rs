@@ -20,6 +20,9 @@ use crate::group::ResumptionPsk;
 #[cfg(feature = "psk")]
 use mls_rs_core::psk::PreSharedKey;
 
+#[cfg(feature = "psk")]
+use crate::psk::resolver::StoredResumptionSecrets;
+
 /// A set of changes to apply to a GroupStateStorage implementation. These changes MUST
 /// be made in a single transaction to avoid creating invalid states.
 #[derive(Default, Clone, Debug)]
@@ -125,12 +128,36 @@ where
             ));
         }
 
+        // Search the stored cache
+        self.storage.resumption_secret(psk_id).await
+    }
+
+    /// Copy of a retained epoch. Unlike [`Self::get_epoch_mut`], an epoch read
+    /// from storage is not cached.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn get_epoch(&self, epoch_id: u64) -> Result<Option<PriorEpoch>, MlsError> {
+        // Search the local inserts cache
+        if let Some(min) = self.pending_commit.inserts.front().map(|e| e.epoch_id()) {
+            if epoch_id >= min {
+                return Ok(self
+                    .pending_commit
+                    .inserts
+                    .get((epoch_id - min) as usize)
+                    .cloned());
+            }
+        }
+
+        // Search the local updates cache
+        if let Some(i) = self.find_pending(epoch_id) {
+            return Ok(Some(self.pending_commit.updates[i].clone()));
+        }
+
         // Search the stored cache
         self.storage
-            .epoch(&psk_id.psk_group_id.0, psk_id.psk_epoch)
+            .epoch(&self.group_id, epoch_id)
             .await
             .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?
-            .map(|e| Ok(PriorEpoch::mls_decode(&mut &*e)?.secrets.resumption_secret))
+            .map(|epoch| PriorEpoch::mls_decode(&mut &*epoch).map_err(Into::into))
             .transpose()
     }
 
@@ -230,7 +257,6 @@ where
         Ok(())
     }
 
-    #[cfg(any(feature = "psk", feature = "private_message"))]
     fn find_pending(&self, epoch_id: u64) -> Option<usize> {
         self.pending_commit
             .updates
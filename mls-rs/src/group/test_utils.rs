@@ -112,7 +112,8 @@ impl TestGroup {
             &welcome_messages[0],
             ratchet_tree,
             new_client.config.clone(),
-            new_client.signer.clone().unwrap(),
+            |_| Ok(new_client.signer.clone().unwrap()),
+            None,
         )
         .await?;
 
@@ -9,8 +9,8 @@ use mls_rs_core::{error::IntoAnyError, key_package::KeyPackageData};
 
 use crate::client::MlsError;
 use crate::{
-    crypto::{HpkeSecretKey, SignatureSecretKey},
-    group::framing::MlsMessagePayload,
+    crypto::{HpkePublicKey, HpkeSecretKey, SignatureSecretKey},
+    group::{framing::MlsMessagePayload, key_schedule::kdf_derive_secret},
     identity::SigningIdentity,
     protocol_version::ProtocolVersion,
     signer::Signable,
@@ -92,23 +92,92 @@ where
         key_package_extensions: ExtensionList,
         leaf_node_extensions: ExtensionList,
     ) -> Result<KeyPackageGeneration, MlsError> {
-        let (init_secret_key, public_init) = self
-            .cipher_suite_provider
-            .kem_generate()
-            .await
-            .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+        self.generate_internal(
+            lifetime,
+            capabilities,
+            key_package_extensions,
+            leaf_node_extensions,
+            None,
+        )
+        .await
+    }
+
+    /// Generate a key package whose init and leaf node HPKE key pairs are derived from
+    /// `init_seed` instead of being random.
+    ///
+    /// The key package and its reference are reproducible for a fixed seed and fixed other
+    /// inputs, provided the signature scheme of the cipher suite is deterministic (e.g.
+    /// Ed25519). GREASE values are drawn from the crypto provider's random number generator,
+    /// so they are never added to key packages generated from a seed, even with the `grease`
+    /// feature enabled. The seed must be kept secret and must not be used for more than one
+    /// key package.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn generate_from_seed(
+        &self,
+        lifetime: Lifetime,
+        capabilities: Capabilities,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+        init_seed: &[u8],
+    ) -> Result<KeyPackageGeneration, MlsError> {
+        self.generate_internal(
+            lifetime,
+            capabilities,
+            key_package_extensions,
+            leaf_node_extensions,
+            Some(init_seed),
+        )
+        .await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn key_pair(
+        &self,
+        seed: Option<&[u8]>,
+        label: &[u8],
+    ) -> Result<(HpkeSecretKey, HpkePublicKey), MlsError> {
+        let key_pair = match seed {
+            Some(seed) => {
+                let prk = self
+                    .cipher_suite_provider
+                    .kdf_extract(&[], seed)
+                    .await
+                    .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
+
+                let ikm = kdf_derive_secret(self.cipher_suite_provider, &prk, label).await?;
+                self.cipher_suite_provider.kem_derive(&ikm).await
+            }
+            None => self.cipher_suite_provider.kem_generate().await,
+        };
+
+        key_pair.map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    async fn generate_internal(
+        &self,
+        lifetime: Lifetime,
+        capabilities: Capabilities,
+        key_package_extensions: ExtensionList,
+        leaf_node_extensions: ExtensionList,
+        seed: Option<&[u8]>,
+    ) -> Result<KeyPackageGeneration, MlsError> {
+        let (init_secret_key, public_init) = self.key_pair(seed, b"key package init").await?;
+        let leaf_node_key_pair = self.key_pair(seed, b"key package leaf node").await?;
 
         let properties = ConfigProperties {
             capabilities,
             extensions: leaf_node_extensions,
         };
 
-        let (leaf_node, leaf_node_secret) = LeafNode::generate(
+        let (leaf_node, leaf_node_secret) = LeafNode::generate_with_key_pair(
             self.cipher_suite_provider,
             properties,
             self.signing_identity.clone(),
             self.signing_key,
             lifetime,
+            leaf_node_key_pair,
+            seed.is_none(),
         )
         .await?;
 
@@ -121,7 +190,10 @@ where
             signature: vec![],
         };
 
-        package.grease(self.cipher_suite_provider)?;
+        // GREASE values are random, which would make seeded generation non-deterministic
+        if seed.is_none() {
+            package.grease(self.cipher_suite_provider)?;
+        }
 
         self.sign(&mut package).await?;
 
@@ -139,7 +211,7 @@ where
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
-    use mls_rs_core::crypto::CipherSuiteProvider;
+    use mls_rs_core::crypto::{CipherSuite, CipherSuiteProvider};
 
     use crate::{
         crypto::test_utils::{test_cipher_suite_provider, TestCryptoProvider},
@@ -331,4 +403,67 @@ mod tests {
             }
         }
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn generation_from_seed_is_deterministic() {
+        // Ed25519 signatures are deterministic
+        let cipher_suite = CipherSuite::CURVE25519_AES128;
+        let cipher_suite_provider = test_cipher_suite_provider(cipher_suite);
+
+        let (signing_identity, signing_key) = get_test_signing_identity(cipher_suite, b"foo").await;
+
+        let test_generator = KeyPackageGenerator {
+            protocol_version: ProtocolVersion::MLS_10,
+            cipher_suite_provider: &cipher_suite_provider,
+            signing_identity: &signing_identity,
+            signing_key: &signing_key,
+        };
+
+        let generate = |seed: &'static [u8]| {
+            test_generator.generate_from_seed(
+                Lifetime::new(0, u64::MAX),
+                get_test_capabilities(),
+                ExtensionList::default(),
+                ExtensionList::default(),
+                seed,
+            )
+        };
+
+        let first = generate(b"seed").await.unwrap();
+        let second = generate(b"seed").await.unwrap();
+
+        assert_eq!(first.key_package, second.key_package);
+        assert_eq!(first.reference, second.reference);
+        assert_eq!(first.init_secret_key, second.init_secret_key);
+        assert_eq!(first.leaf_node_secret_key, second.leaf_node_secret_key);
+
+        assert_ne!(
+            first.key_package.hpke_init_key.as_ref(),
+            first.key_package.leaf_node.public_key.as_ref()
+        );
+
+        assert_eq!(
+            first.key_package.leaf_node.capabilities,
+            first.key_package.leaf_node.ungreased_capabilities()
+        );
+        assert_eq!(
+            first.key_package.extensions,
+            first.key_package.ungreased_extensions()
+        );
+
+        let other = generate(b"other seed").await.unwrap();
+        assert_ne!(
+            first.key_package.hpke_init_key,
+            other.key_package.hpke_init_key
+        );
+        assert_ne!(first.reference, other.reference);
+
+        validate_key_package_properties(
+            &first.key_package,
+            ProtocolVersion::MLS_10,
+            &cipher_suite_provider,
+        )
+        .await
+        .unwrap();
+    }
 }
@@ -7,19 +7,26 @@ use crate::client::MlsError;
 use crate::crypto::HpkePublicKey;
 use crate::hash_reference::HashReference;
 use crate::identity::SigningIdentity;
+use crate::label::mls_label;
 use crate::protocol_version::ProtocolVersion;
 use crate::signer::Signable;
+use crate::time::MlsTime;
 use crate::tree_kem::leaf_node::{LeafNode, LeafNodeSource};
 use crate::CipherSuiteProvider;
 use alloc::vec::Vec;
+
+#[cfg(feature = "pem")]
+use alloc::string::String;
 use core::{
     fmt::{self, Debug},
+    hash::{Hash, Hasher},
     ops::Deref,
 };
 use mls_rs_codec::MlsDecode;
 use mls_rs_codec::MlsEncode;
 use mls_rs_codec::MlsSize;
 use mls_rs_core::extension::ExtensionList;
+use mls_rs_core::group::Capabilities;
 
 mod validator;
 pub(crate) use validator::*;
@@ -28,7 +35,7 @@ pub(crate) mod generator;
 pub(crate) use generator::*;
 
 #[non_exhaustive]
-#[derive(Clone, MlsSize, MlsEncode, MlsDecode, PartialEq)]
+#[derive(Clone, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     all(feature = "ffi", not(test)),
@@ -46,6 +53,26 @@ pub struct KeyPackage {
     pub signature: Vec<u8>,
 }
 
+// Key packages are equal when their MLS encodings are, which is also when their
+// `KeyPackageRef`s are. Comparing fields instead would ignore the order of extensions,
+// so a reordered copy with a broken signature could pass for the original.
+impl PartialEq for KeyPackage {
+    fn eq(&self, other: &Self) -> bool {
+        self.signature == other.signature
+            && self.mls_encode_to_vec().ok() == other.mls_encode_to_vec().ok()
+    }
+}
+
+impl Eq for KeyPackage {}
+
+// Equal key packages have equal signatures, so hashing the signature alone is consistent
+// with `Eq` and avoids encoding the package.
+impl Hash for KeyPackage {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.signature.hash(state);
+    }
+}
+
 impl Debug for KeyPackage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("KeyPackage")
@@ -84,6 +111,29 @@ impl From<Vec<u8>> for KeyPackageRef {
     }
 }
 
+impl KeyPackageRef {
+    /// Compute the reference of an MLS encoded key package, as defined in
+    /// [RFC 9420 Section 5.2](https://www.rfc-editor.org/rfc/rfc9420.html#section-5.2).
+    ///
+    /// The key package is not decoded, so `key_package_bytes` must be exactly the
+    /// encoding that was received. Use [`KeyPackage::to_reference`] for a decoded
+    /// key package.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn from_value<CP: CipherSuiteProvider>(
+        cipher_suite_provider: &CP,
+        key_package_bytes: &[u8],
+    ) -> Result<Self, MlsError> {
+        Ok(Self(
+            HashReference::compute(
+                key_package_bytes,
+                &mls_label(cipher_suite_provider, b"KeyPackage Reference"),
+                cipher_suite_provider,
+            )
+            .await?,
+        ))
+    }
+}
+
 #[derive(MlsSize, MlsEncode)]
 struct KeyPackageData<'a> {
     pub version: ProtocolVersion,
@@ -110,6 +160,19 @@ impl KeyPackage {
         &self.leaf_node.signing_identity
     }
 
+    /// Capabilities advertised by the leaf node of this key package.
+    ///
+    /// These may include GREASE values, see
+    /// [`LeafNode::ungreased_capabilities`](crate::group::LeafNode::ungreased_capabilities).
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.leaf_node.capabilities
+    }
+
+    /// Extensions of this key package, not including the extensions of its leaf node.
+    pub fn extensions(&self) -> &ExtensionList {
+        &self.extensions
+    }
+
     #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     pub async fn to_reference<CP: CipherSuiteProvider>(
@@ -120,14 +183,62 @@ impl KeyPackage {
             return Err(MlsError::CipherSuiteMismatch);
         }
 
-        Ok(KeyPackageRef(
-            HashReference::compute(
-                &self.mls_encode_to_vec()?,
-                b"MLS 1.0 KeyPackage Reference",
+        KeyPackageRef::from_value(cipher_suite_provider, &self.mls_encode_to_vec()?).await
+    }
+
+    /// Verify the signatures of this key package and of its leaf node, and check
+    /// that the current time is within the lifetime of the leaf node.
+    ///
+    /// The credential is not validated, as that requires an
+    /// [`IdentityProvider`](crate::IdentityProvider). Key packages added to a group
+    /// are validated in full, including their credential, when the commit is created
+    /// or processed.
+    ///
+    /// Without the `std` feature, the current time is not available and
+    /// [`KeyPackage::validate_with_time`] must be used instead.
+    #[cfg(feature = "std")]
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate<CP: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &CP,
+    ) -> Result<(), MlsError> {
+        self.validate_with_time(cipher_suite_provider, MlsTime::now())
+            .await
+    }
+
+    /// Verify the signatures of this key package and of its leaf node, and check
+    /// that `time` is within the lifetime of the leaf node.
+    ///
+    /// See [`KeyPackage::validate`] for what is not validated.
+    #[cfg_attr(all(feature = "ffi", not(test)), safer_ffi_gen::safer_ffi_gen_ignore)]
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub async fn validate_with_time<CP: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &CP,
+        time: MlsTime,
+    ) -> Result<(), MlsError> {
+        validate_key_package_properties(self, self.version, cipher_suite_provider).await?;
+
+        self.leaf_node
+            .verify(
                 cipher_suite_provider,
+                &self.leaf_node.signing_identity.signature_key,
+                &Default::default(),
             )
-            .await?,
-        ))
+            .await?;
+
+        let LeafNodeSource::KeyPackage(lifetime) = &self.leaf_node.leaf_node_source else {
+            return Err(MlsError::InvalidLeafNodeSource);
+        };
+
+        lifetime.check_range()?;
+
+        if !lifetime.within_lifetime(time) {
+            return Err(MlsError::InvalidLifetime);
+        }
+
+        Ok(())
     }
 
     pub fn expiration(&self) -> Result<u64, MlsError> {
@@ -139,6 +250,44 @@ impl KeyPackage {
     }
 }
 
+#[cfg(feature = "pem")]
+const PEM_LABEL: &str = "MLS KEYPACKAGE";
+
+#[cfg(feature = "pem")]
+impl KeyPackage {
+    /// Encode this key package as PEM text, with the MLS encoding wrapped
+    /// between `-----BEGIN MLS KEYPACKAGE-----` and `-----END MLS KEYPACKAGE-----`.
+    pub fn to_pem(&self) -> Result<String, MlsError> {
+        pem_rfc7468::encode_string(
+            PEM_LABEL,
+            pem_rfc7468::LineEnding::LF,
+            &self.mls_encode_to_vec()?,
+        )
+        .map_err(|_| MlsError::InvalidPem)
+    }
+
+    /// Decode a key package from PEM text produced by [`KeyPackage::to_pem`].
+    ///
+    /// The PEM label must be `MLS KEYPACKAGE` and the content must be exactly one
+    /// MLS encoded key package. Signatures are not verified, see [`KeyPackage::validate`].
+    pub fn from_pem(pem: &str) -> Result<KeyPackage, MlsError> {
+        let (label, bytes) =
+            pem_rfc7468::decode_vec(pem.as_bytes()).map_err(|_| MlsError::InvalidPem)?;
+
+        if label != PEM_LABEL {
+            return Err(MlsError::InvalidPem);
+        }
+
+        let reader = &mut bytes.as_slice();
+        let key_package = KeyPackage::mls_decode(reader)?;
+
+        reader
+            .is_empty()
+            .then_some(key_package)
+            .ok_or(MlsError::InvalidPem)
+    }
+}
+
 impl<'a> Signable<'a> for KeyPackage {
     const SIGN_LABEL: &'static str = "KeyPackageTBS";
 
@@ -165,6 +314,10 @@ impl<'a> Signable<'a> for KeyPackage {
     fn write_signature(&mut self, signature: Vec<u8>) {
         self.signature = signature
     }
+
+    fn protocol_version(&self, _context: &Self::SigningContext) -> ProtocolVersion {
+        self.version
+    }
 }
 
 #[cfg(test)]
@@ -241,9 +394,15 @@ mod tests {
     use crate::{
         client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
         crypto::test_utils::{test_cipher_suite_provider, try_test_cipher_suite_provider},
+        extension::test_utils::TestExtension,
+        identity::test_utils::get_test_signing_identity,
+        tree_kem::{leaf_node::test_utils::get_test_capabilities, Lifetime},
     };
 
-    use super::{test_utils::test_key_package, *};
+    use super::{
+        test_utils::{test_key_package, test_key_package_with_signer},
+        *,
+    };
     use alloc::format;
     use assert_matches::assert_matches;
 
@@ -315,6 +474,40 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_ref_from_value_matches_test_vectors() {
+        let cases = load_test_cases().await;
+
+        for one_case in cases {
+            let Some(provider) = try_test_cipher_suite_provider(one_case.cipher_suite) else {
+                continue;
+            };
+
+            let key_package_ref = KeyPackageRef::from_value(&provider, &one_case.input)
+                .await
+                .unwrap();
+
+            assert_eq!(key_package_ref.to_vec(), one_case.output);
+        }
+    }
+
+    // The value is the `ref_hash` value of the RFC 9420 crypto-basics vector for cipher suite 1.
+    // The expected reference was computed independently of this crate, by hashing the RefHashInput
+    // structure of RFC 9420 Section 5.2 with SHA-256.
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_ref_from_value_matches_known_answer() {
+        let Some(provider) = try_test_cipher_suite_provider(1) else {
+            return;
+        };
+
+        let value = hex!("40312db83f651883c05ab26fa12c6af61930015c81947cfd0f129e6d99210bb2");
+
+        let key_package_ref = KeyPackageRef::from_value(&provider, &value).await.unwrap();
+
+        let expected = hex!("21040d20f9c6968e927965f4ffabce5529175bff049805c443bec83d8169cb5a");
+        assert_eq!(key_package_ref.to_vec(), expected);
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn key_package_ref_fails_invalid_cipher_suite() {
         let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "test").await;
@@ -327,4 +520,285 @@ mod tests {
             }
         }
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn valid_key_package_passes_validation() {
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let time = MlsTime::from(key_package.expiration().unwrap());
+
+        key_package.validate_with_time(&cs, time).await.unwrap();
+
+        #[cfg(feature = "std")]
+        key_package.validate(&cs).await.unwrap();
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_with_tampered_signature_fails_validation() {
+        let mut key_package =
+            test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        key_package.signature[0] ^= 1;
+
+        let time = MlsTime::from(key_package.expiration().unwrap());
+
+        let res = key_package
+            .validate_with_time(&test_cipher_suite_provider(TEST_CIPHER_SUITE), time)
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_with_tampered_leaf_signature_fails_validation() {
+        let (mut key_package, signer) =
+            test_key_package_with_signer(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        // Only the outer signature is recomputed, so the leaf node signature no longer matches
+        key_package.leaf_node.signature[0] ^= 1;
+        key_package.sign(&cs, &signer, &()).await.unwrap();
+
+        let time = MlsTime::from(key_package.expiration().unwrap());
+
+        let res = key_package.validate_with_time(&cs, time).await;
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn expired_key_package_fails_validation() {
+        let (signing_identity, signing_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let generator = KeyPackageGenerator {
+            protocol_version: TEST_PROTOCOL_VERSION,
+            cipher_suite_provider: &cs,
+            signing_identity: &signing_identity,
+            signing_key: &signing_key,
+        };
+
+        let key_package = generator
+            .generate(
+                Lifetime::new(0, 1),
+                get_test_capabilities(),
+                ExtensionList::new(),
+                ExtensionList::new(),
+            )
+            .await
+            .unwrap()
+            .key_package;
+
+        let res = key_package.validate_with_time(&cs, MlsTime::from(2)).await;
+        assert_matches!(res, Err(MlsError::InvalidLifetime));
+
+        #[cfg(feature = "std")]
+        {
+            let res = key_package.validate(&cs).await;
+            assert_matches!(res, Err(MlsError::InvalidLifetime));
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn inverted_lifetime_key_package_fails_validation() {
+        let (signing_identity, signing_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let generator = KeyPackageGenerator {
+            protocol_version: TEST_PROTOCOL_VERSION,
+            cipher_suite_provider: &cs,
+            signing_identity: &signing_identity,
+            signing_key: &signing_key,
+        };
+
+        let key_package = generator
+            .generate(
+                Lifetime::new(u64::MAX, 0),
+                get_test_capabilities(),
+                ExtensionList::new(),
+                ExtensionList::new(),
+            )
+            .await
+            .unwrap()
+            .key_package;
+
+        let res = key_package.validate_with_time(&cs, MlsTime::from(0)).await;
+        assert_matches!(res, Err(MlsError::InvalidLifetimeRange));
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn capabilities_and_extensions_match_generator() {
+        let (signing_identity, signing_key) =
+            get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+
+        let mut capabilities = get_test_capabilities();
+        capabilities.extensions.push(42.into());
+
+        let mut extensions = ExtensionList::new();
+        extensions.set_from(TestExtension::from(1)).unwrap();
+
+        let generator = KeyPackageGenerator {
+            protocol_version: TEST_PROTOCOL_VERSION,
+            cipher_suite_provider: &test_cipher_suite_provider(TEST_CIPHER_SUITE),
+            signing_identity: &signing_identity,
+            signing_key: &signing_key,
+        };
+
+        let key_package = generator
+            .generate(
+                Lifetime::years(1).unwrap(),
+                capabilities.clone(),
+                extensions,
+                ExtensionList::new(),
+            )
+            .await
+            .unwrap()
+            .key_package;
+
+        let advertised = key_package.capabilities();
+
+        // Greasing may add values, but never removes the ones that were set
+        assert!(capabilities
+            .cipher_suites
+            .iter()
+            .all(|cs| advertised.cipher_suites.contains(cs)));
+
+        assert!(capabilities
+            .extensions
+            .iter()
+            .all(|ext| advertised.extensions.contains(ext)));
+
+        assert!(capabilities
+            .proposals
+            .iter()
+            .all(|p| advertised.proposals.contains(p)));
+
+        assert!(capabilities
+            .credentials
+            .iter()
+            .all(|c| advertised.credentials.contains(c)));
+
+        assert_eq!(
+            key_package.extensions().get_as::<TestExtension>().unwrap(),
+            Some(TestExtension::from(1))
+        );
+    }
+
+    #[cfg(feature = "pem")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_pem_round_trip() {
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let pem = key_package.to_pem().unwrap();
+
+        assert!(pem.starts_with("-----BEGIN MLS KEYPACKAGE-----\n"));
+        assert!(pem.ends_with("-----END MLS KEYPACKAGE-----\n"));
+        assert_eq!(KeyPackage::from_pem(&pem).unwrap(), key_package);
+    }
+
+    #[cfg(feature = "pem")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn malformed_key_package_pem_is_rejected() {
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+        let pem = key_package.to_pem().unwrap();
+
+        let wrong_label = pem.replace("MLS KEYPACKAGE", "MLS WELCOME");
+
+        assert_matches!(
+            KeyPackage::from_pem(&wrong_label),
+            Err(MlsError::InvalidPem)
+        );
+
+        let bad_base64 = pem.replacen('\n', "\n!", 1);
+
+        assert_matches!(KeyPackage::from_pem(&bad_base64), Err(MlsError::InvalidPem));
+
+        let mut bytes = key_package.mls_encode_to_vec().unwrap();
+        bytes.push(0);
+
+        let trailing =
+            pem_rfc7468::encode_string(PEM_LABEL, pem_rfc7468::LineEnding::LF, &bytes).unwrap();
+
+        assert_matches!(KeyPackage::from_pem(&trailing), Err(MlsError::InvalidPem));
+
+        let truncated = pem_rfc7468::encode_string(
+            PEM_LABEL,
+            pem_rfc7468::LineEnding::LF,
+            &bytes[..bytes.len() / 2],
+        )
+        .unwrap();
+
+        assert_matches!(
+            KeyPackage::from_pem(&truncated),
+            Err(MlsError::SerializationError(_))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_packages_are_deduplicated_by_reference() {
+        use mls_rs_core::extension::{Extension, ExtensionType};
+        use std::collections::HashSet;
+
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let alice = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+        let bob = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "bob").await;
+
+        // A decoded copy has the same reference and is the same key package
+        let alice_copy = KeyPackage::mls_decode(&mut &*alice.mls_encode_to_vec().unwrap()).unwrap();
+
+        let alice_ref = alice.to_reference(&cs).await.unwrap();
+        let copy_ref = alice_copy.to_reference(&cs).await.unwrap();
+        assert_eq!(alice_ref, copy_ref);
+
+        // A package sharing the signature of another but differing in content has a
+        // different reference and must not be treated as a duplicate
+        let mut modified = alice.clone();
+        modified
+            .extensions
+            .set_from(TestExtension { foo: 1 })
+            .unwrap();
+
+        let modified_ref = modified.to_reference(&cs).await.unwrap();
+        assert_ne!(alice_ref, modified_ref);
+
+        // Reordering the extensions changes the reference, so the copy is distinct too
+        modified
+            .extensions
+            .set(Extension::new(ExtensionType::new(65000), vec![1]));
+
+        let mut reordered = modified.clone();
+        reordered.extensions = modified
+            .extensions
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into();
+
+        let modified_ref = modified.to_reference(&cs).await.unwrap();
+        let reordered_ref = reordered.to_reference(&cs).await.unwrap();
+        assert_ne!(modified_ref, reordered_ref);
+        assert_ne!(modified, reordered);
+
+        let set = [
+            alice.clone(),
+            bob.clone(),
+            alice_copy,
+            modified.clone(),
+            reordered.clone(),
+        ]
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&alice));
+        assert!(set.contains(&bob));
+        assert!(set.contains(&modified));
+        assert!(set.contains(&reordered));
+    }
 }
@@ -0,0 +1,175 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::vec::Vec;
+use mls_rs_core::{
+    crypto::{CipherSuite, SignatureSecretKey},
+    identity::SigningIdentity,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KeychainEntry {
+    cipher_suite: CipherSuite,
+    signing_identity: SigningIdentity,
+    signer: SignatureSecretKey,
+}
+
+/// Signing identities of a client, indexed by cipher suite and by identity.
+///
+/// Each cipher suite determines a signature scheme, so a client supporting several
+/// cipher suites can hold a distinct identity and signing key for each of them. The
+/// identity most recently inserted for a cipher suite is the one used for it, while
+/// identities it replaced, for instance after a key rotation, can still be looked up
+/// with [`Keychain::signer_for_identity`] until they are removed. A
+/// keychain is set with
+/// [`ClientBuilder::keychain`](crate::client_builder::ClientBuilder::keychain), after
+/// which the [`Client`](crate::Client) picks the matching identity when generating key
+/// packages for a given cipher suite. Welcome messages are joined with the signer of the
+/// identity in the key package they were sent to, even if it was since replaced.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Keychain {
+    entries: Vec<KeychainEntry>,
+}
+
+impl Keychain {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Insert `signing_identity` with its `signer` to be used in groups using
+    /// `cipher_suite`.
+    ///
+    /// If `signing_identity` was already inserted, its signer and cipher suite are
+    /// replaced.
+    pub fn insert(
+        &mut self,
+        signing_identity: SigningIdentity,
+        signer: SignatureSecretKey,
+        cipher_suite: CipherSuite,
+    ) {
+        let entry = KeychainEntry {
+            cipher_suite,
+            signing_identity,
+            signer,
+        };
+
+        self.remove(&entry.signing_identity);
+        self.entries.push(entry);
+    }
+
+    /// Remove `signing_identity`, returning its signer if it was present.
+    ///
+    /// If `signing_identity` was used for its cipher suite, the identity inserted
+    /// before it for the same cipher suite, if any, is used instead.
+    pub fn remove(&mut self, signing_identity: &SigningIdentity) -> Option<SignatureSecretKey> {
+        let position = self
+            .entries
+            .iter()
+            .position(|e| &e.signing_identity == signing_identity)?;
+
+        Some(self.entries.remove(position).signer)
+    }
+
+    /// Signing identity and signer used in groups using `cipher_suite`.
+    pub fn get(
+        &self,
+        cipher_suite: CipherSuite,
+    ) -> Option<(&SigningIdentity, &SignatureSecretKey)> {
+        self.entries
+            .iter()
+            .rfind(|e| e.cipher_suite == cipher_suite)
+            .map(|e| (&e.signing_identity, &e.signer))
+    }
+
+    /// Signer of `signing_identity`, whether or not it is the identity currently used
+    /// for its cipher suite.
+    pub fn signer_for_identity(
+        &self,
+        signing_identity: &SigningIdentity,
+    ) -> Option<&SignatureSecretKey> {
+        self.entries
+            .iter()
+            .find(|e| &e.signing_identity == signing_identity)
+            .map(|e| &e.signer)
+    }
+
+    /// Cipher suites for which the keychain holds an identity.
+    pub fn cipher_suites(&self) -> impl Iterator<Item = CipherSuite> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(i, e)| {
+                !self.entries[i + 1..]
+                    .iter()
+                    .any(|later| later.cipher_suite == e.cipher_suite)
+            })
+            .map(|(_, e)| e.cipher_suite)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        client::test_utils::TEST_CIPHER_SUITE, identity::test_utils::get_test_signing_identity,
+        CipherSuite,
+    };
+
+    use super::Keychain;
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn insert_replaces_identity_of_same_cipher_suite() {
+        let (alice, alice_signer) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let (bob, bob_signer) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let mut keychain = Keychain::new();
+        keychain.insert(alice.clone(), alice_signer.clone(), TEST_CIPHER_SUITE);
+        keychain.insert(bob.clone(), bob_signer.clone(), TEST_CIPHER_SUITE);
+
+        assert_eq!(keychain.get(TEST_CIPHER_SUITE), Some((&bob, &bob_signer)));
+        assert_eq!(keychain.get(CipherSuite::CURVE25519_AES128), None);
+
+        assert_eq!(
+            keychain.cipher_suites().collect::<Vec<_>>(),
+            [TEST_CIPHER_SUITE]
+        );
+
+        assert_eq!(keychain.remove(&bob), Some(bob_signer));
+        assert_eq!(
+            keychain.get(TEST_CIPHER_SUITE),
+            Some((&alice, &alice_signer))
+        );
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn signer_is_found_for_each_identity() {
+        let (old, old_signer) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let (new, new_signer) = get_test_signing_identity(TEST_CIPHER_SUITE, b"alice").await;
+        let (other, _) = get_test_signing_identity(TEST_CIPHER_SUITE, b"bob").await;
+
+        let mut keychain = Keychain::new();
+        keychain.insert(old.clone(), old_signer.clone(), TEST_CIPHER_SUITE);
+        keychain.insert(new.clone(), new_signer.clone(), TEST_CIPHER_SUITE);
+
+        assert_eq!(keychain.signer_for_identity(&old), Some(&old_signer));
+        assert_eq!(keychain.signer_for_identity(&new), Some(&new_signer));
+        assert_eq!(keychain.signer_for_identity(&other), None);
+
+        // Inserting an identity again replaces its signer instead of adding a duplicate
+        keychain.insert(old.clone(), new_signer.clone(), TEST_CIPHER_SUITE);
+
+        assert_eq!(keychain.signer_for_identity(&old), Some(&new_signer));
+        assert_eq!(keychain.get(TEST_CIPHER_SUITE), Some((&old, &new_signer)));
+
+        keychain.remove(&old);
+        keychain.remove(&new);
+
+        assert!(keychain.is_empty());
+    }
+}
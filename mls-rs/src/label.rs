@@ -0,0 +1,451 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::{borrow::Cow, format, vec::Vec};
+
+use crate::{crypto::CipherSuiteProvider, protocol_version::ProtocolVersion};
+
+#[cfg(not(feature = "custom_label_prefix"))]
+const RFC_LABEL_PREFIX: &str = "MLS 1.0 ";
+
+/// Prefix of every MLS 1.0 label used for key derivation, signing, HPKE encryption,
+/// exporting the external init secret and computing hash references.
+///
+/// This is `"MLS 1.0 "` as required by RFC 9420. With the `custom_label_prefix` feature,
+/// it is the [label prefix](CipherSuiteProvider::label_prefix) of `cipher_suite_provider`.
+#[cfg(feature = "custom_label_prefix")]
+fn mls_10_label_prefix<P: CipherSuiteProvider>(cipher_suite_provider: &P) -> &str {
+    cipher_suite_provider.label_prefix()
+}
+
+#[cfg(not(feature = "custom_label_prefix"))]
+fn mls_10_label_prefix<P: CipherSuiteProvider>(_cipher_suite_provider: &P) -> &str {
+    RFC_LABEL_PREFIX
+}
+
+/// `label` with the MLS 1.0 label prefix prepended.
+pub(crate) fn mls_label<P: CipherSuiteProvider>(
+    cipher_suite_provider: &P,
+    label: &[u8],
+) -> Vec<u8> {
+    [mls_10_label_prefix(cipher_suite_provider).as_bytes(), label].concat()
+}
+
+/// Label prefix for `version`.
+///
+/// This is the MLS 1.0 label prefix for MLS 1.0. Other versions are not supported yet and
+/// get the prefix `"MLS <version>.0 "`, where `<version>` is the raw value of the version.
+pub(crate) fn label_prefix<P: CipherSuiteProvider>(
+    cipher_suite_provider: &P,
+    version: ProtocolVersion,
+) -> Cow<'_, str> {
+    if version == ProtocolVersion::MLS_10 {
+        Cow::Borrowed(mls_10_label_prefix(cipher_suite_provider))
+    } else {
+        Cow::Owned(format!("MLS {}.0 ", version.raw_value()))
+    }
+}
+
+/// `label` with the [label prefix](label_prefix) of `version` prepended.
+pub(crate) fn versioned_label<P: CipherSuiteProvider>(
+    cipher_suite_provider: &P,
+    version: ProtocolVersion,
+    label: &[u8],
+) -> Vec<u8> {
+    [
+        label_prefix(cipher_suite_provider, version).as_bytes(),
+        label,
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use mls_rs_codec::MlsEncode;
+
+    use crate::{
+        client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+        crypto::test_utils::test_cipher_suite_provider,
+        group::key_schedule::kdf_expand_with_label,
+        hash_reference::HashReference,
+        key_package::test_utils::test_key_package,
+    };
+
+    use super::*;
+
+    #[test]
+    fn labels_have_rfc_prefix() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        assert_eq!(mls_label(&cs, b"sender data"), b"MLS 1.0 sender data");
+
+        assert_eq!(
+            versioned_label(&cs, ProtocolVersion::MLS_10, b"GroupInfoTBS"),
+            b"MLS 1.0 GroupInfoTBS"
+        );
+
+        assert_eq!(label_prefix(&cs, ProtocolVersion::new(2)), "MLS 2.0 ");
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn key_package_reference_uses_rfc_label() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let key_package = test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let expected = HashReference::compute(
+            &key_package.mls_encode_to_vec().unwrap(),
+            b"MLS 1.0 KeyPackage Reference",
+            &cs,
+        )
+        .await
+        .unwrap();
+
+        let reference = key_package.to_reference(&cs).await.unwrap();
+
+        assert_eq!(reference.as_ref(), expected.as_ref());
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn kdf_label_uses_rfc_label() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let secret = vec![0u8; cs.kdf_extract_size()];
+
+        // KDFLabel { uint16 length; opaque label<V>; opaque context<V>; }
+        let kdf_label = [
+            &32u16.to_be_bytes()[..],
+            &[14],
+            b"MLS 1.0 secret",
+            &[7],
+            b"context",
+        ]
+        .concat();
+
+        let expected = cs.kdf_expand(&secret, &kdf_label, 32).await.unwrap();
+
+        let derived = kdf_expand_with_label(&cs, &secret, b"secret", b"context", Some(32))
+            .await
+            .unwrap();
+
+        assert_eq!(derived, expected);
+    }
+
+    #[cfg(feature = "custom_label_prefix")]
+    mod custom_prefix {
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        use mls_rs_core::crypto::{
+            CipherSuite, CipherSuiteProvider, HpkeCiphertext, HpkePublicKey, HpkeSecretKey,
+            SignaturePublicKey, SignatureSecretKey,
+        };
+        use zeroize::Zeroizing;
+
+        use crate::{
+            client::test_utils::{TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            crypto::test_utils::test_cipher_suite_provider,
+            group::{
+                key_schedule::{kdf_expand_with_label, InitSecret},
+                proposal_ref::ProposalRef,
+            },
+            key_package::test_utils::test_key_package,
+            signer::{test_utils::TestSignable, Signable},
+            tree_kem::{hpke_encryption::HpkeEncryptable, path_secret::PathSecret},
+        };
+
+        use super::super::*;
+
+        const PREFIX: &str = "XMLS 9.9 ";
+
+        /// Provider that delegates everything to `inner` except for the label prefix.
+        #[derive(Clone, Debug)]
+        struct PrefixedProvider<P> {
+            inner: P,
+        }
+
+        #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+        #[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+        #[cfg_attr(
+            all(not(target_arch = "wasm32"), mls_build_async),
+            maybe_async::must_be_async
+        )]
+        impl<P: CipherSuiteProvider> CipherSuiteProvider for PrefixedProvider<P> {
+            type Error = P::Error;
+            type HpkeContextS = P::HpkeContextS;
+            type HpkeContextR = P::HpkeContextR;
+
+            fn cipher_suite(&self) -> CipherSuite {
+                self.inner.cipher_suite()
+            }
+
+            fn label_prefix(&self) -> &str {
+                PREFIX
+            }
+
+            async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+                self.inner.hash(data).await
+            }
+
+            async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+                self.inner.mac(key, data).await
+            }
+
+            async fn aead_seal(
+                &self,
+                key: &[u8],
+                data: &[u8],
+                aad: Option<&[u8]>,
+                nonce: &[u8],
+            ) -> Result<Vec<u8>, Self::Error> {
+                self.inner.aead_seal(key, data, aad, nonce).await
+            }
+
+            async fn aead_open(
+                &self,
+                key: &[u8],
+                ciphertext: &[u8],
+                aad: Option<&[u8]>,
+                nonce: &[u8],
+            ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+                self.inner.aead_open(key, ciphertext, aad, nonce).await
+            }
+
+            fn aead_key_size(&self) -> usize {
+                self.inner.aead_key_size()
+            }
+
+            fn aead_nonce_size(&self) -> usize {
+                self.inner.aead_nonce_size()
+            }
+
+            async fn kdf_extract(
+                &self,
+                salt: &[u8],
+                ikm: &[u8],
+            ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+                self.inner.kdf_extract(salt, ikm).await
+            }
+
+            async fn kdf_expand(
+                &self,
+                prk: &[u8],
+                info: &[u8],
+                len: usize,
+            ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+                self.inner.kdf_expand(prk, info, len).await
+            }
+
+            fn kdf_extract_size(&self) -> usize {
+                self.inner.kdf_extract_size()
+            }
+
+            async fn hpke_seal(
+                &self,
+                remote_key: &HpkePublicKey,
+                info: &[u8],
+                aad: Option<&[u8]>,
+                pt: &[u8],
+            ) -> Result<HpkeCiphertext, Self::Error> {
+                self.inner.hpke_seal(remote_key, info, aad, pt).await
+            }
+
+            async fn hpke_open(
+                &self,
+                ciphertext: &HpkeCiphertext,
+                local_secret: &HpkeSecretKey,
+                local_public: &HpkePublicKey,
+                info: &[u8],
+                aad: Option<&[u8]>,
+            ) -> Result<Vec<u8>, Self::Error> {
+                self.inner
+                    .hpke_open(ciphertext, local_secret, local_public, info, aad)
+                    .await
+            }
+
+            async fn hpke_setup_s(
+                &self,
+                remote_key: &HpkePublicKey,
+                info: &[u8],
+            ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+                self.inner.hpke_setup_s(remote_key, info).await
+            }
+
+            async fn hpke_setup_r(
+                &self,
+                kem_output: &[u8],
+                local_secret: &HpkeSecretKey,
+                local_public: &HpkePublicKey,
+                info: &[u8],
+            ) -> Result<Self::HpkeContextR, Self::Error> {
+                self.inner
+                    .hpke_setup_r(kem_output, local_secret, local_public, info)
+                    .await
+            }
+
+            async fn kem_derive(
+                &self,
+                ikm: &[u8],
+            ) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+                self.inner.kem_derive(ikm).await
+            }
+
+            async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+                self.inner.kem_generate().await
+            }
+
+            fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), Self::Error> {
+                self.inner.kem_public_key_validate(key)
+            }
+
+            fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+                self.inner.random_bytes(out)
+            }
+
+            async fn signature_key_generate(
+                &self,
+            ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+                self.inner.signature_key_generate().await
+            }
+
+            async fn signature_key_derive_public(
+                &self,
+                secret_key: &SignatureSecretKey,
+            ) -> Result<SignaturePublicKey, Self::Error> {
+                self.inner.signature_key_derive_public(secret_key).await
+            }
+
+            async fn sign(
+                &self,
+                secret_key: &SignatureSecretKey,
+                data: &[u8],
+            ) -> Result<Vec<u8>, Self::Error> {
+                self.inner.sign(secret_key, data).await
+            }
+
+            async fn verify(
+                &self,
+                public_key: &SignaturePublicKey,
+                signature: &[u8],
+                data: &[u8],
+            ) -> Result<(), Self::Error> {
+                self.inner.verify(public_key, signature, data).await
+            }
+        }
+
+        fn prefixed<P>(inner: P) -> PrefixedProvider<P> {
+            PrefixedProvider { inner }
+        }
+
+        #[test]
+        fn labels_have_custom_prefix() {
+            let cs = prefixed(test_cipher_suite_provider(TEST_CIPHER_SUITE));
+
+            assert_eq!(mls_label(&cs, b"sender data"), b"XMLS 9.9 sender data");
+
+            assert_eq!(
+                versioned_label(&cs, ProtocolVersion::MLS_10, b"GroupInfoTBS"),
+                b"XMLS 9.9 GroupInfoTBS"
+            );
+        }
+
+        #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+        async fn custom_prefix_changes_key_derivation() {
+            let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+            let secret = vec![0u8; cs.kdf_extract_size()];
+
+            let rfc = kdf_expand_with_label(&cs, &secret, b"secret", b"context", None)
+                .await
+                .unwrap();
+
+            let custom = kdf_expand_with_label(&prefixed(cs), &secret, b"secret", b"context", None)
+                .await
+                .unwrap();
+
+            assert_ne!(rfc, custom);
+        }
+
+        #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+        async fn custom_prefix_changes_hash_references() {
+            let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+            let key_package =
+                test_key_package(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+            let rfc = key_package.to_reference(&cs).await.unwrap();
+            let custom = key_package
+                .to_reference(&prefixed(cs.clone()))
+                .await
+                .unwrap();
+
+            assert_ne!(rfc, custom);
+
+            let rfc = ProposalRef::from_value(&cs, b"proposal").await.unwrap();
+            let custom = ProposalRef::from_value(&prefixed(cs), b"proposal")
+                .await
+                .unwrap();
+
+            assert_ne!(rfc, custom);
+        }
+
+        #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+        async fn custom_prefix_changes_signatures() {
+            let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+            let (secret_key, public_key) = cs.signature_key_generate().await.unwrap();
+            let context = b"context".to_vec();
+
+            let mut signable = TestSignable {
+                content: b"content".to_vec(),
+                signature: vec![],
+            };
+
+            signable
+                .sign(&prefixed(cs.clone()), &secret_key, &context)
+                .await
+                .unwrap();
+
+            signable
+                .verify(&prefixed(cs.clone()), &public_key, &context)
+                .await
+                .unwrap();
+
+            let res = signable.verify(&cs, &public_key, &context).await;
+
+            assert!(res.is_err());
+        }
+
+        #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+        async fn custom_prefix_changes_hpke_encryption() {
+            let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+            let (secret_key, public_key) = cs.kem_generate().await.unwrap();
+            let path_secret = PathSecret::from(vec![0u8; cs.kdf_extract_size()]);
+
+            let ciphertext = path_secret
+                .encrypt(&prefixed(cs.clone()), &public_key, b"context")
+                .await
+                .unwrap();
+
+            let res =
+                PathSecret::decrypt(&cs, &secret_key, &public_key, b"context", &ciphertext).await;
+
+            assert!(res.is_err());
+        }
+
+        #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+        async fn custom_prefix_changes_external_init_secret() {
+            let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+            let (secret_key, public_key) = cs.kem_generate().await.unwrap();
+
+            let (custom, kem_output) =
+                InitSecret::encode_for_external(&prefixed(cs.clone()), &public_key)
+                    .await
+                    .unwrap();
+
+            let rfc = InitSecret::decode_for_external(&cs, &kem_output, &secret_key, &public_key)
+                .await
+                .unwrap();
+
+            assert_ne!(rfc, custom);
+        }
+    }
+}
@@ -148,6 +148,9 @@ mod hash_reference;
 pub mod identity;
 mod iter;
 mod key_package;
+/// Signing identities of a [`Client`] supporting several cipher suites.
+pub mod keychain;
+mod label;
 pub(crate) mod map;
 /// Pre-shared key support.
 pub mod psk;
@@ -12,14 +12,14 @@ use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 #[cfg(any(test, feature = "external_client"))]
 use mls_rs_core::psk::PreSharedKeyStorage;
 
-#[cfg(any(test, feature = "external_client"))]
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
 use core::convert::Infallible;
 use core::fmt::{self, Debug};
 
 #[cfg(feature = "psk")]
 use crate::{client::MlsError, CipherSuiteProvider};
 
-#[cfg(feature = "psk")]
 use mls_rs_core::error::IntoAnyError;
 
 #[cfg(feature = "psk")]
@@ -113,16 +113,63 @@ pub(crate) struct ResumptionPsk {
     pub psk_epoch: u64,
 }
 
+/// Purpose of a resumption PSK.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd, MlsSize, MlsEncode, MlsDecode)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
-pub(crate) enum ResumptionPSKUsage {
+pub enum ResumptionPSKUsage {
+    /// PSK injected by the application into a commit.
     Application = 1u8,
+    /// PSK used to join the group created by reinitializing a group.
     Reinit = 2u8,
+    /// PSK used to join the group created by branching a group.
     Branch = 3u8,
 }
 
+/// Application provided source of resumption secrets, used to resolve resumption PSKs
+/// when branching or reinitializing a group and when joining the resulting group.
+///
+/// The resolver is only consulted for resumption PSKs referencing an epoch that is
+/// neither the current epoch of the group processing it nor stored in the
+/// [`GroupStateStorage`](mls_rs_core::group::GroupStateStorage) of the client, e.g.
+/// an epoch the application archived after it was evicted from storage.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+pub trait ResumptionPskResolver: Send + Sync {
+    /// Error type that the underlying resolver returns on internal failure.
+    type Error: IntoAnyError;
+
+    /// Return the resumption secret of epoch `epoch` of group `group_id`, or `None` if
+    /// this epoch is not known to the resolver. `usage` is the purpose of the PSK.
+    async fn resumption_secret(
+        &self,
+        group_id: &[u8],
+        epoch: u64,
+        usage: ResumptionPSKUsage,
+    ) -> Result<Option<PreSharedKey>, Self::Error>;
+}
+
+/// Resolver that knows no resumption secrets. Resumption PSKs are then only resolved
+/// from the state of the client.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoResumptionPskResolver;
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl ResumptionPskResolver for NoResumptionPskResolver {
+    type Error = Infallible;
+
+    async fn resumption_secret(
+        &self,
+        _group_id: &[u8],
+        _epoch: u64,
+        _usage: ResumptionPSKUsage,
+    ) -> Result<Option<PreSharedKey>, Self::Error> {
+        Ok(None)
+    }
+}
+
 #[cfg(feature = "psk")]
 #[derive(Clone, Debug, PartialEq, MlsSize, MlsEncode)]
 struct PSKLabel<'a> {
@@ -183,7 +230,12 @@ mod tests {
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::wasm_bindgen_test as test;
 
-    use super::test_utils::make_nonce;
+    use super::{test_utils::make_nonce, PskNonce};
+    use crate::{
+        client::test_utils::TEST_CIPHER_SUITE, crypto::test_utils::test_cipher_suite_provider,
+        test_utils::deterministic::DeterministicCipherSuiteProvider,
+    };
+    use mls_rs_core::crypto::CipherSuiteProvider;
 
     #[test]
     fn random_generation_of_nonces_is_random() {
@@ -198,4 +250,23 @@ mod tests {
 
         assert!(good);
     }
+
+    #[test]
+    fn deterministic_provider_produces_reproducible_nonces() {
+        let cs =
+            DeterministicCipherSuiteProvider::new(test_cipher_suite_provider(TEST_CIPHER_SUITE));
+
+        let len = cs.kdf_extract_size();
+
+        let first = PskNonce::random(&cs).unwrap();
+        let second = PskNonce::random(&cs.clone()).unwrap();
+
+        assert_eq!(first, PskNonce((0..len).map(|i| i as u8).collect()));
+        assert_eq!(second, PskNonce((len..2 * len).map(|i| i as u8).collect()));
+
+        let fresh =
+            DeterministicCipherSuiteProvider::new(test_cipher_suite_provider(TEST_CIPHER_SUITE));
+
+        assert_eq!(PskNonce::random(&fresh).unwrap(), first);
+    }
 }
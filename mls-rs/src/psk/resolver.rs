@@ -2,37 +2,121 @@
 // Copyright by contributors to this project.
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
+#[cfg(mls_build_async)]
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 use mls_rs_core::{
     crypto::CipherSuiteProvider,
     error::IntoAnyError,
     group::GroupStateStorage,
-    key_package::KeyPackageStorage,
     psk::{ExternalPskId, PreSharedKey, PreSharedKeyStorage},
 };
 
+#[cfg(feature = "prior_epoch")]
+use mls_rs_codec::MlsDecode;
+
+#[cfg(feature = "prior_epoch")]
+use zeroize::Zeroizing;
+
+use mls_rs_core::key_package::KeyPackageStorage;
+
+#[cfg(feature = "prior_epoch")]
+use crate::group::{epoch::PriorEpoch, state_repo::GroupStateRepository};
+
+#[cfg(not(feature = "prior_epoch"))]
+use crate::group::state_repo_light::GroupStateRepository;
+
 use crate::{
     client::MlsError,
-    group::{epoch::EpochSecrets, state_repo::GroupStateRepository, GroupContext},
+    group::{epoch::EpochSecrets, GroupContext},
     psk::secret::PskSecret,
 };
 
-use super::{secret::PskSecretInput, JustPreSharedKeyID, PreSharedKeyID, ResumptionPsk};
+use super::{
+    secret::PskSecretInput, JustPreSharedKeyID, PreSharedKeyID, ResumptionPsk,
+    ResumptionPskResolver,
+};
+
+/// Resumption secrets of epochs stored by a client. The referenced epoch may belong to
+/// any group, e.g. the group a new group was branched or reinitialized from.
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+pub(crate) trait StoredResumptionSecrets: Send + Sync {
+    /// Return the resumption secret of the epoch referenced by `psk_id`, or `None` if this
+    /// epoch is not available.
+    async fn resumption_secret(
+        &self,
+        psk_id: &ResumptionPsk,
+    ) -> Result<Option<PreSharedKey>, MlsError>;
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl<GS: GroupStateStorage> StoredResumptionSecrets for GS {
+    #[cfg(feature = "prior_epoch")]
+    async fn resumption_secret(
+        &self,
+        psk_id: &ResumptionPsk,
+    ) -> Result<Option<PreSharedKey>, MlsError> {
+        // The stored epoch contains all of its secrets, so its encoding is wiped as well
+        self.epoch(&psk_id.psk_group_id.0, psk_id.psk_epoch)
+            .await
+            .map_err(|e| MlsError::GroupStorageError(e.into_any_error()))?
+            .map(Zeroizing::new)
+            .map(|e| Ok(PriorEpoch::mls_decode(&mut &**e)?.secrets.resumption_secret))
+            .transpose()
+    }
+
+    // Prior epochs are not stored without the `prior_epoch` feature.
+    #[cfg(not(feature = "prior_epoch"))]
+    async fn resumption_secret(
+        &self,
+        _psk_id: &ResumptionPsk,
+    ) -> Result<Option<PreSharedKey>, MlsError> {
+        Ok(None)
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(mls_build_async, maybe_async::must_be_async)]
+impl<GS: GroupStateStorage, K: KeyPackageStorage> StoredResumptionSecrets
+    for GroupStateRepository<GS, K>
+{
+    #[cfg(feature = "prior_epoch")]
+    async fn resumption_secret(
+        &self,
+        psk_id: &ResumptionPsk,
+    ) -> Result<Option<PreSharedKey>, MlsError> {
+        GroupStateRepository::resumption_secret(self, psk_id).await
+    }
 
-pub(crate) struct PskResolver<'a, GS, K, PS>
+    #[cfg(not(feature = "prior_epoch"))]
+    async fn resumption_secret(
+        &self,
+        _psk_id: &ResumptionPsk,
+    ) -> Result<Option<PreSharedKey>, MlsError> {
+        Ok(None)
+    }
+}
+
+pub(crate) struct PskResolver<'a, R, PS, RR>
 where
-    GS: GroupStateStorage,
+    R: StoredResumptionSecrets,
     PS: PreSharedKeyStorage,
-    K: KeyPackageStorage,
+    RR: ResumptionPskResolver,
 {
     pub group_context: Option<&'a GroupContext>,
     pub current_epoch: Option<&'a EpochSecrets>,
-    pub prior_epochs: Option<&'a GroupStateRepository<GS, K>>,
+    pub prior_epochs: Option<&'a R>,
     pub psk_store: &'a PS,
+    pub resumption_psk_resolver: &'a RR,
 }
 
-impl<GS: GroupStateStorage, K: KeyPackageStorage, PS: PreSharedKeyStorage>
-    PskResolver<'_, GS, K, PS>
+impl<R, PS, RR> PskResolver<'_, R, PS, RR>
+where
+    R: StoredResumptionSecrets,
+    PS: PreSharedKeyStorage,
+    RR: ResumptionPskResolver,
 {
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
     async fn resolve_resumption(&self, psk_id: &ResumptionPsk) -> Result<PreSharedKey, MlsError> {
@@ -43,14 +127,21 @@ impl<GS: GroupStateStorage, K: KeyPackageStorage, PS: PreSharedKeyStorage>
             }
         }
 
-        #[cfg(feature = "prior_epoch")]
         if let Some(eps) = self.prior_epochs {
             if let Some(psk) = eps.resumption_secret(psk_id).await? {
                 return Ok(psk);
             }
         }
 
-        Err(MlsError::OldGroupStateNotFound)
+        self.resumption_psk_resolver
+            .resumption_secret(
+                &psk_id.psk_group_id.0,
+                psk_id.psk_epoch,
+                psk_id.usage.clone(),
+            )
+            .await
+            .map_err(|e| MlsError::ResumptionPskResolverError(e.into_any_error()))?
+            .ok_or(MlsError::ResumptionEpochNotFound(psk_id.psk_epoch))
     }
 
     #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
@@ -9,7 +9,7 @@ use core::{
     ops::Deref,
 };
 use mls_rs_core::crypto::CipherSuiteProvider;
-use zeroize::Zeroizing;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 #[cfg(feature = "psk")]
 use mls_rs_codec::MlsEncode;
@@ -31,12 +31,12 @@ pub(crate) struct PskSecretInput {
     pub psk: PreSharedKey,
 }
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Zeroize, ZeroizeOnDrop)]
 pub(crate) struct PskSecret(Zeroizing<Vec<u8>>);
 
 impl Debug for PskSecret {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        mls_rs_core::debug::pretty_bytes(&self.0)
+        mls_rs_core::debug::pretty_secret(&self.0)
             .named("PskSecret")
             .fmt(f)
     }
@@ -9,6 +9,8 @@ use mls_rs_core::error::IntoAnyError;
 
 use crate::client::MlsError;
 use crate::crypto::{CipherSuiteProvider, SignaturePublicKey, SignatureSecretKey};
+use crate::label::versioned_label;
+use crate::protocol_version::ProtocolVersion;
 
 #[derive(Clone, MlsSize, MlsEncode)]
 struct SignContent {
@@ -28,9 +30,14 @@ impl Debug for SignContent {
 }
 
 impl SignContent {
-    pub fn new(label: &str, content: Vec<u8>) -> Self {
+    pub fn new<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        version: ProtocolVersion,
+        label: &str,
+        content: Vec<u8>,
+    ) -> Self {
         Self {
-            label: [b"MLS 1.0 ", label.as_bytes()].concat(),
+            label: versioned_label(cipher_suite_provider, version, label.as_bytes()),
             content,
         }
     }
@@ -56,13 +63,23 @@ pub(crate) trait Signable<'a> {
 
     fn write_signature(&mut self, signature: Vec<u8>);
 
+    /// Protocol version selecting the prefix of [`Self::SIGN_LABEL`].
+    fn protocol_version(&self, _context: &Self::SigningContext) -> ProtocolVersion {
+        ProtocolVersion::MLS_10
+    }
+
     async fn sign<P: CipherSuiteProvider>(
         &mut self,
         signature_provider: &P,
         signer: &SignatureSecretKey,
         context: &Self::SigningContext,
     ) -> Result<(), MlsError> {
-        let sign_content = SignContent::new(Self::SIGN_LABEL, self.signable_content(context)?);
+        let sign_content = SignContent::new(
+            signature_provider,
+            self.protocol_version(context),
+            Self::SIGN_LABEL,
+            self.signable_content(context)?,
+        );
 
         let signature = signature_provider
             .sign(signer, &sign_content.mls_encode_to_vec()?)
@@ -80,7 +97,12 @@ pub(crate) trait Signable<'a> {
         public_key: &SignaturePublicKey,
         context: &Self::SigningContext,
     ) -> Result<(), MlsError> {
-        let sign_content = SignContent::new(Self::SIGN_LABEL, self.signable_content(context)?);
+        let sign_content = SignContent::new(
+            signature_provider,
+            self.protocol_version(context),
+            Self::SIGN_LABEL,
+            self.signable_content(context)?,
+        );
 
         signature_provider
             .verify(
@@ -354,4 +376,116 @@ mod tests {
 
         assert_matches!(res, Err(MlsError::InvalidSignature));
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn test_tampered_signature() {
+        let cipher_suite_provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let (secret, public) = cipher_suite_provider
+            .signature_key_generate()
+            .await
+            .unwrap();
+
+        let mut test_signable = TestSignable {
+            content: random_bytes(32),
+            signature: vec![],
+        };
+
+        test_signable
+            .sign(&cipher_suite_provider, &secret, &vec![])
+            .await
+            .unwrap();
+
+        test_signable
+            .verify(&cipher_suite_provider, &public, &vec![])
+            .await
+            .unwrap();
+
+        let last = test_signable.signature.len() - 1;
+        test_signable.signature[last] ^= 1;
+
+        let res = test_signable
+            .verify(&cipher_suite_provider, &public, &vec![])
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
+
+    struct VersionedSignable {
+        version: ProtocolVersion,
+        inner: TestSignable,
+    }
+
+    impl<'a> Signable<'a> for VersionedSignable {
+        const SIGN_LABEL: &'static str = "SignWithLabel";
+
+        type SigningContext = Vec<u8>;
+
+        fn signature(&self) -> &[u8] {
+            self.inner.signature()
+        }
+
+        fn signable_content(
+            &self,
+            context: &Self::SigningContext,
+        ) -> Result<Vec<u8>, mls_rs_codec::Error> {
+            self.inner.signable_content(context)
+        }
+
+        fn write_signature(&mut self, signature: Vec<u8>) {
+            self.inner.write_signature(signature)
+        }
+
+        fn protocol_version(&self, _context: &Self::SigningContext) -> ProtocolVersion {
+            self.version
+        }
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn sign_label_depends_on_protocol_version() {
+        let cipher_suite_provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let (secret, public) = cipher_suite_provider
+            .signature_key_generate()
+            .await
+            .unwrap();
+
+        let content = random_bytes(32);
+
+        let mut mls_10 = VersionedSignable {
+            version: ProtocolVersion::MLS_10,
+            inner: TestSignable {
+                content: content.clone(),
+                signature: vec![],
+            },
+        };
+
+        mls_10
+            .sign(&cipher_suite_provider, &secret, &vec![])
+            .await
+            .unwrap();
+
+        // The default version is MLS 1.0
+        TestSignable {
+            content: content.clone(),
+            signature: mls_10.inner.signature.clone(),
+        }
+        .verify(&cipher_suite_provider, &public, &vec![])
+        .await
+        .unwrap();
+
+        let other_version = VersionedSignable {
+            version: ProtocolVersion::new(2),
+            inner: TestSignable {
+                content,
+                signature: mls_10.inner.signature,
+            },
+        };
+
+        let res = other_version
+            .verify(&cipher_suite_provider, &public, &vec![])
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidSignature));
+    }
 }
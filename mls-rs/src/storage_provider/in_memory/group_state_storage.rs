@@ -23,6 +23,12 @@ use crate::{
     map::{LargeMap, LargeMapEntry},
 };
 
+#[cfg(feature = "prior_epoch")]
+use crate::group::{epoch::PriorEpoch, GroupContext};
+
+#[cfg(feature = "prior_epoch")]
+use mls_rs_codec::MlsDecode;
+
 #[cfg(feature = "std")]
 use std::sync::{Mutex, MutexGuard};
 
@@ -42,7 +48,7 @@ impl Debug for InMemoryGroupData {
         f.debug_struct("InMemoryGroupData")
             .field(
                 "state_data",
-                &mls_rs_core::debug::pretty_bytes(&self.state_data),
+                &mls_rs_core::debug::pretty_secret(&self.state_data),
             )
             .field("epoch_data", &self.epoch_data)
             .finish()
@@ -93,6 +99,37 @@ impl InMemoryGroupData {
     }
 }
 
+/// Public information about an epoch retained by [`InMemoryGroupStateStorage`].
+///
+/// The secrets of the epoch are not exposed.
+#[cfg(feature = "prior_epoch")]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RetainedEpoch {
+    /// Group context of the epoch.
+    pub group_context: GroupContext,
+    /// Leaf index of the local member in the epoch.
+    pub self_index: u32,
+}
+
+#[cfg(feature = "prior_epoch")]
+impl RetainedEpoch {
+    /// Epoch number.
+    pub fn epoch_id(&self) -> u64 {
+        self.group_context.epoch
+    }
+}
+
+#[cfg(feature = "prior_epoch")]
+impl From<PriorEpoch> for RetainedEpoch {
+    fn from(epoch: PriorEpoch) -> Self {
+        Self {
+            group_context: epoch.context,
+            self_index: *epoch.self_index,
+        }
+    }
+}
+
 #[derive(Clone)]
 /// In memory group state storage backed by a HashMap.
 ///
@@ -152,6 +189,27 @@ impl InMemoryGroupStateStorage {
         self.lock().remove(group_id);
     }
 
+    /// Get the epochs retained for `group_id` in ascending order.
+    ///
+    /// At most `max_epoch_retention` epochs are retained per group. Older
+    /// epochs are not returned.
+    #[cfg(feature = "prior_epoch")]
+    pub fn epochs(&self, group_id: &[u8]) -> Result<impl Iterator<Item = RetainedEpoch>, MlsError> {
+        let epochs = self
+            .lock()
+            .get(group_id)
+            .map(|data| {
+                data.epoch_data
+                    .iter()
+                    .map(|e| Ok(PriorEpoch::mls_decode(&mut &*e.data)?.into()))
+                    .collect::<Result<Vec<_>, MlsError>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(epochs.into_iter())
+    }
+
     fn lock(&self) -> MutexGuard<'_, LargeMap<Vec<u8>, InMemoryGroupData>> {
         #[cfg(feature = "std")]
         return self.inner.lock().unwrap();
@@ -230,7 +288,13 @@ mod tests {
     use assert_matches::assert_matches;
 
     use super::{InMemoryGroupData, InMemoryGroupStateStorage};
-    use crate::{client::MlsError, group::test_utils::TEST_GROUP};
+    use crate::{
+        client::{
+            test_utils::{test_client_with_key_pkg, TEST_CIPHER_SUITE, TEST_PROTOCOL_VERSION},
+            MlsError,
+        },
+        group::test_utils::TEST_GROUP,
+    };
 
     use mls_rs_core::group::{EpochRecord, GroupState, GroupStateStorage};
 
@@ -343,4 +407,42 @@ mod tests {
         let expected = epoch_inserts.pop().unwrap();
         assert_eq!(stored.epoch_data[0], expected);
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn retained_epochs_are_iterated_in_order() {
+        let (client, _) =
+            test_client_with_key_pkg(TEST_PROTOCOL_VERSION, TEST_CIPHER_SUITE, "alice").await;
+
+        let mut group = client
+            .create_group(Default::default(), Default::default())
+            .await
+            .unwrap();
+
+        for _ in 0..5 {
+            group.commit(Vec::new()).await.unwrap();
+            group.apply_pending_commit().await.unwrap();
+        }
+
+        group.write_to_storage().await.unwrap();
+
+        let storage = client.group_state_storage();
+        let epochs = storage
+            .epochs(group.group_id())
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        let epoch_ids = epochs.iter().map(|e| e.epoch_id()).collect::<Vec<_>>();
+        assert_eq!(epoch_ids, vec![2, 3, 4]);
+
+        assert!(epochs
+            .iter()
+            .all(|e| e.group_context.group_id == group.group_id()
+                && e.self_index == group.current_member_index()));
+    }
+
+    #[test]
+    fn no_epochs_for_unknown_group() {
+        let storage = InMemoryGroupStateStorage::new();
+        assert_eq!(storage.epochs(TEST_GROUP).unwrap().count(), 0);
+    }
 }
@@ -0,0 +1,217 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// Copyright by contributors to this project.
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(not(target_has_atomic = "ptr"))]
+use portable_atomic::{AtomicUsize, Ordering};
+#[cfg(not(target_has_atomic = "ptr"))]
+use portable_atomic_util::Arc;
+
+use mls_rs_core::crypto::{
+    CipherSuite, CipherSuiteProvider, HpkeCiphertext, HpkePublicKey, HpkeSecretKey,
+    SignaturePublicKey, SignatureSecretKey,
+};
+use zeroize::Zeroizing;
+
+/// [`CipherSuiteProvider`] that produces a predictable sequence of "random" bytes.
+///
+/// Every byte returned by [`random_bytes`](CipherSuiteProvider::random_bytes) is the
+/// value of a counter, starting at 0 and wrapping around after 255, which is incremented
+/// for each byte. Clones share the counter. All other operations, including key
+/// generation, are delegated to the wrapped provider.
+///
+/// This makes values such as PSK nonces reproducible in tests. It must never be used
+/// outside of tests.
+#[derive(Clone, Debug)]
+pub struct DeterministicCipherSuiteProvider<P> {
+    inner: P,
+    counter: Arc<AtomicUsize>,
+}
+
+impl<P> DeterministicCipherSuiteProvider<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            counter: Default::default(),
+        }
+    }
+}
+
+#[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+#[cfg_attr(all(target_arch = "wasm32", mls_build_async), maybe_async::must_be_async(?Send))]
+#[cfg_attr(
+    all(not(target_arch = "wasm32"), mls_build_async),
+    maybe_async::must_be_async
+)]
+impl<P: CipherSuiteProvider> CipherSuiteProvider for DeterministicCipherSuiteProvider<P> {
+    type Error = P::Error;
+    type HpkeContextS = P::HpkeContextS;
+    type HpkeContextR = P::HpkeContextR;
+
+    fn cipher_suite(&self) -> CipherSuite {
+        self.inner.cipher_suite()
+    }
+
+    #[cfg(feature = "custom_label_prefix")]
+    fn label_prefix(&self) -> &str {
+        self.inner.label_prefix()
+    }
+
+    async fn hash(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.inner.hash(data).await
+    }
+
+    async fn mac(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.inner.mac(key, data).await
+    }
+
+    async fn aead_seal(
+        &self,
+        key: &[u8],
+        data: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.inner.aead_seal(key, data, aad, nonce).await
+    }
+
+    async fn aead_open(
+        &self,
+        key: &[u8],
+        ciphertext: &[u8],
+        aad: Option<&[u8]>,
+        nonce: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.inner.aead_open(key, ciphertext, aad, nonce).await
+    }
+
+    fn aead_key_size(&self) -> usize {
+        self.inner.aead_key_size()
+    }
+
+    fn aead_nonce_size(&self) -> usize {
+        self.inner.aead_nonce_size()
+    }
+
+    async fn kdf_extract(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.inner.kdf_extract(salt, ikm).await
+    }
+
+    async fn kdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>, Self::Error> {
+        self.inner.kdf_expand(prk, info, len).await
+    }
+
+    fn kdf_extract_size(&self) -> usize {
+        self.inner.kdf_extract_size()
+    }
+
+    async fn hpke_seal(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+        pt: &[u8],
+    ) -> Result<HpkeCiphertext, Self::Error> {
+        self.inner.hpke_seal(remote_key, info, aad, pt).await
+    }
+
+    async fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+        aad: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.inner
+            .hpke_open(ciphertext, local_secret, local_public, info, aad)
+            .await
+    }
+
+    async fn hpke_setup_s(
+        &self,
+        remote_key: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<(Vec<u8>, Self::HpkeContextS), Self::Error> {
+        self.inner.hpke_setup_s(remote_key, info).await
+    }
+
+    async fn hpke_setup_r(
+        &self,
+        kem_output: &[u8],
+        local_secret: &HpkeSecretKey,
+        local_public: &HpkePublicKey,
+        info: &[u8],
+    ) -> Result<Self::HpkeContextR, Self::Error> {
+        self.inner
+            .hpke_setup_r(kem_output, local_secret, local_public, info)
+            .await
+    }
+
+    async fn kem_derive(&self, ikm: &[u8]) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+        self.inner.kem_derive(ikm).await
+    }
+
+    async fn kem_generate(&self) -> Result<(HpkeSecretKey, HpkePublicKey), Self::Error> {
+        self.inner.kem_generate().await
+    }
+
+    fn kem_public_key_validate(&self, key: &HpkePublicKey) -> Result<(), Self::Error> {
+        self.inner.kem_public_key_validate(key)
+    }
+
+    fn random_bytes(&self, out: &mut [u8]) -> Result<(), Self::Error> {
+        let start = self.counter.fetch_add(out.len(), Ordering::Relaxed);
+
+        out.iter_mut()
+            .enumerate()
+            .for_each(|(i, byte)| *byte = start.wrapping_add(i) as u8);
+
+        Ok(())
+    }
+
+    async fn signature_key_generate(
+        &self,
+    ) -> Result<(SignatureSecretKey, SignaturePublicKey), Self::Error> {
+        self.inner.signature_key_generate().await
+    }
+
+    async fn signature_key_derive_public(
+        &self,
+        secret_key: &SignatureSecretKey,
+    ) -> Result<SignaturePublicKey, Self::Error> {
+        self.inner.signature_key_derive_public(secret_key).await
+    }
+
+    async fn sign(
+        &self,
+        secret_key: &SignatureSecretKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.inner.sign(secret_key, data).await
+    }
+
+    async fn verify(
+        &self,
+        public_key: &SignaturePublicKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.inner.verify(public_key, signature, data).await
+    }
+}
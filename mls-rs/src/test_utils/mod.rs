@@ -8,6 +8,9 @@ pub mod benchmarks;
 #[cfg(all(feature = "fuzz_util", not(mls_build_async)))]
 pub mod fuzz_tests;
 
+pub mod deterministic;
+
+use mls_rs_codec::{MlsDecode, MlsEncode, MlsSize};
 use mls_rs_core::{
     crypto::{CipherSuite, CipherSuiteProvider, CryptoProvider},
     identity::{BasicCredential, Credential, SigningIdentity},
@@ -28,6 +31,28 @@ use crate::group::{mls_rules::EncryptionOptions, padding::PaddingMode};
 
 use alloc::{vec, vec::Vec};
 
+/// Decode `data` as an [`MlsMessage`] and, if that succeeds, check that encoding the
+/// message reproduces the consumed bytes exactly and decodes back to the same message.
+///
+/// Panics if the round trip is not idempotent. Inputs that fail to decode are ignored.
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn check_message_round_trip(data: &[u8]) {
+    let mut reader = data;
+
+    let Ok(message) = MlsMessage::mls_decode(&mut reader) else {
+        return;
+    };
+
+    let consumed = &data[..data.len() - reader.len()];
+    let encoded = message.mls_encode_to_vec().unwrap();
+
+    assert_eq!(encoded, consumed);
+    assert_eq!(message.mls_encoded_len(), encoded.len());
+
+    let decoded = MlsMessage::mls_decode(&mut &*encoded).unwrap();
+    assert_eq!(decoded, message);
+}
+
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub fn get_test_basic_credential(identity: Vec<u8>) -> Credential {
     BasicCredential::new(identity).into_credential()
@@ -12,6 +12,7 @@ use mls_rs_core::{
 use zeroize::Zeroizing;
 
 use crate::client::MlsError;
+use crate::label::mls_label;
 
 #[derive(Clone, MlsSize, MlsEncode)]
 struct EncryptContext<'a> {
@@ -31,9 +32,13 @@ impl Debug for EncryptContext<'_> {
 }
 
 impl<'a> EncryptContext<'a> {
-    pub fn new(label: &str, context: &'a [u8]) -> Self {
+    pub fn new<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        label: &str,
+        context: &'a [u8],
+    ) -> Self {
         Self {
-            label: [b"MLS 1.0 ", label.as_bytes()].concat(),
+            label: mls_label(cipher_suite_provider, label.as_bytes()),
             context,
         }
     }
@@ -55,14 +60,25 @@ pub(crate) trait HpkeEncryptable: Sized {
         public_key: &HpkePublicKey,
         context: &[u8],
     ) -> Result<HpkeCiphertext, MlsError> {
-        let context = EncryptContext::new(Self::ENCRYPT_LABEL, context)
+        self.encrypt_with_aad(cipher_suite_provider, public_key, context, None)
+            .await
+    }
+
+    async fn encrypt_with_aad<P: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &P,
+        public_key: &HpkePublicKey,
+        context: &[u8],
+        aad: Option<&[u8]>,
+    ) -> Result<HpkeCiphertext, MlsError> {
+        let context = EncryptContext::new(cipher_suite_provider, Self::ENCRYPT_LABEL, context)
             .mls_encode_to_vec()
             .map(Zeroizing::new)?;
 
         let content = self.get_bytes().map(Zeroizing::new)?;
 
         cipher_suite_provider
-            .hpke_seal(public_key, &context, None, &content)
+            .hpke_seal(public_key, &context, aad, &content)
             .await
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))
     }
@@ -74,10 +90,30 @@ pub(crate) trait HpkeEncryptable: Sized {
         context: &[u8],
         ciphertext: &HpkeCiphertext,
     ) -> Result<Self, MlsError> {
-        let context = EncryptContext::new(Self::ENCRYPT_LABEL, context).mls_encode_to_vec()?;
+        Self::decrypt_with_aad(
+            cipher_suite_provider,
+            secret_key,
+            public_key,
+            context,
+            ciphertext,
+            None,
+        )
+        .await
+    }
+
+    async fn decrypt_with_aad<P: CipherSuiteProvider>(
+        cipher_suite_provider: &P,
+        secret_key: &HpkeSecretKey,
+        public_key: &HpkePublicKey,
+        context: &[u8],
+        ciphertext: &HpkeCiphertext,
+        aad: Option<&[u8]>,
+    ) -> Result<Self, MlsError> {
+        let context = EncryptContext::new(cipher_suite_provider, Self::ENCRYPT_LABEL, context)
+            .mls_encode_to_vec()?;
 
         let plaintext = cipher_suite_provider
-            .hpke_open(ciphertext, secret_key, public_key, &context, None)
+            .hpke_open(ciphertext, secret_key, public_key, &context, aad)
             .await
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
@@ -420,11 +420,13 @@ mod tests {
         group::test_utils::{get_test_group_context, random_bytes},
         identity::basic::BasicIdentityProvider,
         tree_kem::{
+            hpke_encryption::HpkeEncryptable,
             leaf_node::{
                 test_utils::{get_basic_test_node_sig_key, get_test_capabilities},
                 ConfigProperties,
             },
             node::LeafIndex,
+            path_secret::PathSecret,
             Capabilities, TreeKemPrivate, TreeKemPublic, UpdatePath, ValidatedUpdatePath,
         },
         ExtensionList,
@@ -660,6 +662,96 @@ mod tests {
         }
     }
 
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn encrypted_path_secrets_follow_resolution_order() {
+        let cipher_suite_provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let mut leaf_nodes = Vec::new();
+        let mut leaf_secrets = Vec::new();
+
+        for index in 1..10 {
+            let (leaf_node, hpke_secret, _) =
+                get_basic_test_node_sig_key(TEST_CIPHER_SUITE, &format!("{index}")).await;
+
+            leaf_nodes.push(leaf_node);
+            leaf_secrets.push(hpke_secret);
+        }
+
+        let (encap_node, encap_hpke_secret, encap_signer) =
+            get_basic_test_node_sig_key(TEST_CIPHER_SUITE, "encap").await;
+
+        let (mut test_tree, mut encap_private_key) = TreeKemPublic::derive(
+            encap_node,
+            encap_hpke_secret,
+            &BasicIdentityProvider,
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+        // All parent nodes are blank, so most copath nodes resolve to several leaves
+        test_tree
+            .add_leaves(leaf_nodes, &BasicIdentityProvider, &cipher_suite_provider)
+            .await
+            .unwrap();
+
+        let mut encap_tree = test_tree.clone();
+        let mut context = get_test_group_context(42, TEST_CIPHER_SUITE).await;
+
+        let encap_gen = TreeKem::new(&mut encap_tree, &mut encap_private_key)
+            .encap(
+                &mut context,
+                &[],
+                &encap_signer,
+                None,
+                None,
+                &cipher_suite_provider,
+                #[cfg(test)]
+                &Default::default(),
+            )
+            .await
+            .unwrap();
+
+        let context_bytes = context.mls_encode_to_vec().unwrap();
+
+        let path_secrets = encap_gen
+            .path_secrets
+            .iter()
+            .zip(test_tree.nodes.direct_copath(LeafIndex(0)))
+            .filter_map(|(secret, node)| secret.as_ref().map(|secret| (secret, node.copath)));
+
+        for ((path_secret, copath_index), update) in path_secrets.zip(&encap_gen.update_path.nodes)
+        {
+            let resolution = test_tree.nodes.get_resolution_index(copath_index).unwrap();
+            assert_eq!(resolution.len(), update.encrypted_path_secret.len());
+
+            // The i-th ciphertext must be encrypted to the i-th node of the resolution
+            for (node_index, ciphertext) in
+                resolution.into_iter().zip(&update.encrypted_path_secret)
+            {
+                let public_key = test_tree
+                    .nodes
+                    .borrow_node(node_index)
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .public_key();
+
+                let decrypted = PathSecret::decrypt(
+                    &cipher_suite_provider,
+                    &leaf_secrets[node_index as usize / 2 - 1],
+                    public_key,
+                    &context_bytes,
+                    ciphertext,
+                )
+                .await
+                .unwrap();
+
+                assert_eq!(&decrypted, path_secret);
+            }
+        }
+    }
+
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_encap_decap() {
         for cipher_suite in TestCryptoProvider::all_supported_cipher_suites() {
@@ -5,6 +5,7 @@
 use super::{parent_hash::ParentHash, Capabilities, Lifetime};
 use crate::client::MlsError;
 use crate::crypto::{CipherSuiteProvider, HpkePublicKey, HpkeSecretKey, SignatureSecretKey};
+use crate::extension::ApplicationIdExt;
 use crate::{identity::SigningIdentity, signer::Signable, ExtensionList};
 use alloc::vec::Vec;
 use core::fmt::{self, Debug};
@@ -70,11 +71,36 @@ impl LeafNode {
     where
         CSP: CipherSuiteProvider,
     {
-        let (secret_key, public_key) = cipher_suite_provider
+        let key_pair = cipher_suite_provider
             .kem_generate()
             .await
             .map_err(|e| MlsError::CryptoProviderError(e.into_any_error()))?;
 
+        Self::generate_with_key_pair(
+            cipher_suite_provider,
+            properties,
+            signing_identity,
+            signer,
+            lifetime,
+            key_pair,
+            true,
+        )
+        .await
+    }
+
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn generate_with_key_pair<CSP>(
+        cipher_suite_provider: &CSP,
+        properties: ConfigProperties,
+        signing_identity: SigningIdentity,
+        signer: &SignatureSecretKey,
+        lifetime: Lifetime,
+        (secret_key, public_key): (HpkeSecretKey, HpkePublicKey),
+        grease: bool,
+    ) -> Result<(Self, HpkeSecretKey), MlsError>
+    where
+        CSP: CipherSuiteProvider,
+    {
         let mut leaf_node = LeafNode {
             public_key,
             signing_identity,
@@ -84,7 +110,9 @@ impl LeafNode {
             signature: Default::default(),
         };
 
-        leaf_node.grease(cipher_suite_provider)?;
+        if grease {
+            leaf_node.grease(cipher_suite_provider)?;
+        }
 
         leaf_node
             .sign(
@@ -173,6 +201,13 @@ impl LeafNode {
 
         Ok(secret)
     }
+
+    /// The application level identifier stored in this leaf node's extensions, if any.
+    ///
+    /// The identifier is opaque to MLS and is not interpreted during validation.
+    pub fn application_id(&self) -> Result<Option<ApplicationIdExt>, MlsError> {
+        self.extensions.get_as().map_err(Into::into)
+    }
 }
 
 #[derive(Debug)]
@@ -71,6 +71,8 @@ impl<'a, C: IdentityProvider, CP: CipherSuiteProvider> LeafNodeValidator<'a, C,
             ValidationContext::Add(time) => {
                 // If the context is add, and we specified a time to check for lifetime, verify it
                 if let LeafNodeSource::KeyPackage(lifetime) = &leaf_node.leaf_node_source {
+                    lifetime.check_range()?;
+
                     if let Some(current_time) = time {
                         if !lifetime.within_lifetime(*current_time) {
                             return Err(MlsError::InvalidLifetime);
@@ -621,6 +623,32 @@ mod tests {
 
         assert_matches!(res, Err(MlsError::InvalidLifetime));
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn inverted_lifetime_is_rejected_without_time() {
+        let (signing_identity, secret) = get_test_signing_identity(TEST_CIPHER_SUITE, b"foo").await;
+
+        let (leaf_node, _) = get_test_node_with_lifetime(
+            TEST_CIPHER_SUITE,
+            signing_identity,
+            &secret,
+            get_test_capabilities(),
+            ExtensionList::new(),
+            crate::tree_kem::Lifetime::new(10, 5),
+        )
+        .await;
+
+        let cipher_suite_provider = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+
+        let test_validator =
+            LeafNodeValidator::new(&cipher_suite_provider, &BasicIdentityProvider, None);
+
+        let res = test_validator
+            .check_if_valid(&leaf_node, ValidationContext::Add(None))
+            .await;
+
+        assert_matches!(res, Err(MlsError::InvalidLifetimeRange));
+    }
 }
 
 #[cfg(test)]
@@ -22,6 +22,21 @@ impl Lifetime {
         }
     }
 
+    /// Create a lifetime that starts at `not_before` and ends at `not_after`, both
+    /// inclusive.
+    ///
+    /// Fails with [`MlsError::InvalidLifetimeRange`] if `not_before` is after `not_after`.
+    pub fn from_dates(not_before: MlsTime, not_after: MlsTime) -> Result<Self, MlsError> {
+        let lifetime = Lifetime::new(
+            not_before.seconds_since_epoch(),
+            not_after.seconds_since_epoch(),
+        );
+
+        lifetime.check_range()?;
+
+        Ok(lifetime)
+    }
+
     pub fn seconds(s: u64) -> Result<Self, MlsError> {
         #[cfg(feature = "std")]
         let not_before = MlsTime::now().seconds_since_epoch();
@@ -46,6 +61,14 @@ impl Lifetime {
         Self::days(365 * y as u32)
     }
 
+    /// An inverted lifetime is malformed rather than merely expired, so it is reported
+    /// separately from [`Lifetime::within_lifetime`].
+    pub(crate) fn check_range(&self) -> Result<(), MlsError> {
+        (self.not_before <= self.not_after)
+            .then_some(())
+            .ok_or(MlsError::InvalidLifetimeRange)
+    }
+
     pub(crate) fn within_lifetime(&self, time: MlsTime) -> bool {
         let since_epoch = time.seconds_since_epoch();
         since_epoch >= self.not_before && since_epoch <= self.not_after
@@ -94,6 +117,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lifetime_from_dates() {
+        let not_before = MlsTime::from_duration_since_epoch(Duration::from_secs(5));
+        let not_after = MlsTime::from_duration_since_epoch(Duration::from_secs(10));
+
+        assert_eq!(
+            Lifetime::from_dates(not_before, not_after).unwrap(),
+            Lifetime::new(5, 10)
+        );
+
+        assert_eq!(
+            Lifetime::from_dates(not_before, not_before).unwrap(),
+            Lifetime::new(5, 5)
+        );
+
+        assert_matches!(
+            Lifetime::from_dates(not_after, not_before),
+            Err(MlsError::InvalidLifetimeRange)
+        );
+    }
+
     #[test]
     fn test_bounds() {
         let test_lifetime = Lifetime {
@@ -182,7 +182,6 @@ impl TreeKemPublic {
         self.nodes.total_leaf_count()
     }
 
-    #[cfg(any(test, all(feature = "custom_proposal", feature = "tree_index")))]
     pub fn occupied_leaf_count(&self) -> u32 {
         self.nodes.occupied_leaf_count()
     }
@@ -842,6 +841,7 @@ pub(crate) mod test_utils {
                 .unwrap();
 
             self.tree.nodes.blank_leaf_node(LeafIndex(member)).unwrap();
+            self.tree.tree_hashes.current = vec![];
 
             *self
                 .signers
@@ -175,7 +175,6 @@ impl DerefMut for NodeVec {
 }
 
 impl NodeVec {
-    #[cfg(any(test, all(feature = "custom_proposal", feature = "tree_index")))]
     pub fn occupied_leaf_count(&self) -> u32 {
         self.non_empty_leaves().count() as u32
     }
@@ -299,7 +298,11 @@ impl NodeVec {
     }
 
     pub fn borrow_as_leaf(&self, index: LeafIndex) -> Result<&LeafNode, MlsError> {
-        let node_index = NodeIndex::from(index);
+        let node_index = index
+            .0
+            .checked_mul(2)
+            .ok_or(MlsError::LeafNotFound(*index))?;
+
         self.borrow_node(node_index).and_then(|n| n.as_leaf())
     }
 
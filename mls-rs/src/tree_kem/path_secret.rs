@@ -27,7 +27,7 @@ pub struct PathSecret(
 
 impl Debug for PathSecret {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        mls_rs_core::debug::pretty_bytes(&self.0)
+        mls_rs_core::debug::pretty_secret(&self.0)
             .named("PathSecret")
             .fmt(f)
     }
@@ -261,6 +261,35 @@ impl TreeKemPublic {
     }
 }
 
+impl NodeVec {
+    // Compute the tree hash from scratch, without caching intermediate hashes.
+    #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
+    pub(crate) async fn tree_hash<P: CipherSuiteProvider>(
+        &self,
+        cipher_suite_provider: &P,
+    ) -> Result<Vec<u8>, MlsError> {
+        if self.is_empty() {
+            return Err(MlsError::UnexpectedEmptyTree);
+        }
+
+        let num_leaves = self.total_leaf_count();
+
+        let mut hashes = vec![];
+
+        tree_hash(
+            &mut hashes,
+            self,
+            None,
+            &[],
+            num_leaves,
+            cipher_suite_provider,
+        )
+        .await?;
+
+        Ok(hashes[num_leaves.root() as usize].to_vec())
+    }
+}
+
 #[cfg_attr(not(mls_build_async), maybe_async::must_be_sync)]
 async fn tree_hash<P: CipherSuiteProvider>(
     hashes: &mut Vec<TreeHash>,
@@ -361,9 +390,13 @@ async fn hash_for_parent<P: CipherSuiteProvider>(
 mod tests {
     use mls_rs_codec::MlsDecode;
 
+    use assert_matches::assert_matches;
+
     use crate::{
         cipher_suite::CipherSuite,
+        client::test_utils::TEST_CIPHER_SUITE,
         crypto::test_utils::{test_cipher_suite_provider, try_test_cipher_suite_provider},
+        group::ExportedTree,
         identity::basic::BasicIdentityProvider,
         tree_kem::{node::NodeVec, parent_hash::test_utils::get_test_tree_fig_12},
     };
@@ -432,6 +465,47 @@ mod tests {
             let calculated_hash = tree.tree_hash(&cs_provider).await.unwrap();
 
             assert_eq!(calculated_hash, one_case.tree_hash);
+
+            let exported_hash = ExportedTree::from_bytes(&one_case.tree_data)
+                .unwrap()
+                .tree_hash(&cs_provider)
+                .await
+                .unwrap();
+
+            assert_eq!(exported_hash, one_case.tree_hash);
         }
     }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn tree_hash_changes_when_node_changes() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let tree = get_test_tree_fig_12(TEST_CIPHER_SUITE).await;
+        let original_hash = tree.nodes.tree_hash(&cs).await.unwrap();
+
+        let mut nodes = tree.nodes.clone();
+        nodes.borrow_as_leaf_mut(LeafIndex(2)).unwrap().signature[0] ^= 1;
+
+        let mutated_hash = nodes.tree_hash(&cs).await.unwrap();
+        assert_ne!(mutated_hash, original_hash);
+
+        let mut nodes = tree.nodes.clone();
+
+        let parent = (1..nodes.len() as u32)
+            .step_by(2)
+            .find(|&i| !nodes.is_blank(i).unwrap())
+            .unwrap();
+
+        nodes.borrow_as_parent_mut(parent).unwrap().unmerged_leaves = vec![LeafIndex(0)];
+
+        let mutated_hash = nodes.tree_hash(&cs).await.unwrap();
+        assert_ne!(mutated_hash, original_hash);
+    }
+
+    #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
+    async fn tree_hash_of_empty_tree_fails() {
+        let cs = test_cipher_suite_provider(TEST_CIPHER_SUITE);
+        let res = NodeVec::default().tree_hash(&cs).await;
+
+        assert_matches!(res, Err(MlsError::UnexpectedEmptyTree));
+    }
 }
@@ -98,6 +98,10 @@ impl<'a, C: IdentityProvider, CSP: CipherSuiteProvider> TreeValidator<'a, C, CSP
                 self.leaf_node_validator
                     .revalidate(leaf_node, self.group_id, *index)
                     .await
+                    .map_err(|e| match e {
+                        MlsError::InvalidSignature => MlsError::InvalidLeafNodeSignature(*index),
+                        e => e,
+                    })
             })
             .await
     }
@@ -288,24 +292,26 @@ mod tests {
     #[maybe_async::test(not(mls_build_async), async(mls_build_async, crate::futures_test))]
     async fn test_key_package_validation_failure() {
         for cipher_suite in TestCryptoProvider::all_supported_cipher_suites() {
-            let mut test_tree = get_valid_tree(cipher_suite).await;
+            for index in [0, 2] {
+                let mut test_tree = get_valid_tree(cipher_suite).await;
 
-            test_tree
-                .nodes
-                .borrow_as_leaf_mut(LeafIndex(0))
-                .unwrap()
-                .signature = random_bytes(32);
+                test_tree
+                    .nodes
+                    .borrow_as_leaf_mut(LeafIndex(index))
+                    .unwrap()
+                    .signature = random_bytes(32);
 
-            let cipher_suite_provider = test_cipher_suite_provider(cipher_suite);
-            let mut context = get_test_group_context(1, cipher_suite).await;
-            context.tree_hash = test_tree.tree_hash(&cipher_suite_provider).await.unwrap();
+                let cipher_suite_provider = test_cipher_suite_provider(cipher_suite);
+                let mut context = get_test_group_context(1, cipher_suite).await;
+                context.tree_hash = test_tree.tree_hash(&cipher_suite_provider).await.unwrap();
 
-            let validator =
-                TreeValidator::new(&cipher_suite_provider, &context, &BasicIdentityProvider);
+                let validator =
+                    TreeValidator::new(&cipher_suite_provider, &context, &BasicIdentityProvider);
 
-            let res = validator.validate(&mut test_tree).await;
+                let res = validator.validate(&mut test_tree).await;
 
-            assert_matches!(res, Err(MlsError::InvalidSignature));
+                assert_matches!(res, Err(MlsError::InvalidLeafNodeSignature(i)) if i == index);
+            }
         }
     }
 
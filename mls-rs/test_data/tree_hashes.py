@@ -0,0 +1,194 @@
+#!/usr/bin/env python3
+# Copyright by contributors to this project.
+# SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+"""Tree hashes of serialized ratchet trees, computed independently of mls-rs.
+
+Implements the tree hash of RFC 9420 section 7.8 for cipher suite 1 (SHA-256),
+so the hashes in the tree operations vectors are not produced by the code they
+test.
+
+Usage:
+    tree_hashes.py validate interop_tree_validation.json
+        Check this script against the tree hashes of the RFC tree validation
+        vectors.
+    tree_hashes.py check tree_modifications_interop.json
+        Check tree_hash_before and tree_hash_after of every case.
+    tree_hashes.py fill tree_modifications_interop.json
+        Write tree_hash_before and tree_hash_after into every case.
+"""
+
+import hashlib
+import json
+import sys
+
+
+class Reader:
+    def __init__(self, data):
+        self.data = data
+        self.pos = 0
+
+    def take(self, n):
+        value = self.data[self.pos:self.pos + n]
+        assert len(value) == n, "unexpected end of data"
+        self.pos += n
+        return value
+
+    def u8(self):
+        return self.take(1)[0]
+
+    def u16(self):
+        return int.from_bytes(self.take(2), "big")
+
+    def varint(self):
+        n = {0: 1, 1: 2, 2: 4}[self.data[self.pos] >> 6]
+        return int.from_bytes(self.take(n), "big") & ((1 << (8 * n - 2)) - 1)
+
+    def opaque(self):
+        return self.take(self.varint())
+
+
+def skip_leaf_node(r):
+    r.opaque()  # encryption_key
+    r.opaque()  # signature_key
+    r.u16()  # credential_type
+    r.opaque()  # basic identity, certificate chain or custom credential
+    for _ in range(5):
+        r.opaque()  # capabilities
+    source = r.u8()
+    if source == 1:
+        r.take(16)  # lifetime
+    elif source == 3:
+        r.opaque()  # parent_hash
+    r.opaque()  # extensions
+    r.opaque()  # signature
+
+
+def skip_parent_node(r):
+    r.opaque()  # encryption_key
+    r.opaque()  # parent_hash
+    r.opaque()  # unmerged_leaves
+
+
+def parse_tree(data):
+    """Split an encoded ratchet tree into optional (node_type, node) pairs."""
+    r = Reader(data)
+    body = Reader(r.opaque())
+    assert r.pos == len(data), "trailing data after the tree"
+
+    nodes = []
+
+    while body.pos < len(body.data):
+        if body.u8() == 0:
+            nodes.append(None)
+            continue
+
+        node_type = body.u8()
+        start = body.pos
+        skip_leaf_node(body) if node_type == 1 else skip_parent_node(body)
+        nodes.append((node_type, body.data[start:body.pos]))
+
+    return nodes
+
+
+def encode_opaque(value):
+    n = len(value)
+    if n < 1 << 6:
+        prefix = n.to_bytes(1, "big")
+    elif n < 1 << 14:
+        prefix = (n | 0x4000).to_bytes(2, "big")
+    else:
+        prefix = (n | 0x80000000).to_bytes(4, "big")
+    return prefix + value
+
+
+def tree_hashes(nodes):
+    """Tree hash of every node, indexed by node index."""
+    leaf_count = 1
+    while leaf_count < (len(nodes) + 1) // 2:
+        leaf_count *= 2
+
+    width = 2 * leaf_count - 1
+    nodes = nodes + [None] * (width - len(nodes))
+    hashes = [None] * width
+
+    def hash_node(x):
+        level = 0
+        while (x >> level) & 1:
+            level += 1
+
+        node = nodes[x]
+        optional = b"\x01" + node[1] if node else b"\x00"
+
+        if level == 0:
+            data = b"\x01" + (x // 2).to_bytes(4, "big") + optional
+        else:
+            left = hash_node(x ^ (1 << (level - 1)))
+            right = hash_node(x ^ (3 << (level - 1)))
+            data = b"\x02" + optional + encode_opaque(left) + encode_opaque(right)
+
+        hashes[x] = hashlib.sha256(data).digest()
+        return hashes[x]
+
+    hash_node(leaf_count - 1)
+    return hashes
+
+
+def tree_hash(tree_hex):
+    """Tree hash of the root of a hex encoded ratchet tree."""
+    hashes = tree_hashes(parse_tree(bytes.fromhex(tree_hex)))
+    return hashes[len(hashes) // 2].hex()
+
+
+def validate(cases):
+    for i, case in enumerate(cases):
+        if case["cipher_suite"] != 1:
+            continue
+        hashes = tree_hashes(parse_tree(bytes.fromhex(case["tree"])))
+        expected = case["tree_hashes"]
+        assert [h.hex() for h in hashes[:len(expected)]] == expected, f"case {i}"
+
+
+def check(cases):
+    for i, case in enumerate(cases):
+        for stage in ("before", "after"):
+            actual = tree_hash(case["tree_" + stage])
+            assert case.get("tree_hash_" + stage) == actual, f"{stage} of case {i}"
+
+
+def fill(cases):
+    filled = []
+
+    # Keep the field order of the RFC tree operations vectors
+    for case in cases:
+        filled.append({
+            "tree_before": case["tree_before"],
+            "proposal": case["proposal"],
+            "proposal_sender": case["proposal_sender"],
+            "tree_hash_before": tree_hash(case["tree_before"]),
+            "tree_after": case["tree_after"],
+            "tree_hash_after": tree_hash(case["tree_after"]),
+        })
+
+    return filled
+
+
+def main():
+    command, path = sys.argv[1], sys.argv[2]
+
+    with open(path) as f:
+        cases = json.load(f)
+
+    if command == "validate":
+        validate(cases)
+    elif command == "check":
+        check(cases)
+    elif command == "fill":
+        with open(path, "w") as f:
+            f.write(json.dumps(fill(cases), indent=2))
+    else:
+        sys.exit(__doc__)
+
+
+if __name__ == "__main__":
+    main()